@@ -0,0 +1,655 @@
+use proc_macro2::{Span, TokenTree};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Attribute, Expr, Ident, LitInt, LitStr, Path, Token, Type, Visibility};
+
+use crate::integration::Integration;
+
+/// What division by a zero-valued identifier results in.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum DivPolicy {
+    /// Division panics, as it does for the inner type.
+    #[default]
+    Panic,
+    /// Division returns `Option<Self>`, which is `None` when dividing by zero.
+    Checked,
+}
+
+/// How identifiers are represented by the generated serde impls.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum SerdeMode {
+    /// Always as the inner value.
+    #[default]
+    Inner,
+    /// As a string produced by `Display` and parsed by `FromStr` for human-readable formats,
+    /// and as the inner value otherwise.
+    HumanReadable,
+}
+
+/// Inlining attribute added to generated methods with `#[id(inline = "...")]`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Inline {
+    /// `#[inline(always)]`.
+    Always,
+    /// `#[inline(never)]`.
+    Never,
+    /// `#[inline]`, which leaves the decision to the compiler but allows inlining across crates.
+    Default,
+}
+
+impl Inline {
+    /// Values of the `inline` option.
+    const CHOICES: [(&'static str, Self); 3] = [
+        ("always", Self::Always),
+        ("never", Self::Never),
+        ("default", Self::Default),
+    ];
+}
+
+/// How `shard` maps identifiers to shards.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ShardHash {
+    /// The inner value modulo the number of shards.
+    Modulo,
+    /// The inner value multiplied by a 64-bit odd constant, scaled to the number of shards.
+    Multiplicative,
+}
+
+/// Pinned hash function of `from_hash`, producing the same hashes on every run and platform.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StableHash {
+    /// 64-bit FNV-1a, hashing one byte at a time.
+    Fnv1a,
+    /// The multiply-rotate mixing of `FxHash`, hashing eight bytes at a time.
+    Fx,
+}
+
+/// Cryptographic hash function of `from_bytes`, requested with `#[id(content_hash = "...")]`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ContentHash {
+    /// BLAKE3, which requires the `blake3` feature.
+    Blake3,
+}
+
+impl Parse for ContentHash {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: LitStr = input.parse()?;
+        match name.value().as_str() {
+            "blake3" if cfg!(feature = "blake3") => Ok(Self::Blake3),
+            "blake3" => Err(syn::Error::new(
+                name.span(),
+                "The `blake3` content hash requires the `blake3` feature of id-derive, \
+                 which is not enabled",
+            )),
+            other => Err(syn::Error::new(
+                name.span(),
+                format!("Unknown content hash `{other}`, expected `blake3`"),
+            )),
+        }
+    }
+}
+
+/// Check digit appended by `Display` and verified by `FromStr`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CheckDigit {
+    /// The Luhn algorithm, which catches any single mistyped digit and most swaps
+    /// of adjacent digits.
+    Luhn,
+}
+
+/// Output of an operator applied to two identifiers, if other than the identifier type.
+pub enum OpOutput {
+    /// The inner type.
+    Inner,
+    /// Another type constructed with `From` of the inner type.
+    Type(Box<Type>),
+}
+
+/// Format string of `#[id(display = "...")]`, with a single placeholder for the inner value.
+pub struct DisplayTemplate {
+    /// The format string as written, passed on to `write!`.
+    pub format: LitStr,
+    /// Text before the placeholder, with `{{` and `}}` unescaped.
+    pub prefix: String,
+    /// Text after the placeholder, with `{{` and `}}` unescaped.
+    pub suffix: String,
+    /// Format spec of the placeholder without the leading colon, e.g., `04`, or empty.
+    #[cfg_attr(not(feature = "itoa"), allow(dead_code))]
+    pub spec: String,
+}
+
+impl DisplayTemplate {
+    /// Returns the minimum width of the placeholder set by the format spec, or 0 if none.
+    #[cfg(feature = "itoa")]
+    pub fn width(&self) -> usize {
+        let mut spec = self.spec.as_str();
+        for align in ['<', '^', '>'] {
+            if let Some(index) = spec
+                .char_indices()
+                .take(2)
+                .find(|&(_, c)| c == align)
+                .map(|(index, _)| index)
+            {
+                spec = &spec[index + 1..];
+                break;
+            }
+        }
+        let spec = spec.trim_start_matches(['+', '-']);
+        let spec = spec.strip_prefix('#').unwrap_or(spec);
+        let spec = spec.strip_prefix('0').unwrap_or(spec);
+        let digits = spec
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(spec.len());
+        spec[..digits].parse().unwrap_or(0)
+    }
+}
+
+impl Parse for DisplayTemplate {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let format: LitStr = input.parse()?;
+        let error = |message: &str| syn::Error::new(format.span(), message);
+        let value = format.value();
+        let mut chars = value.chars().peekable();
+        let (mut prefix, mut suffix) = (String::new(), String::new());
+        let mut placeholders = 0;
+        let mut placeholder_spec = String::new();
+        while let Some(c) = chars.next() {
+            let text = if placeholders == 0 {
+                &mut prefix
+            } else {
+                &mut suffix
+            };
+            match c {
+                '{' | '}' if chars.peek() == Some(&c) => {
+                    chars.next();
+                    text.push(c);
+                }
+                '{' => {
+                    let spec: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    if !(spec.is_empty() || spec.starts_with(':')) {
+                        return Err(error(
+                            "The display placeholder cannot be named or positional, expected `{}`",
+                        ));
+                    }
+                    placeholder_spec = String::from(spec.trim_start_matches(':'));
+                    placeholders += 1;
+                }
+                '}' => {
+                    return Err(error(
+                        "Unmatched `}` in display format, use `}}` to escape it",
+                    ))
+                }
+                _ => text.push(c),
+            }
+        }
+        if placeholders != 1 {
+            return Err(error(
+                "Expected exactly one `{}` placeholder for the inner value in display format",
+            ));
+        }
+        Ok(Self {
+            format,
+            prefix,
+            suffix,
+            spec: placeholder_spec,
+        })
+    }
+}
+
+/// Option `#[id(validate(function, error = Error))]`, restricting the valid inner values.
+pub struct Validate {
+    /// Function taking a reference to the inner value and returning `Result<(), Error>`.
+    pub function: Path,
+    /// Error returned by `function`.
+    pub error: Type,
+}
+
+impl Parse for Validate {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let function = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let key: Ident = input.parse()?;
+        if key != "error" {
+            return Err(syn::Error::new(
+                key.span(),
+                "Expected `error = ...` after the validation function",
+            ));
+        }
+        let error = parse_value(input)?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { function, error })
+    }
+}
+
+/// Option `#[id(snowflake(epoch = ..., shift = ...))]`, describing an integer identifier
+/// whose high bits hold the creation time in milliseconds.
+pub struct Snowflake {
+    /// Milliseconds since the Unix epoch that the timestamp counts from, 0 by default.
+    pub epoch: u64,
+    /// Number of bits below the timestamp, 22 by default as in Twitter snowflakes.
+    pub shift: u32,
+}
+
+impl Parse for Snowflake {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut snowflake = Self {
+            epoch: 0,
+            shift: 22,
+        };
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let value: LitInt = parse_value(input)?;
+            match key.to_string().as_str() {
+                "epoch" => snowflake.epoch = value.base10_parse()?,
+                "shift" => {
+                    snowflake.shift = value.base10_parse()?;
+                    if snowflake.shift >= 64 {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "Snowflake shift must be less than 64",
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("Unknown snowflake option `{key}`, expected `epoch` or `shift`"),
+                    ))
+                }
+            }
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(snowflake)
+    }
+}
+
+/// Option `#[id(scramble(key = ...))]`, mapping identifiers to public values and back
+/// with a keyed permutation.
+pub struct Scramble {
+    /// Expression of type `u64` keying the permutation.
+    pub key: Expr,
+}
+
+impl Parse for Scramble {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        let key = parse_value(input)?;
+        input.parse::<Option<Token![,]>>()?;
+        if name != "key" {
+            return Err(syn::Error::new(
+                name.span(),
+                "Expected `key = ...` in `scramble`",
+            ));
+        }
+        Ok(Self { key })
+    }
+}
+
+/// Options of `#[id(parse(...))]`, making `FromStr` more lenient.
+#[derive(Default)]
+pub struct ParseOptions {
+    /// Whether to ignore leading and trailing whitespace.
+    pub trim: bool,
+    /// Whether to match the prefix regardless of ASCII case.
+    pub case_insensitive_prefix: bool,
+    /// Whether to accept a `+` sign before the inner value.
+    pub allow_plus: bool,
+}
+
+impl ParseOptions {
+    /// Enables the option named `option`.
+    fn set(&mut self, option: &Ident) -> syn::Result<()> {
+        match option.to_string().as_str() {
+            "trim" => self.trim = true,
+            "case_insensitive_prefix" => self.case_insensitive_prefix = true,
+            "allow_plus" => self.allow_plus = true,
+            _ => {
+                return Err(syn::Error::new(
+                    option.span(),
+                    format!("Unknown parse option `{option}`"),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Options passed through the `#[id(...)]` helper attribute.
+#[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Config {
+    /// Type of the difference between two identifiers.
+    pub delta: Option<Type>,
+    /// Visibility of generated inherent items and companion types.
+    pub vis: Option<Visibility>,
+    /// Identifier types that can be produced with a `to_<snake_case_name>` method.
+    pub cast_to: Vec<Path>,
+    /// Identifier types that can be converted from through the inner value.
+    pub from_id: Vec<Type>,
+    /// Output of the generated `Div` impls.
+    pub div: DivPolicy,
+    /// Span of the `div` value, pointed at by errors about the chosen policy.
+    pub div_span: Option<Span>,
+    /// Whether generated arithmetic `debug_assert!`s that it does not overflow.
+    pub debug_overflow_checks: bool,
+    /// Whether the `Id` derive leaves out operators taking another identifier.
+    pub no_id_arithmetic: bool,
+    /// Whether the `Id` derive implements all traits regardless of the inner type.
+    pub strict: bool,
+    /// Whether `From<Inner>` is replaced by crate-visible `from_raw` and `into_raw` methods.
+    pub opaque: bool,
+    /// Whether `#[repr(transparent)]` is required and `from_raw` and `into_raw` are `const`.
+    pub ffi: bool,
+    /// Outputs of the self-operations, keyed by options such as `mul_output`.
+    pub outputs: Vec<(Ident, OpOutput)>,
+    /// Representation used by the generated serde impls.
+    pub serde: SerdeMode,
+    /// Whether the `Serde` derive generates `serialize_packed` and `deserialize_packed`.
+    pub serde_packed: bool,
+    /// The `key` option, if given, making `Bytes` generate the order-preserving key encoding
+    /// and the `<Name>Key` companion type.
+    pub key: Option<Ident>,
+    /// Inlining attribute added to every generated method, if any.
+    pub inline: Option<Inline>,
+    /// Whether `FromStr` returns a `<Name>ParseError` instead of the error of the inner type.
+    pub parse_error: bool,
+    /// Prefix written by `Display` and required by `FromStr`.
+    pub prefix: Option<LitStr>,
+    /// Format string used by `Display` instead of the prefix.
+    pub display: Option<DisplayTemplate>,
+    /// Options relaxing what `FromStr` accepts.
+    pub parse: ParseOptions,
+    /// Check digit following the inner value in `Display` and `FromStr`.
+    pub check_digit: Option<CheckDigit>,
+    /// The `base32` option, if given, making `Display` and `FromStr` represent the inner value
+    /// in Crockford's Base32.
+    pub base32: Option<Ident>,
+    /// Additional derives applied to generated companion types.
+    pub companion_derive: Vec<Path>,
+    /// Module generated to hold companion types, which then drop the identifier name.
+    pub module: Option<Ident>,
+    /// Former name of the identifier type, kept as a deprecated alias.
+    pub renamed_from: Option<Ident>,
+    /// Third-party trait impls emitted by umbrella derives.
+    pub integrations: Vec<Integration>,
+    /// Validation of inner values converted or deserialized into identifiers.
+    pub validate: Option<Validate>,
+    /// Layout of an integer identifier holding its creation time.
+    pub snowflake: Option<Snowflake>,
+    /// Hashing of the `shard` method, if generated.
+    pub shard: Option<ShardHash>,
+    /// Type returned by the `shard` method instead of `u32`, constructed with `From<u32>`.
+    pub shard_output: Option<Type>,
+    /// Hash function of the `from_hash` constructor, if generated.
+    pub from_hash: Option<StableHash>,
+    /// Hash function of the `from_bytes` constructor, if generated.
+    pub content_hash: Option<ContentHash>,
+    /// Key of the `to_public` and `from_public` permutation, if generated.
+    pub scramble: Option<Scramble>,
+}
+
+impl Config {
+    pub fn from_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut config = Self::default();
+        let mut errors: Option<syn::Error> = None;
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("id")) {
+            let result = attr.parse_args_with(|input: ParseStream| {
+                config.parse_options(input, &mut errors);
+                Ok(())
+            });
+            if let Err(err) = result {
+                push_error(&mut errors, err);
+            }
+        }
+        if let (Some(_), Some((key, _))) = (&config.delta, config.output("sub")) {
+            push_error(
+                &mut errors,
+                syn::Error::new(
+                    key.span(),
+                    "`sub_output` cannot be combined with `delta`, which defines the output of `Sub`",
+                ),
+            );
+        }
+        if let (Some(prefix), Some(_)) = (&config.prefix, &config.display) {
+            push_error(
+                &mut errors,
+                syn::Error::new(
+                    prefix.span(),
+                    "`prefix` cannot be combined with `display`, which can include the prefix",
+                ),
+            );
+        }
+        if let (Some(_), Some(base32)) = (&config.check_digit, &config.base32) {
+            push_error(
+                &mut errors,
+                syn::Error::new(
+                    base32.span(),
+                    "`check_digit` cannot be combined with `base32`, which has no decimal digits",
+                ),
+            );
+        }
+        if let (Some(output), None) = (&config.shard_output, config.shard) {
+            push_error(
+                &mut errors,
+                syn::Error::new_spanned(output, "`shard_output` requires `shard`"),
+            );
+        }
+        match errors {
+            Some(err) => Err(err),
+            None => Ok(config),
+        }
+    }
+
+    /// Returns the output option of the operator with the given method name, e.g., `mul`.
+    pub fn output(&self, method: &str) -> Option<&(Ident, OpOutput)> {
+        self.outputs
+            .iter()
+            .rev()
+            .find(|(key, _)| key.to_string().strip_suffix("_output") == Some(method))
+    }
+
+    /// Returns the text written by `Display` before and after the inner value, and expected
+    /// by `FromStr`, if set with `prefix` or `display`.
+    pub fn affixes(&self) -> Option<(String, String)> {
+        match (&self.display, &self.prefix) {
+            (Some(display), _) => Some((display.prefix.clone(), display.suffix.clone())),
+            (None, Some(prefix)) => Some((prefix.value(), String::new())),
+            (None, None) => None,
+        }
+    }
+
+    /// Parses a comma-separated list of options.
+    /// An invalid option is recorded in `errors`, and parsing resumes with the next option.
+    fn parse_options(&mut self, input: ParseStream, errors: &mut Option<syn::Error>) {
+        while !input.is_empty() {
+            if let Err(err) = self.parse_option(input) {
+                push_error(errors, err);
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    let _ = input.parse::<TokenTree>();
+                }
+            }
+            if input.is_empty() {
+                break;
+            }
+            if let Err(err) = input.parse::<Token![,]>() {
+                push_error(errors, err);
+                return;
+            }
+        }
+    }
+
+    /// Parses the value of the `div` option, keeping its span for later errors.
+    fn parse_div(&mut self, input: ParseStream) -> syn::Result<()> {
+        let value = input.fork();
+        value.parse::<Token![=]>()?;
+        self.div_span = Some(value.span());
+        self.div = parse_choice(
+            input,
+            "div policy",
+            &[("panic", DivPolicy::Panic), ("checked", DivPolicy::Checked)],
+        )?;
+        Ok(())
+    }
+
+    fn parse_option(&mut self, input: ParseStream) -> syn::Result<()> {
+        let key = input.call(Ident::parse_any)?;
+        match key.to_string().as_str() {
+            "delta" => self.delta = Some(parse_value(input)?),
+            "vis" => self.vis = Some(parse_value::<LitStr>(input)?.parse()?),
+            "div" => self.parse_div(input)?,
+            "serde" => {
+                self.serde = parse_choice(
+                    input,
+                    "serde mode",
+                    &[
+                        ("inner", SerdeMode::Inner),
+                        ("human_readable", SerdeMode::HumanReadable),
+                    ],
+                )?;
+            }
+            "serde_packed" => self.serde_packed = true,
+            "key" => self.key = Some(key),
+            "inline" => self.inline = Some(parse_choice(input, "inline mode", &Inline::CHOICES)?),
+            "debug_overflow_checks" => self.debug_overflow_checks = true,
+            "no_id_arithmetic" => self.no_id_arithmetic = true,
+            "strict" => self.strict = true,
+            "opaque" => self.opaque = true,
+            "ffi" => self.ffi = true,
+            "parse_error" => self.parse_error = true,
+            "base32" => self.base32 = Some(key),
+            "prefix" => self.prefix = Some(parse_value(input)?),
+            "check_digit" => {
+                self.check_digit = Some(parse_choice(
+                    input,
+                    "check digit",
+                    &[("luhn", CheckDigit::Luhn)],
+                )?);
+            }
+            "display" => self.display = Some(parse_value(input)?),
+            "parse" => {
+                for option in parse_list::<Ident>(input)? {
+                    self.parse.set(&option)?;
+                }
+            }
+            "add_output" | "sub_output" | "mul_output" | "div_output" => {
+                let output = match parse_value(input)? {
+                    Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self") => {
+                        self.outputs.retain(|(other, _)| *other != key);
+                        return Ok(());
+                    }
+                    Type::Path(path) if path.qself.is_none() && path.path.is_ident("Inner") => {
+                        OpOutput::Inner
+                    }
+                    ty => OpOutput::Type(Box::new(ty)),
+                };
+                self.outputs.push((key, output));
+            }
+            "companion_derive" => self.companion_derive.extend(parse_list(input)?),
+            "module" => self.module = Some(parse_value(input)?),
+            "renamed_from" => self.renamed_from = Some(parse_value::<LitStr>(input)?.parse()?),
+            "validate" => self.validate = Some(parse_args(input)?),
+            "shard" => {
+                self.shard = Some(parse_optional_choice(
+                    input,
+                    "shard hashing",
+                    &[
+                        ("modulo", ShardHash::Modulo),
+                        ("multiplicative", ShardHash::Multiplicative),
+                    ],
+                )?);
+            }
+            "from_hash" => {
+                self.from_hash = Some(parse_optional_choice(
+                    input,
+                    "stable hash",
+                    &[("fnv1a", StableHash::Fnv1a), ("fx", StableHash::Fx)],
+                )?);
+            }
+            "content_hash" => self.content_hash = Some(parse_value(input)?),
+            "shard_output" => self.shard_output = Some(parse_value(input)?),
+            "scramble" => self.scramble = Some(parse_args(input)?),
+            "snowflake" => self.snowflake = Some(parse_args(input)?),
+            "integrations" => {
+                for integration in parse_list::<Ident>(input)? {
+                    self.integrations
+                        .push(Integration::from_ident(&integration)?);
+                }
+            }
+            "cast_to" => self.cast_to.extend(parse_list(input)?),
+            "from_id" => self.from_id.extend(parse_list(input)?),
+            _ => {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("Unknown id attribute option `{key}`"),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the value of a `key = value` option.
+fn parse_value<T: Parse>(input: ParseStream) -> syn::Result<T> {
+    input.parse::<Token![=]>()?;
+    input.parse()
+}
+
+/// Parses the arguments of a `key(...)` option.
+fn parse_args<T: Parse>(input: ParseStream) -> syn::Result<T> {
+    let content;
+    parenthesized!(content in input);
+    content.parse()
+}
+
+/// Parses the comma-separated values of a `key(value, ...)` option.
+fn parse_list<T: Parse>(input: ParseStream) -> syn::Result<Punctuated<T, Token![,]>> {
+    let content;
+    parenthesized!(content in input);
+    Punctuated::parse_terminated(&content)
+}
+
+/// Parses the value of a `key = "value"` option, which must be one of `choices`.
+fn parse_choice<T: Copy>(input: ParseStream, what: &str, choices: &[(&str, T)]) -> syn::Result<T> {
+    let value = parse_value::<LitStr>(input)?;
+    choices
+        .iter()
+        .find(|(choice, _)| *choice == value.value())
+        .map(|(_, choice)| *choice)
+        .ok_or_else(|| {
+            let expected: Vec<_> = choices
+                .iter()
+                .map(|(choice, _)| format!("`{choice}`"))
+                .collect();
+            syn::Error::new(
+                value.span(),
+                format!(
+                    "Unknown {what} `{}`, expected {}",
+                    value.value(),
+                    expected.join(" or ")
+                ),
+            )
+        })
+}
+
+/// Parses the optional value of a `key` or `key = "value"` option, which must be one of
+/// `choices`, the first being the default.
+fn parse_optional_choice<T: Copy>(
+    input: ParseStream,
+    what: &str,
+    choices: &[(&str, T)],
+) -> syn::Result<T> {
+    if input.peek(Token![=]) {
+        parse_choice(input, what, choices)
+    } else {
+        Ok(choices[0].1)
+    }
+}
+
+/// Adds `err` to the accumulated `errors`.
+pub fn push_error(errors: &mut Option<syn::Error>, err: syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(err),
+        None => *errors = Some(err),
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(feature = "serde")]
+use syn::Lifetime;
+use syn::{GenericArgument, Path, PathArguments, Type};
+
+/// Classification of the inner type, for operations whose implementation depends on it.
+pub enum InnerKind<'a> {
+    /// `Wrapping<T>` or `Saturating<T>`, whose arithmetic never overflows.
+    Wrapper {
+        /// Path of the wrapper type without generic arguments, used as its constructor.
+        wrapper: Path,
+        /// The wrapped integer type.
+        primitive: &'a Type,
+        /// Whether the wrapper is `Saturating` rather than `Wrapping`.
+        saturating: bool,
+    },
+    /// `f32` or `f64`, which have no bit representation to format, no total order,
+    /// and no checked arithmetic.
+    Float,
+    /// Any other type.
+    Other,
+}
+
+impl<'a> InnerKind<'a> {
+    /// Classifies `ty` by the name of its last path segment, as the type cannot be resolved
+    /// in a derive.
+    pub fn of(ty: &'a Type) -> Self {
+        match ty {
+            Type::Path(path) if path.path.is_ident("f32") || path.path.is_ident("f64") => {
+                Self::Float
+            }
+            _ => Self::wrapper(ty).unwrap_or(Self::Other),
+        }
+    }
+
+    fn wrapper(ty: &'a Type) -> Option<Self> {
+        let path = match ty {
+            Type::Path(ty) if ty.qself.is_none() => &ty.path,
+            _ => return None,
+        };
+        let segment = path.segments.last()?;
+        let saturating = match segment.ident.to_string().as_str() {
+            "Wrapping" => false,
+            "Saturating" => true,
+            _ => return None,
+        };
+        let primitive = match &segment.arguments {
+            PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+                GenericArgument::Type(primitive) => ungroup(primitive),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let mut wrapper = path.clone();
+        if let Some(segment) = wrapper.segments.last_mut() {
+            segment.arguments = PathArguments::None;
+        }
+        Some(Self::Wrapper {
+            wrapper,
+            primitive,
+            saturating,
+        })
+    }
+}
+
+/// Returns `ty` without the invisible groups that `macro_rules!` wraps around types passed
+/// as `$ty:ty` fragments, and without redundant parentheses, so that it can be classified
+/// by its path.
+pub fn ungroup(mut ty: &Type) -> &Type {
+    loop {
+        ty = match ty {
+            Type::Group(group) => &group.elem,
+            Type::Paren(paren) => &paren.elem,
+            _ => return ty,
+        }
+    }
+}
+
+/// Returns a description of `ty`, such as `string`, if it is a well-known type without
+/// arithmetic, so that numeric derives can point to `OpaqueId` instead of failing with trait
+/// bound errors in generated code. Other types are assumed to be numeric.
+pub fn non_numeric(ty: &Type) -> Option<&'static str> {
+    let path = match ty {
+        Type::Reference(reference) => {
+            return match ungroup(&reference.elem) {
+                Type::Path(path) if path.path.is_ident("str") => Some("string"),
+                _ => None,
+            }
+        }
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    match (segment.ident.to_string().as_str(), &segment.arguments) {
+        ("String", PathArguments::None) => Some("string"),
+        ("Cow" | "Box" | "Rc" | "Arc", PathArguments::AngleBracketed(args))
+            if args.args.iter().any(
+                |arg| matches!(arg, GenericArgument::Type(Type::Path(ty)) if ty.path.is_ident("str")),
+            ) =>
+        {
+            Some("string")
+        }
+        ("Uuid", PathArguments::None) => Some("UUID"),
+        ("Ulid", PathArguments::None) => Some("ULID"),
+        ("bool", PathArguments::None) => Some("boolean"),
+        ("char", PathArguments::None) => Some("character"),
+        _ => None,
+    }
+}
+
+/// A string type that can borrow from the deserialized input: `&'a str` or `Cow<'a, str>`.
+#[cfg(feature = "serde")]
+pub struct BorrowedStr<'a> {
+    /// Lifetime of the borrowed string.
+    pub lifetime: &'a Lifetime,
+    /// Whether the type is `Cow<'a, str>`, which falls back to an owned string.
+    pub cow: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> BorrowedStr<'a> {
+    /// Matches `ty` against `&'a str` and `Cow<'a, str>`, by the name of the last path segment.
+    pub fn of(ty: &'a Type) -> Option<Self> {
+        match ty {
+            Type::Reference(reference)
+                if reference.mutability.is_none() && is_str(&reference.elem) =>
+            {
+                Some(Self {
+                    lifetime: reference.lifetime.as_ref()?,
+                    cow: false,
+                })
+            }
+            Type::Path(path) if path.qself.is_none() => {
+                let segment = path.path.segments.last()?;
+                let args = match &segment.arguments {
+                    PathArguments::AngleBracketed(args) if segment.ident == "Cow" => &args.args,
+                    _ => return None,
+                };
+                match (args.first(), args.iter().nth(1), args.len()) {
+                    (
+                        Some(GenericArgument::Lifetime(lifetime)),
+                        Some(GenericArgument::Type(ty)),
+                        2,
+                    ) if is_str(ty) => Some(Self {
+                        lifetime,
+                        cow: true,
+                    }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn is_str(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("str"))
+}
+
+/// Layout of a time-ordered 128-bit identifier, whose most significant 48 bits hold
+/// the creation time in milliseconds since the Unix epoch.
+#[derive(Clone, Copy)]
+pub enum TimeOrdered {
+    /// `Uuid`, generated as version 7.
+    UuidV7,
+    /// `Ulid`.
+    Ulid,
+}
+
+impl TimeOrdered {
+    /// Matches `ty` against `Uuid` and `Ulid`, by the name of the last path segment.
+    pub fn of(ty: &Type) -> Option<Self> {
+        let segment = match ty {
+            Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+            _ => return None,
+        };
+        if !segment.arguments.is_empty() {
+            return None;
+        }
+        match segment.ident.to_string().as_str() {
+            "Uuid" => Some(Self::UuidV7),
+            "Ulid" => Some(Self::Ulid),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, FieldsUnnamed, Generics, Ident, Path, Token, Type,
+    VisRestricted, Visibility,
+};
+
+use crate::attr::{push_error, Config};
+use crate::inner::{non_numeric, ungroup, InnerKind};
+
+/// Derive input validated and parsed once, and shared by all generated operations.
+pub struct IdInput<'a> {
+    /// Name of the identifier type.
+    pub ident: &'a Ident,
+    /// Visibility of the identifier type.
+    pub vis: &'a Visibility,
+    /// Generics of the identifier type.
+    pub generics: &'a Generics,
+    /// Type of the only field.
+    pub inner_ty: &'a Type,
+    /// Classification of the inner type.
+    pub inner_kind: InnerKind<'a>,
+    /// Options passed with `#[id(...)]`.
+    pub config: Config,
+    /// Name of the derive being expanded, for error messages.
+    pub derive_name: &'static str,
+    /// Whether the type is `#[repr(transparent)]`, which makes it safe to cast references
+    /// to the inner type into references to the identifier.
+    pub transparent: bool,
+}
+
+impl<'a> IdInput<'a> {
+    /// Parses the input of `derive_name`, reporting both shape and attribute errors at once.
+    pub fn parse(derive_name: &'static str, input: &'a DeriveInput) -> syn::Result<Self> {
+        match (
+            single_field(derive_name, input),
+            Config::from_attributes(&input.attrs),
+        ) {
+            (Ok(field), Ok(config)) => Ok(Self {
+                ident: &input.ident,
+                vis: &input.vis,
+                generics: &input.generics,
+                inner_ty: ungroup(&field.ty),
+                inner_kind: InnerKind::of(ungroup(&field.ty)),
+                config,
+                derive_name,
+                transparent: repr_hints(&input.attrs).any(|hint| hint == "transparent"),
+            }),
+            (Err(mut err), Err(config_err)) => {
+                err.combine(config_err);
+                Err(err)
+            }
+            (Err(err), _) | (_, Err(err)) => Err(err),
+        }
+    }
+}
+
+impl IdInput<'_> {
+    /// Whether generated arithmetic should check for overflow in debug builds, which is
+    /// requested with `#[id(debug_overflow_checks)]` and pointless for wrapper and float
+    /// inner types.
+    pub fn overflow_checks(&self) -> bool {
+        self.config.debug_overflow_checks && matches!(self.inner_kind, InnerKind::Other)
+    }
+
+    /// Visibility of generated methods that create identifiers from unchecked inner values,
+    /// or give mutable access to them, given the visibility `vis` of other generated items:
+    /// `pub(crate)` for `#[id(opaque)]` and `#[id(validate(...))]` identifiers, so that only
+    /// the owning crate can bypass them, unless overridden with `#[id(vis = "...")]`.
+    pub fn unchecked_vis(&self, vis: &TokenStream) -> TokenStream {
+        if (self.config.opaque || self.config.validate.is_some()) && self.config.vis.is_none() {
+            quote!(pub(crate))
+        } else {
+            vis.clone()
+        }
+    }
+
+    /// Reports an error spanned on the inner type if it is a well-known type without
+    /// arithmetic, such as `String` or `Uuid`, for which the derive cannot work.
+    pub fn require_numeric(&self) -> syn::Result<()> {
+        match non_numeric(self.inner_ty) {
+            Some(kind) => Err(syn::Error::new_spanned(
+                self.inner_ty,
+                format!(
+                    "Cannot derive {} for a {kind} inner type, which has no arithmetic; \
+                     use OpaqueId for {kind}-backed identifiers",
+                    self.derive_name
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports an error spanned on the inner type unless it is a plain integer, for derives
+    /// whose generated methods use integer literals or methods of the primitive integers.
+    pub fn require_integer(&self) -> syn::Result<()> {
+        self.require_numeric()?;
+        let kind = match self.inner_kind {
+            InnerKind::Other => return Ok(()),
+            InnerKind::Float => "floating-point",
+            InnerKind::Wrapper { .. } => "wrapped",
+        };
+        Err(syn::Error::new_spanned(
+            self.inner_ty,
+            format!(
+                "Cannot derive {} for a {kind} inner type, which is not a plain integer",
+                self.derive_name
+            ),
+        ))
+    }
+
+    /// Reports an error spanned on the generic parameters, if any, for derives whose generated
+    /// items are tied to a single concrete type, with `reason` explaining why.
+    pub fn require_non_generic(&self, reason: &str) -> syn::Result<()> {
+        if self.generics.params.is_empty() {
+            Ok(())
+        } else {
+            Err(syn::Error::new_spanned(
+                self.generics,
+                format!(
+                    "Cannot derive {} for a generic type, {reason}",
+                    self.derive_name
+                ),
+            ))
+        }
+    }
+}
+
+/// Parts of the derive input shared by struct- and enum-backed identifiers, which determine
+/// the names and visibilities of generated items and companion types.
+pub trait IdItem {
+    /// Name of the identifier type.
+    fn ident(&self) -> &Ident;
+
+    /// Visibility of the identifier type.
+    fn vis(&self) -> &Visibility;
+
+    /// Options passed with `#[id(...)]`.
+    fn config(&self) -> &Config;
+
+    /// Visibility of generated inherent methods and constants: `pub` unless overridden
+    /// with `#[id(vis = "...")]`.
+    fn item_vis(&self) -> TokenStream {
+        self.config()
+            .vis
+            .as_ref()
+            .map_or_else(|| quote!(pub), |vis| quote!(#vis))
+    }
+
+    /// Visibility of generated companion types: same as the identifier type unless overridden
+    /// with `#[id(vis = "...")]`.
+    fn companion_vis(&self) -> &Visibility {
+        self.config().vis.as_ref().unwrap_or(self.vis())
+    }
+
+    /// Name of the companion type with the given prefix and suffix, e.g., `UserIdParseError`,
+    /// or just `ParseError` when placed in a module with `#[id(module = ...)]`.
+    fn companion_name(&self, prefix: &str, suffix: &str) -> Ident {
+        if self.config().module.is_some() {
+            format_ident!("{}{}", prefix, suffix)
+        } else {
+            format_ident!("{}{}{}", prefix, self.ident(), suffix)
+        }
+    }
+
+    /// Visibilities of inherent items and companion types generated by `items`, as seen from
+    /// where `items` end up, which is the module passed to `in_companion_module`, if any.
+    fn companion_item_vis(&self) -> (TokenStream, TokenStream) {
+        let companion_vis = self.companion_vis();
+        if self.config().module.is_none() {
+            return (self.item_vis(), quote!(#companion_vis));
+        }
+        let item_vis = self
+            .config()
+            .vis
+            .as_ref()
+            .map_or_else(|| quote!(pub), nested_vis);
+        (item_vis, nested_vis(companion_vis))
+    }
+
+    /// Places `items` in the module requested with `#[id(module = ...)]`, if any.
+    fn in_companion_module(&self, items: TokenStream) -> TokenStream {
+        let Some(module) = &self.config().module else {
+            return items;
+        };
+        let vis = self.companion_vis();
+        let doc = format!("Companion types of [`{}`].", self.ident());
+        quote! {
+            #[doc = #doc]
+            #vis mod #module {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #items
+            }
+        }
+    }
+}
+
+impl IdItem for IdInput<'_> {
+    fn ident(&self) -> &Ident {
+        self.ident
+    }
+
+    fn vis(&self) -> &Visibility {
+        self.vis
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+}
+
+/// Derive input of an enum-backed identifier: a fieldless enum with an integer `#[repr(...)]`,
+/// whose variants form a closed set of identifiers represented by their discriminants.
+pub struct EnumIdInput<'a> {
+    /// Name of the identifier type.
+    pub ident: &'a Ident,
+    /// Visibility of the identifier type.
+    pub vis: &'a Visibility,
+    /// Generics of the identifier type.
+    pub generics: &'a Generics,
+    /// Integer type of the discriminants, as given with `#[repr(...)]`.
+    pub repr: Ident,
+    /// Names of the variants, in declaration order.
+    pub variants: Vec<&'a Ident>,
+    /// Options passed with `#[id(...)]`.
+    pub config: Config,
+}
+
+impl<'a> EnumIdInput<'a> {
+    /// Parses the enum input of `derive_name`, reporting a missing `repr` and all variants
+    /// with fields at once.
+    pub fn parse(derive_name: &'static str, input: &'a DeriveInput) -> syn::Result<Self> {
+        let data = match &input.data {
+            Data::Enum(data) => data,
+            Data::Struct(data) => {
+                return Err(syn::Error::new(
+                    data.struct_token.span,
+                    format!("Cannot derive {derive_name} for struct, expected enum."),
+                ))
+            }
+            Data::Union(data) => {
+                return Err(syn::Error::new(
+                    data.union_token.span,
+                    format!("Cannot derive {derive_name} for union, expected enum."),
+                ))
+            }
+        };
+        let mut errors = None;
+        for variant in &data.variants {
+            if !matches!(variant.fields, Fields::Unit) {
+                push_error(
+                    &mut errors,
+                    syn::Error::new_spanned(
+                        &variant.fields,
+                        format!("Deriving {derive_name} for an enum requires unit variants"),
+                    ),
+                );
+            }
+        }
+        let repr = int_repr(&input.attrs).ok_or_else(|| {
+            syn::Error::new(
+                input.ident.span(),
+                format!(
+                    "Deriving {derive_name} for an enum requires an integer `#[repr(...)]`, \
+                     such as `#[repr(u8)]`"
+                ),
+            )
+        });
+        let config = Config::from_attributes(&input.attrs);
+        match (repr, config, errors) {
+            (Ok(repr), Ok(config), None) => Ok(Self {
+                ident: &input.ident,
+                vis: &input.vis,
+                generics: &input.generics,
+                repr,
+                variants: data.variants.iter().map(|variant| &variant.ident).collect(),
+                config,
+            }),
+            (repr, config, mut errors) => {
+                if let Err(err) = repr {
+                    push_error(&mut errors, err);
+                }
+                if let Err(err) = config {
+                    push_error(&mut errors, err);
+                }
+                Err(errors.expect("at least one error"))
+            }
+        }
+    }
+}
+
+impl IdItem for EnumIdInput<'_> {
+    fn ident(&self) -> &Ident {
+        self.ident
+    }
+
+    fn vis(&self) -> &Visibility {
+        self.vis
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+}
+
+/// Returns the integer type given with `#[repr(...)]`, if any, skipping other representation
+/// hints, such as `C`.
+fn int_repr(attrs: &[Attribute]) -> Option<Ident> {
+    const INTEGERS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    repr_hints(attrs).find(|hint| INTEGERS.iter().any(|int| hint == int))
+}
+
+/// Returns the representation hints given with `#[repr(...)]`, such as `C` or `transparent`.
+fn repr_hints(attrs: &[Attribute]) -> impl Iterator<Item = Ident> + '_ {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+}
+
+/// Returns `vis` as written in a child module, so that it grants access to the same scope.
+fn nested_vis(vis: &Visibility) -> TokenStream {
+    match vis {
+        Visibility::Inherited => quote!(pub(super)),
+        Visibility::Restricted(VisRestricted { path, .. }) => {
+            let mut path: Path = (**path).clone();
+            match path
+                .segments
+                .first()
+                .map(|segment| segment.ident.to_string())
+            {
+                Some(first) if first == "self" => {
+                    path.segments[0].ident = format_ident!("super");
+                }
+                Some(first) if first == "super" => {
+                    path.segments.insert(0, format_ident!("super").into());
+                }
+                _ => {}
+            }
+            quote!(pub(in #path))
+        }
+        Visibility::Public(_) | Visibility::Crate(_) => quote!(#vis),
+    }
+}
+
+/// Returns the only field of a single-field tuple struct, or an error spanned on the construct
+/// that prevents deriving `derive_name`.
+fn single_field<'a>(derive_name: &'static str, input: &'a DeriveInput) -> syn::Result<&'a Field> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) => single_unnamed_field(derive_name, fields),
+            Fields::Unit => Err(syn::Error::new(
+                input.ident.span(),
+                format!("Unit struct cannot derive {derive_name}"),
+            )),
+            Fields::Named(fields) => Err(syn::Error::new(
+                fields.brace_token.span,
+                format!(
+                    "Deriving from {derive_name} by a struct with named fields is not yet implemented"
+                ),
+            )),
+        },
+        Data::Enum(data) => Err(syn::Error::new(
+            data.enum_token.span,
+            format!("Cannot derive {derive_name} for enum, expected struct."),
+        )),
+        Data::Union(data) => Err(syn::Error::new(
+            data.union_token.span,
+            format!("Cannot derive {derive_name} for union, expected struct."),
+        )),
+    }
+}
+
+fn single_unnamed_field<'a>(
+    derive_name: &'static str,
+    fields: &'a FieldsUnnamed,
+) -> syn::Result<&'a Field> {
+    let mut iter = fields.unnamed.iter();
+    match (iter.next(), iter.next()) {
+        (Some(field), None) => Ok(field),
+        (None, _) => Err(syn::Error::new(
+            fields.paren_token.span,
+            format!("Cannot derive {derive_name} for a struct without fields"),
+        )),
+        (Some(_), Some(_)) => {
+            let extra = fields.unnamed.iter().skip(1);
+            Err(syn::Error::new_spanned(
+                quote!(#(#extra),*),
+                "Only single-field structs supported at the moment",
+            ))
+        }
+    }
+}
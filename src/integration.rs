@@ -0,0 +1,121 @@
+use proc_macro2::TokenStream;
+use syn::Ident;
+
+use crate::input::IdInput;
+#[cfg(any(
+    feature = "serde",
+    feature = "speedy",
+    feature = "miniserde",
+    feature = "hash32",
+    feature = "schemars",
+    feature = "metrics",
+    feature = "opentelemetry",
+    feature = "http"
+))]
+use crate::operation;
+
+/// Impls of third-party traits, each behind a cargo feature of the same name, which umbrella
+/// derives emit when requested with `#[id(integrations(...))]`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Integration {
+    Serde,
+    Speedy,
+    Miniserde,
+    Hash32,
+    Schemars,
+    Metrics,
+    Opentelemetry,
+    Http,
+}
+
+impl Integration {
+    const ALL: &'static [(&'static str, Self, bool)] = &[
+        ("serde", Self::Serde, cfg!(feature = "serde")),
+        ("speedy", Self::Speedy, cfg!(feature = "speedy")),
+        ("miniserde", Self::Miniserde, cfg!(feature = "miniserde")),
+        ("hash32", Self::Hash32, cfg!(feature = "hash32")),
+        ("schemars", Self::Schemars, cfg!(feature = "schemars")),
+        ("metrics", Self::Metrics, cfg!(feature = "metrics")),
+        (
+            "opentelemetry",
+            Self::Opentelemetry,
+            cfg!(feature = "opentelemetry"),
+        ),
+        ("http", Self::Http, cfg!(feature = "http")),
+    ];
+
+    /// Parses the name of an integration, which fails if it is unknown or its feature
+    /// is not enabled.
+    pub fn from_ident(ident: &Ident) -> syn::Result<Self> {
+        let name = ident.to_string();
+        match Self::ALL.iter().find(|(known, _, _)| *known == name) {
+            Some((_, integration, true)) => Ok(*integration),
+            Some((_, _, false)) => Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "The `{name}` integration requires the `{name}` feature of id-derive, \
+                     which is not enabled"
+                ),
+            )),
+            None => {
+                let known: Vec<_> = Self::ALL
+                    .iter()
+                    .map(|(known, _, _)| format!("`{known}`"))
+                    .collect();
+                Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "Unknown integration `{name}`, expected one of {}",
+                        known.join(", ")
+                    ),
+                ))
+            }
+        }
+    }
+
+    fn enabled(self) -> bool {
+        Self::ALL
+            .iter()
+            .any(|(_, integration, enabled)| *integration == self && *enabled)
+    }
+
+    // `input` is unused if none of the integration features are enabled.
+    #[allow(unused_variables)]
+    fn implement(self, input: &IdInput) -> syn::Result<TokenStream> {
+        match self {
+            #[cfg(feature = "serde")]
+            Self::Serde => operation::serde(input),
+            #[cfg(feature = "speedy")]
+            Self::Speedy => operation::speedy(input),
+            #[cfg(feature = "miniserde")]
+            Self::Miniserde => operation::miniserde(input),
+            #[cfg(feature = "hash32")]
+            Self::Hash32 => operation::hash32(input),
+            #[cfg(feature = "schemars")]
+            Self::Schemars => operation::schemars(input),
+            #[cfg(feature = "metrics")]
+            Self::Metrics => operation::metrics(input),
+            #[cfg(feature = "opentelemetry")]
+            Self::Opentelemetry => operation::opentelemetry(input),
+            #[cfg(feature = "http")]
+            Self::Http => operation::http(input),
+            #[allow(unreachable_patterns)]
+            _ => Ok(TokenStream::new()),
+        }
+    }
+}
+
+/// Implements the integrations requested with `#[id(integrations(...))]`, along with
+/// the `defaults` of the umbrella derive whose features are enabled.
+pub fn integrations(input: &IdInput, defaults: &[Integration]) -> syn::Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    let mut implemented = Vec::new();
+    let defaults = defaults.iter().filter(|integration| integration.enabled());
+    for &integration in defaults.chain(&input.config.integrations) {
+        if !implemented.contains(&integration) {
+            tokens.extend(integration.implement(input)?);
+            implemented.push(integration);
+        }
+    }
+    Ok(tokens)
+}
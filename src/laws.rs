@@ -0,0 +1,181 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Expr, Ident, Token, Type};
+
+use crate::operation::snake_case;
+
+/// Laws checked when `laws(...)` is not given.
+const DEFAULT_LAWS: &[&str] = &["convert", "display", "arithmetic", "hash"];
+
+/// Input of `id_laws!`: `Name(Inner)`, optionally followed by `samples(...)` and `laws(...)`.
+pub struct LawsInput {
+    ident: Ident,
+    inner_ty: Type,
+    samples: Vec<Expr>,
+    laws: Vec<Ident>,
+}
+
+impl Parse for LawsInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        let content;
+        parenthesized!(content in input);
+        let inner_ty = content.parse()?;
+        let mut samples = Vec::new();
+        let mut laws = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let content;
+            parenthesized!(content in input);
+            match key.to_string().as_str() {
+                "samples" => {
+                    samples.extend(Punctuated::<Expr, Token![,]>::parse_terminated(&content)?);
+                }
+                "laws" => {
+                    for law in Punctuated::<Ident, Token![,]>::parse_terminated(&content)? {
+                        if !DEFAULT_LAWS.contains(&law.to_string().as_str()) {
+                            return Err(syn::Error::new(
+                                law.span(),
+                                format!(
+                                    "Unknown law `{law}`, expected one of: {}",
+                                    DEFAULT_LAWS.join(", ")
+                                ),
+                            ));
+                        }
+                        laws.push(law);
+                    }
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("Unknown id_laws option `{key}`, expected `samples` or `laws`"),
+                    ))
+                }
+            }
+        }
+        if laws.is_empty() {
+            laws = DEFAULT_LAWS
+                .iter()
+                .map(|law| format_ident!("{}", law))
+                .collect();
+        }
+        Ok(Self {
+            ident,
+            inner_ty,
+            samples,
+            laws,
+        })
+    }
+}
+
+/// Expands to a test module with a test per law, each checked for all pairs of samples.
+pub fn laws(input: &LawsInput) -> TokenStream {
+    let name = &input.ident;
+    let ty = &input.inner_ty;
+    let module = format_ident!("{}_laws", snake_case(&name.to_string()));
+    let default_samples: Vec<Expr> = vec![
+        syn::parse_quote!(0_u8),
+        syn::parse_quote!(1_u8),
+        syn::parse_quote!(7_u8),
+        syn::parse_quote!(42_u8),
+    ];
+    let samples = if input.samples.is_empty() {
+        &default_samples
+    } else {
+        &input.samples
+    };
+    let tests = input.laws.iter().map(|law| {
+        let body = match law.to_string().as_str() {
+            "convert" => convert_law(name, ty),
+            "display" => display_law(name),
+            "arithmetic" => arithmetic_law(name),
+            _ => hash_law(name),
+        };
+        quote! {
+            #[test]
+            fn #law() {
+                #body
+            }
+        }
+    });
+    quote! {
+        #[cfg(test)]
+        mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            fn samples() -> ::std::vec::Vec<#ty> {
+                ::std::vec![#(<#ty as ::std::convert::From<_>>::from(#samples)),*]
+            }
+
+            #(#tests)*
+        }
+    }
+}
+
+fn convert_law(name: &Ident, ty: &Type) -> TokenStream {
+    let from_inner = format!("From<{}> for {}", quote!(#ty), name);
+    let into_inner = format!("From<{}> for {}", name, quote!(#ty));
+    quote! {
+        for inner in samples() {
+            let id = <#name as ::std::convert::From<#ty>>::from(inner.clone());
+            ::std::assert_eq!(id, #name(inner.clone()), #from_inner);
+            ::std::assert_eq!(<#ty as ::std::convert::From<#name>>::from(id), inner, #into_inner);
+        }
+    }
+}
+
+fn display_law(name: &Ident) -> TokenStream {
+    quote! {
+        for inner in samples() {
+            let id = #name(inner);
+            let s = ::std::string::ToString::to_string(&id);
+            let parsed = <#name as ::std::str::FromStr>::from_str(&s)
+                .unwrap_or_else(|_| ::std::panic!("{:?} does not parse back", s));
+            ::std::assert_eq!(parsed, id, "{:?} parses back as a different identifier", s);
+        }
+    }
+}
+
+fn arithmetic_law(name: &Ident) -> TokenStream {
+    quote! {
+        for a in samples() {
+            for d in samples() {
+                if let ::std::option::Option::Some(sum) = a.checked_add(d) {
+                    ::std::assert_eq!(#name(a) + d, #name(sum));
+                    ::std::assert_eq!(#name(a) + d - d, #name(a));
+                }
+                if let ::std::option::Option::Some(difference) = a.checked_sub(d) {
+                    ::std::assert_eq!(#name(a) - d, #name(difference));
+                    ::std::assert_eq!(#name(a) - d + d, #name(a));
+                }
+            }
+        }
+    }
+}
+
+fn hash_law(name: &Ident) -> TokenStream {
+    quote! {
+        fn hash<T: ::std::hash::Hash>(value: &T) -> ::std::primitive::u64 {
+            let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
+            value.hash(&mut hasher);
+            ::std::hash::Hasher::finish(&hasher)
+        }
+        for a in samples() {
+            for b in samples() {
+                let equal = a == b;
+                let (x, y) = (#name(a.clone()), #name(b));
+                ::std::assert_eq!(x == y, equal);
+                if equal {
+                    ::std::assert_eq!(hash(&x), hash(&y));
+                }
+            }
+        }
+    }
+}
@@ -78,204 +78,2098 @@
     unused_qualifications
 )]
 #![warn(clippy::all, clippy::pedantic)]
-#![allow(clippy::module_name_repetitions, clippy::default_trait_access)]
+#![allow(
+    clippy::module_name_repetitions,
+    clippy::default_trait_access,
+    clippy::unnecessary_wraps
+)]
 #![doc(html_root_url = "https://docs.rs/id-derive/0.1.0")]
 
 extern crate proc_macro;
 
-use syn::{parse_macro_input, DeriveInput};
+use quote::ToTokens;
+#[cfg(feature = "serde")]
+use syn::Data;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, DeriveInput, Expr, File, ImplItem, Item, ItemMod,
+    Stmt,
+};
 
+mod attr;
+mod inner;
+mod input;
+mod integration;
+mod laws;
 mod operation;
 
-macro_rules! handle {
-    ($s:expr) => {
-        proc_macro::TokenStream::from(match $s {
-            Ok(tokens) => tokens,
-            Err(err) => return ::proc_macro::TokenStream::from(err.to_compile_error()),
-        })
+use attr::Inline;
+use input::{EnumIdInput, IdInput, IdItem};
+use integration::Integration;
+
+/// Parses the derive input, or returns the errors as the output of the derive.
+macro_rules! id_input {
+    ($input:expr, $derive_name:expr) => {
+        match IdInput::parse($derive_name, &$input) {
+            Ok(input) => input,
+            Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
+        }
+    };
+}
+
+/// Parses the derive input of an enum-backed identifier, or returns the errors as the output
+/// of the derive.
+macro_rules! enum_id_input {
+    ($input:expr, $derive_name:expr) => {
+        match EnumIdInput::parse($derive_name, &$input) {
+            Ok(input) => input,
+            Err(err) => return proc_macro::TokenStream::from(err.to_compile_error()),
+        }
     };
-    ($($s:expr),*) => {{
+}
+
+macro_rules! handle {
+    ($input:ident, $($s:expr),*) => {{
         let mut tokens = ::proc_macro2::TokenStream::new();
+        let mut errors = ::std::option::Option::None;
         $(
             match $s {
                 Ok(t) => {
                     tokens.extend(t);
                 },
                 Err(err) => {
-                    return ::proc_macro::TokenStream::from(err.to_compile_error())
+                    combine_errors(&mut errors, err);
                 },
             }
         )*
-        return proc_macro::TokenStream::from(tokens);
+        let tokens = match errors {
+            Some(err) => Err(err),
+            None => with_inline(tokens, IdItem::config(&$input).inline),
+        };
+        proc_macro::TokenStream::from(tokens.unwrap_or_else(|err| err.to_compile_error()))
     }};
 }
 
-/// Implements `Display`.
-#[proc_macro_derive(Display)]
+/// Adds `err` to the accumulated `errors`, skipping messages that were already reported,
+/// which happens when several operations fail due to the same problem with the input.
+fn combine_errors(errors: &mut Option<syn::Error>, err: syn::Error) {
+    for err in err {
+        match errors {
+            Some(errors) => {
+                if errors
+                    .clone()
+                    .into_iter()
+                    .all(|e| e.to_string() != err.to_string())
+                {
+                    errors.combine(err);
+                }
+            }
+            None => *errors = Some(err),
+        }
+    }
+}
+
+/// Adds the attribute requested with `#[id(inline = "...")]`, if any, to every method of the
+/// impls in `tokens`, including those in companion modules and `const _` blocks. Methods in
+/// the body of a `macro_rules!` are out of reach, so generators spell out their impls.
+fn with_inline(
+    tokens: proc_macro2::TokenStream,
+    inline: Option<Inline>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some(inline) = inline else {
+        return Ok(tokens);
+    };
+    let attr: Attribute = match inline {
+        Inline::Always => parse_quote!(#[inline(always)]),
+        Inline::Never => parse_quote!(#[inline(never)]),
+        Inline::Default => parse_quote!(#[inline]),
+    };
+    let mut file: File = syn::parse2(tokens)?;
+    add_inline(&mut file.items, &attr);
+    Ok(file.into_token_stream())
+}
+
+fn add_inline(items: &mut [Item], attr: &Attribute) {
+    for item in items {
+        match item {
+            Item::Impl(item) => {
+                for item in &mut item.items {
+                    if let ImplItem::Method(method) = item {
+                        method.attrs.retain(|attr| !attr.path.is_ident("inline"));
+                        method.attrs.push(attr.clone());
+                    }
+                }
+            }
+            Item::Mod(ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => add_inline(items, attr),
+            Item::Const(item) => {
+                if let Expr::Block(block) = &mut *item.expr {
+                    for stmt in &mut block.block.stmts {
+                        if let Stmt::Item(item) = stmt {
+                            add_inline(std::slice::from_mut(item), attr);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Implements `Display` and `Binary`. `Binary` is left out for `f32` and `f64` inner types,
+/// as well as well-known types without a binary representation, such as `String` and `Uuid`.
+///
+/// With `#[id(prefix = "...")]`, `Display` writes the prefix before the inner value.
+/// More generally, `#[id(display = "...")]` takes a format string with a single `{}`
+/// placeholder for the inner value, which may carry a format spec such as `{:04}`.
+/// It cannot be combined with `prefix`.
+///
+/// ```
+/// # use id_derive::Display;
+/// #[derive(Display)]
+/// #[id(display = "<user/{:04}>")]
+/// struct UserId(u32);
+///
+/// assert_eq!(UserId(42).to_string(), "<user/0042>");
+/// ```
+#[proc_macro_derive(Display, attributes(id))]
 pub fn display(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::display("Display", &input))
+    let input = id_input!(input, "Display");
+    handle!(input, operation::display(&input))
+}
+
+/// Implements `FromStr` by parsing the inner type, as well as `TryFrom<&str>` and
+/// `TryFrom<String>` with the same error. `TryFrom<String>` is left out for `String` inner
+/// types, which convert from `String` with [`FromInner`](derive.FromInner.html) instead.
+///
+/// By default, the error is that of the inner type. With `#[id(parse_error)]`, parsing fails
+/// with a `<Name>ParseError` companion type instead, which retains the parsed string,
+/// implements `std::error::Error` with the inner error as its source, and renders as,
+/// e.g., `invalid UserId "abc": invalid digit found in string`.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, FromStr)]
+/// #[id(parse_error)]
+/// struct UserId(u32);
+///
+/// let err = "abc".parse::<UserId>().unwrap_err();
+/// assert_eq!(err.input(), "abc");
+/// assert_eq!(err.to_string(), "invalid UserId \"abc\": invalid digit found in string");
+/// ```
+///
+/// A prefix given with `#[id(prefix = "DOC-")]` is required when parsing, and written by
+/// [`Display`](derive.Display.html). Since a missing prefix cannot be reported with the error
+/// of the inner type, setting a prefix implies `parse_error`. Likewise, a format string given
+/// with `#[id(display = "...")]` is matched around the inner value, so that `FromStr` accepts
+/// what `Display` writes. Format specs that pad with spaces need `parse(trim)` to round-trip.
+///
+/// With `#[id(validate(...))]`, described in [`FromInner`](derive.FromInner.html), parsed values
+/// are checked as well, which implies `parse_error`, and rejected ones fail with, e.g.,
+/// `invalid UserId "0": rejected by validation`.
+///
+/// With a prefix, display format, or check digit, test builds get an `assert_round_trip`
+/// method, which asserts that the `Display` output of an identifier parses back to an equal
+/// inner value.
+///
+/// ```
+/// # use id_derive::*;
+/// # use std::convert::TryFrom;
+/// #[derive(Debug, PartialEq, Display, FromStr)]
+/// #[id(display = "<user/{}>")]
+/// struct UserId(u32);
+///
+/// assert_eq!(UserId::try_from("<user/42>").unwrap(), UserId(42));
+/// let err = "user/42".parse::<UserId>().unwrap_err();
+/// assert_eq!(err.to_string(), "invalid UserId \"user/42\": expected format \"<user/{}>\"");
+/// ```
+///
+/// Parsing can be made more lenient with `#[id(parse(...))]`, accepting any of:
+/// - `trim`: ignores leading and trailing whitespace, both around the whole string and
+///   around the inner value,
+/// - `case_insensitive_prefix`: matches the prefix regardless of ASCII case,
+/// - `allow_plus`: skips a `+` sign before the inner value; note that integers accept it anyway.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, FromStr)]
+/// #[id(prefix = "DOC-", parse(trim, case_insensitive_prefix))]
+/// struct DocId(u32);
+///
+/// assert_eq!("  doc-42 ".parse::<DocId>().unwrap(), DocId(42));
+/// ```
+///
+/// With `#[id(check_digit = "luhn")]`, [`Display`](derive.Display.html) appends a Luhn check
+/// digit to the inner value, which `FromStr` requires, verifies, and strips, implying
+/// `parse_error`. This catches any single mistyped digit and most swaps of adjacent digits
+/// in identifiers that are typed or read out by people. The inner type must be an unsigned
+/// integer of at most 64 bits, and the check digit is included in the display placeholder,
+/// e.g., in its padding.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Display, FromStr)]
+/// #[id(prefix = "INV-", check_digit = "luhn")]
+/// struct InvoiceId(u64);
+///
+/// assert_eq!(InvoiceId(7_992_739_871).to_string(), "INV-79927398713");
+/// assert_eq!("INV-79927398713".parse::<InvoiceId>().unwrap(), InvoiceId(7_992_739_871));
+/// let err = "INV-79927398731".parse::<InvoiceId>().unwrap_err();
+/// assert_eq!(err.to_string(), "invalid InvoiceId \"INV-79927398731\": invalid check digit");
+/// ```
+#[proc_macro_derive(FromStr, attributes(id))]
+pub fn from_str(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "FromStr");
+    handle!(input, operation::from_str(&input))
+}
+
+/// Implements `serde::Serialize` and `serde::Deserialize`, delegating to the inner type.
+///
+/// Identifiers wrapping `&'a str` or `Cow<'a, str>` borrow from the deserialized input,
+/// as with `#[serde(borrow)]`. Unlike `&str`, a `Cow` falls back to an owned string when
+/// the input cannot be borrowed, e.g., for strings with escape sequences in JSON.
+///
+/// Deserialized identifiers are checked with the `validate` function, if any, as described
+/// for [`FromInner`](derive.FromInner.html), and rejected with its error as the message.
+///
+/// With `#[id(serde = "human_readable")]`, identifiers are represented as strings in
+/// human-readable formats, such as JSON, using their `Display` and `FromStr` impls,
+/// and as the inner value in binary formats. Combined with a `prefix` or `display` format,
+/// the strings are the same as those written by `Display` and accepted by `FromStr`.
+///
+/// With `#[id(serde_packed)]`, `serialize_packed` and `deserialize_packed` represent a whole
+/// `Vec<Name>` as a single byte buffer of the little-endian inner values, instead of a sequence
+/// of separately encoded values, which is smaller and faster for long lists of identifiers.
+/// They are meant for `#[serde(serialize_with = "...", deserialize_with = "...")]` on
+/// fields, and work for inner types with `to_le_bytes` and `from_le_bytes`. `usize` and
+/// `isize` take as many bytes as on the platform that serialized them.
+///
+/// ```
+/// # use id_derive::Serde;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serde, Debug, PartialEq)]
+/// #[id(serde_packed)]
+/// struct DocId(u32);
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Postings {
+///     #[serde(
+///         serialize_with = "DocId::serialize_packed",
+///         deserialize_with = "DocId::deserialize_packed"
+///     )]
+///     docs: Vec<DocId>,
+/// }
+///
+/// let postings = Postings { docs: vec![DocId(1), DocId(256)] };
+/// let bytes = bincode::serialize(&postings).unwrap();
+/// assert_eq!(bytes.len(), 8 + 2 * 4);
+/// let postings: Postings = bincode::deserialize(&bytes).unwrap();
+/// assert_eq!(postings.docs, [DocId(1), DocId(256)]);
+/// ```
+///
+/// [Enum-backed identifiers](derive.EnumId.html), i.e., fieldless enums with an integer
+/// `#[repr(...)]`, are represented by their discriminants rather than variant names, as with
+/// `serde_repr`, and unknown discriminants are rejected when deserializing.
+///
+/// ```
+/// # use id_derive::Serde;
+/// #[derive(Serde, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum RegionId {
+///     Europe = 1,
+///     Asia = 2,
+/// }
+///
+/// assert_eq!(serde_json::to_string(&RegionId::Asia).unwrap(), "2");
+/// assert_eq!(serde_json::from_str::<RegionId>("1").unwrap(), RegionId::Europe);
+/// assert!(serde_json::from_str::<RegionId>("3").is_err());
+/// ```
+///
+/// Requires the `serde` feature.
+#[cfg(feature = "serde")]
+#[proc_macro_derive(Serde, attributes(id))]
+pub fn serde(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    if let Data::Enum(_) = input.data {
+        let input = enum_id_input!(input, "Serde");
+        return handle!(input, operation::enum_serde(&input));
+    }
+    let input = id_input!(input, "Serde");
+    handle!(input, operation::serde(&input))
+}
+
+/// Implements `hash32::Hash`, delegating to the inner type, so identifiers can key
+/// `heapless` maps, such as `FnvIndexMap`, on embedded targets.
+///
+/// Requires the `hash32` feature.
+#[cfg(feature = "hash32")]
+#[proc_macro_derive(Hash32, attributes(id))]
+pub fn hash32(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Hash32");
+    handle!(input, operation::hash32(&input))
+}
+
+/// Implements `write_to`, which writes the same text as [`Display`](derive.Display.html) to
+/// any `fmt::Write` without allocating, formatting the inner integer with `itoa`. With
+/// the `arrayvec` feature as well, `to_array_string` returns the text in an `ArrayString`
+/// whose capacity fits the longest possible output. This avoids a `String` per identifier
+/// in hot logging paths. Display formats whose placeholder has a format spec, such as
+/// `{:04}`, are written with `write!`, which does not allocate either, but is slower.
+///
+/// Requires the `itoa` feature. The inner type must be a primitive integer, and `base32` and
+/// `check_digit` are not supported.
+///
+/// ```
+/// # use id_derive::{Display, Itoa};
+/// #[derive(Display, Itoa)]
+/// #[id(prefix = "user-")]
+/// struct UserId(u32);
+///
+/// let mut line = String::from("login ");
+/// UserId(42).write_to(&mut line).unwrap();
+/// assert_eq!(line, "login user-42");
+/// ```
+#[cfg(feature = "itoa")]
+#[proc_macro_derive(Itoa, attributes(id))]
+pub fn itoa(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Itoa");
+    handle!(input, operation::itoa(&input))
+}
+
+/// Implements `to_arrow_array` and `from_arrow_array`, converting between a slice of
+/// identifiers and an Arrow `PrimitiveArray` of the inner type, e.g., of `UInt64Type` for
+/// `u64`, with nulls read as `None`, and `arrow_data_type`, returning the data type of
+/// the array. This keeps identifiers typed on both sides of a columnar boundary.
+///
+/// Requires the `arrow` feature. The inner type must be a fixed-width primitive integer
+/// or float, and the `arrow` crate must be available under its own name.
+///
+/// ```
+/// # use id_derive::Arrow;
+/// #[derive(Debug, PartialEq, Clone, Copy, Arrow)]
+/// struct UserId(u64);
+///
+/// let column = UserId::to_arrow_array(&[UserId(1), UserId(2)]);
+/// assert_eq!(column.values(), &[1, 2]);
+/// assert_eq!(
+///     UserId::from_arrow_array(&column),
+///     vec![Some(UserId(1)), Some(UserId(2))]
+/// );
+/// ```
+#[cfg(feature = "arrow")]
+#[proc_macro_derive(Arrow, attributes(id))]
+pub fn arrow(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Arrow");
+    handle!(input, operation::arrow(&input))
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `metrics::SharedString` with the `Display`
+/// output, including any prefix or display format, so that identifiers can be passed as
+/// label values of the `metrics` macros without a `format!` at every call site.
+///
+/// Requires the `metrics` feature.
+///
+/// ```
+/// # use id_derive::{Display, Metrics};
+/// #[derive(Display, Metrics)]
+/// #[id(prefix = "tenant-")]
+/// struct TenantId(u32);
+///
+/// let tenant = TenantId(7);
+/// metrics::counter!("requests", "tenant" => &tenant).increment(1);
+/// assert_eq!(metrics::SharedString::from(tenant).as_ref(), "tenant-7");
+/// ```
+#[cfg(feature = "metrics")]
+#[proc_macro_derive(Metrics, attributes(id))]
+pub fn metrics(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Metrics");
+    handle!(input, operation::metrics(&input))
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `opentelemetry::Value`, so that identifiers
+/// can be passed as span and metric attributes.
+///
+/// Identifiers whose inner type converts losslessly to `i64`, i.e., integers up to `i64` and
+/// `u32`, become `Value::I64`, and those with an `f32` or `f64` inner type become `Value::F64`.
+/// All others, as well as identifiers with a prefix, a display format, a check digit, or
+/// Base32, become `Value::String` with the `Display` output, so that `u64` identifiers are not
+/// silently truncated and traces show the same form as logs.
+///
+/// Requires the `opentelemetry` feature.
+///
+/// ```
+/// # use id_derive::{Display, Opentelemetry};
+/// use opentelemetry::{KeyValue, Value};
+///
+/// #[derive(Display, Opentelemetry)]
+/// struct ShardId(u32);
+///
+/// #[derive(Display, Opentelemetry)]
+/// #[id(prefix = "order-")]
+/// struct OrderId(u32);
+///
+/// assert_eq!(KeyValue::new("shard", ShardId(3)).value, Value::I64(3));
+/// assert_eq!(Value::from(OrderId(7)), Value::from("order-7"));
+/// ```
+#[cfg(feature = "opentelemetry")]
+#[proc_macro_derive(Opentelemetry, attributes(id))]
+pub fn opentelemetry(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Opentelemetry");
+    handle!(input, operation::opentelemetry(&input))
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `http::HeaderValue` with the `Display`
+/// output, and `TryFrom<&HeaderValue>` for `Name` with `FromStr`, for request and trace
+/// identifiers carried in headers.
+///
+/// The conversion from a header value fails with the `<Name>HeaderError` companion enum,
+/// either `NotVisibleAscii` if the value is not visible ASCII, or `Parse` with the `FromStr`
+/// error. The conversion into a header value panics if the `Display` output is not a valid
+/// header value, which can only happen for identifiers wrapping strings with control
+/// characters.
+///
+/// Requires the `http` feature.
+///
+/// ```
+/// # use id_derive::{Display, FromStr, Http};
+/// use http::{HeaderMap, HeaderValue};
+/// use std::convert::TryFrom;
+///
+/// #[derive(Debug, PartialEq, Display, FromStr, Http)]
+/// #[id(prefix = "req-")]
+/// struct RequestId(u64);
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("x-request-id", RequestId(42).into());
+/// assert_eq!(headers["x-request-id"], "req-42");
+/// assert_eq!(RequestId::try_from(&headers["x-request-id"]).unwrap(), RequestId(42));
+/// assert!(RequestId::try_from(&HeaderValue::from_static("42")).is_err());
+/// ```
+#[cfg(feature = "http")]
+#[proc_macro_derive(Http, attributes(id))]
+pub fn http(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Http");
+    handle!(input, operation::http(&input))
+}
+
+/// Implements `fake::Dummy<fake::Faker>` by faking the inner value, so that test fixtures built
+/// with the fake crate can populate identifier fields, e.g., with `Faker.fake::<UserId>()` or
+/// `#[derive(fake::Dummy)]` on a struct holding identifiers. With `#[id(validate(...))]`,
+/// inner values are drawn until one passes the check, so the check must accept a reasonable
+/// share of the values of the inner type.
+///
+/// Requires the `fake` feature.
+///
+/// ```
+/// # use id_derive::{Fake, FromInner};
+/// use fake::{Fake, Faker};
+///
+/// fn odd(inner: &u32) -> Result<(), ()> {
+///     if inner % 2 == 1 { Ok(()) } else { Err(()) }
+/// }
+///
+/// #[derive(Fake, FromInner)]
+/// #[id(validate(odd, error = ()))]
+/// struct TicketId(u32);
+///
+/// let ticket: TicketId = Faker.fake();
+/// assert_eq!(ticket.0 % 2, 1);
+/// ```
+#[cfg(feature = "fake")]
+#[proc_macro_derive(Fake, attributes(id))]
+pub fn fake(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Fake");
+    handle!(input, operation::fake(&input))
+}
+
+/// Implements `redb::Value` and `redb::Key`, so that identifiers can be keys and values of redb
+/// tables without a hand-written wrapper. Identifiers are stored with the fixed-width,
+/// order-preserving encoding of `to_key_bytes`, described in [`Bytes`](derive.Bytes.html),
+/// so range queries visit them in order. The table type name is the name of the identifier.
+/// The inner type must be an integer.
+///
+/// Requires the `redb` feature.
+///
+/// ```
+/// # use id_derive::Redb;
+/// use redb::backends::InMemoryBackend;
+/// use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
+///
+/// #[derive(Debug, PartialEq, Redb)]
+/// struct Offset(i64);
+///
+/// const TABLE: TableDefinition<Offset, &str> = TableDefinition::new("events");
+///
+/// # fn main() -> Result<(), redb::Error> {
+/// let db = Database::builder().create_with_backend(InMemoryBackend::new())?;
+/// let tx = db.begin_write()?;
+/// {
+///     let mut table = tx.open_table(TABLE)?;
+///     table.insert(Offset(1), "b")?;
+///     table.insert(Offset(-1), "a")?;
+/// }
+/// tx.commit()?;
+///
+/// let table = db.begin_read()?.open_table(TABLE)?;
+/// let (first, value) = table.first()?.unwrap();
+/// assert_eq!((first.value(), value.value()), (Offset(-1), "a"));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "redb")]
+#[proc_macro_derive(Redb, attributes(id))]
+pub fn redb(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Redb");
+    handle!(input, operation::redb(&input))
+}
+
+/// Implements `heed::BytesEncode` and `heed::BytesDecode`, so that the identifier type is its
+/// own codec in LMDB databases opened with heed, e.g., `Database<UserId, Str>`. Identifiers are
+/// encoded as by `to_key_bytes`, described in [`Bytes`](derive.Bytes.html), which is also the
+/// encoding of [`Redb`](derive.Redb.html), so keys are ordered like the identifiers and data
+/// written through one store reads the same through the other. Decoding fails for bytes
+/// of the wrong length. The inner type must be an integer.
+///
+/// Requires the `heed` feature.
+///
+/// ```
+/// # use id_derive::Heed;
+/// use heed::{BytesDecode, BytesEncode};
+///
+/// #[derive(Debug, PartialEq, Heed)]
+/// struct UserId(u32);
+///
+/// let bytes = UserId::bytes_encode(&UserId(258)).unwrap();
+/// assert_eq!(bytes.as_ref(), [0, 0, 1, 2]);
+/// assert_eq!(UserId::bytes_decode(&bytes).unwrap(), UserId(258));
+/// assert!(UserId::bytes_decode(&[1, 2]).is_err());
+/// ```
+#[cfg(feature = "heed")]
+#[proc_macro_derive(Heed, attributes(id))]
+pub fn heed(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Heed");
+    handle!(input, operation::heed(&input))
+}
+
+/// Implements conversions between identifiers and tantivy values, so that search applications
+/// can keep strongly typed document identifiers while talking to the index. What is generated
+/// depends on the inner type:
+///
+/// - `u32`, which is a tantivy `DocId`: `const` methods `from_doc_id` and `doc_id`.
+/// - `u64` or `i64`: `tantivy::fastfield::FastValue`, so that identifiers can be read from a
+///   fast field of the inner type, e.g., with `TopDocs::order_by_fast_field`, and
+///   `From<Name>` for `OwnedValue`, to add them to documents.
+/// - `u64` also gets `const` methods `from_doc_address` and `doc_address`, packing
+///   a `DocAddress` with the segment ordinal in the high 32 bits. Like the `DocAddress` itself,
+///   such identifiers are only meaningful for the searcher that produced them.
+///
+/// The fast-field traits require the identifier to implement `Clone`, `Copy`, `Debug`,
+/// and `PartialOrd`.
+///
+/// Requires the `tantivy` feature.
+///
+/// ```
+/// # use id_derive::Tantivy;
+/// use tantivy::DocAddress;
+///
+/// #[derive(Debug, PartialEq, Tantivy)]
+/// struct SegmentDocId(u32);
+///
+/// #[derive(Debug, PartialEq, Clone, Copy, PartialOrd, Tantivy)]
+/// struct HitId(u64);
+///
+/// assert_eq!(SegmentDocId::from_doc_id(7).doc_id(), 7);
+/// let hit = HitId::from_doc_address(DocAddress::new(1, 7));
+/// assert_eq!(hit, HitId((1 << 32) | 7));
+/// assert_eq!(hit.doc_address(), DocAddress::new(1, 7));
+/// ```
+#[cfg(feature = "tantivy")]
+#[proc_macro_derive(Tantivy, attributes(id))]
+pub fn tantivy(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Tantivy");
+    handle!(input, operation::tantivy(&input))
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `egui::Id`, so that widgets and memory can be
+/// keyed by identifiers. The `egui::Id` hashes the type name along with the inner value, so
+/// identifiers of different types sharing an inner value do not collide. The inner type must
+/// implement `Hash`.
+///
+/// Requires the `egui` feature.
+///
+/// ```
+/// # use id_derive::Egui;
+/// #[derive(Egui)]
+/// struct UserId(u32);
+///
+/// #[derive(Egui)]
+/// struct GroupId(u32);
+///
+/// assert_eq!(egui::Id::from(UserId(1)), egui::Id::from(&UserId(1)));
+/// assert_ne!(egui::Id::from(UserId(1)), egui::Id::from(GroupId(1)));
+/// ```
+#[cfg(feature = "egui")]
+#[proc_macro_derive(Egui, attributes(id))]
+pub fn egui(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Egui");
+    handle!(input, operation::egui(&input))
+}
+
+/// Implements `bevy_reflect::Reflect`, along with the other reflection traits, such as
+/// `FromReflect` and `GetTypeRegistration`, so that identifiers can be stored in components
+/// and resources that derive `Reflect`, and inspected in the editor. Identifiers are reflected
+/// as tuple structs, and their `Hash` and `PartialEq` impls are registered, which lets them key
+/// reflected maps. The identifier must implement `Hash` and `PartialEq`, and cannot be generic.
+///
+/// Requires the `bevy` feature, and the `bevy_reflect` crate as a dependency.
+///
+/// ```
+/// # use id_derive::Bevy;
+/// use bevy_ecs::prelude::*;
+/// use bevy_reflect::Reflect;
+/// use std::collections::HashMap;
+///
+/// #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Bevy)]
+/// struct PlayerId(u32);
+///
+/// #[derive(Component, Reflect)]
+/// struct Owner(PlayerId);
+///
+/// #[derive(Resource, Reflect, Default)]
+/// struct Scores(HashMap<PlayerId, u64>);
+///
+/// let mut world = World::new();
+/// world.init_resource::<Scores>();
+/// world.spawn(Owner(PlayerId(1)));
+/// world.resource_mut::<Scores>().0.insert(PlayerId(1), 10);
+/// assert_eq!(world.resource::<Scores>().0[&PlayerId(1)], 10);
+/// ```
+#[cfg(feature = "bevy")]
+#[proc_macro_derive(Bevy, attributes(id))]
+pub fn bevy(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Bevy");
+    handle!(input, operation::bevy(&input))
+}
+
+/// Implements the gdext conversion traits `GodotConvert`, `ToGodot`, and `FromGodot`, so that
+/// games built with the `godot` crate can pass identifiers across the engine boundary, e.g., as
+/// arguments and return values of `#[func]` methods or in `#[var]` properties. String-backed
+/// identifiers are passed as `StringName`, which the engine interns, and other identifiers as
+/// their inner values, which the engine stores as 64-bit `int`s for integers. With
+/// `#[id(validate(...))]`, values coming from the engine are validated, and the conversion
+/// fails for invalid ones.
+///
+/// Requires the `godot` feature.
+///
+/// ```
+/// # use id_derive::Godot;
+/// use godot::meta::{FromGodot, ToGodot};
+///
+/// #[derive(Debug, PartialEq, Godot)]
+/// struct UnitId(i64);
+///
+/// assert_eq!(UnitId(7).to_godot(), 7);
+/// assert_eq!(UnitId::from_godot(7), UnitId(7));
+/// ```
+#[cfg(feature = "godot")]
+#[proc_macro_derive(Godot, attributes(id))]
+pub fn godot(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Godot");
+    handle!(input, operation::godot(&input))
+}
+
+/// Implements `schemars::JsonSchema` with the schema of the inner type, which matches
+/// the default representation of [`Serde`](derive.Serde.html).
+///
+/// Requires the `schemars` feature.
+#[cfg(feature = "schemars")]
+#[proc_macro_derive(Schemars, attributes(id))]
+pub fn schemars(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Schemars");
+    handle!(input, operation::schemars(&input))
+}
+
+/// Implements `speedy::Readable` and `speedy::Writable`, delegating to the inner type,
+/// so identifiers have the same fixed-width encoding as their inner integers.
+///
+/// Requires the `speedy` feature.
+#[cfg(feature = "speedy")]
+#[proc_macro_derive(Speedy, attributes(id))]
+pub fn speedy(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Speedy");
+    handle!(input, operation::speedy(&input))
+}
+
+/// Implements `miniserde::Serialize` and `miniserde::Deserialize`, delegating to the inner
+/// type, for builds that avoid the compile cost of serde. Deserialization supports inner
+/// types represented by a single value, such as integers, floats, and strings.
+///
+/// Requires the `miniserde` feature.
+///
+/// ```
+/// # use id_derive::Miniserde;
+/// #[derive(Debug, PartialEq, Miniserde)]
+/// struct UserId(u32);
+///
+/// assert_eq!(miniserde::json::to_string(&UserId(7)), "7");
+/// assert_eq!(miniserde::json::from_str::<UserId>("7").unwrap(), UserId(7));
+/// ```
+#[cfg(feature = "miniserde")]
+#[proc_macro_derive(Miniserde, attributes(id))]
+pub fn miniserde(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Miniserde");
+    handle!(input, operation::miniserde(&input))
 }
 
 /// Implements `Add<Self>`.
-#[proc_macro_derive(Add)]
+///
+/// If an offset type is given with `#[id(delta = MyOffset)]`, additionally implements
+/// `Add<MyOffset>`. See [`Sub`](derive.Sub.html) for details.
+#[proc_macro_derive(Add, attributes(id))]
 pub fn add_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::add_self("Add", &input))
+    let input = id_input!(input, "Add");
+    handle!(input, operation::add_self(&input))
 }
 
-/// Implements `Add<T>` where `T` is the type of identifier.
-#[proc_macro_derive(AddInner)]
+/// Implements `Add<T>` and `Add<&T>` where `T` is the type of identifier.
+///
+/// Taking a reference saves a dereference when the inner values come from an iterator
+/// over references. The same holds for the other operators taking the inner type.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Clone, Copy, AddInner)]
+/// struct Offset(u64);
+///
+/// let deltas = [1_u64, 2, 3];
+/// let end = deltas.iter().fold(Offset(10), |offset, delta| offset + delta);
+/// assert_eq!(end, Offset(16));
+/// ```
+#[proc_macro_derive(AddInner, attributes(id))]
 pub fn add_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::add_inner("AddInner", &input))
+    let input = id_input!(input, "AddInner");
+    handle!(input, operation::inner_op(&input, &operation::ADD))
 }
 
 /// Implements `AddAssign<Self>`.
-#[proc_macro_derive(AddAssign)]
+#[proc_macro_derive(AddAssign, attributes(id))]
 pub fn add_assign_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::add_assign_self("Add", &input))
+    let input = id_input!(input, "AddAssign");
+    handle!(input, operation::assign_self_op(&input, &operation::ADD))
 }
 
-/// Implements `AddAssign<T>` where `T` is the type of identifier.
-#[proc_macro_derive(AddAssignInner)]
+/// Implements `AddAssign<T>` and `AddAssign<&T>` where `T` is the type of identifier.
+#[proc_macro_derive(AddAssignInner, attributes(id))]
 pub fn add_assign_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::add_assign_inner("AddInner", &input))
+    let input = id_input!(input, "AddAssignInner");
+    handle!(input, operation::assign_inner_op(&input, &operation::ADD))
 }
 
 /// Implements `Sub<Self>`.
-#[proc_macro_derive(Sub)]
+///
+/// By default, the difference of two identifiers is another identifier.
+/// Alternatively, an offset type can be given with `#[id(delta = MyOffset)]`, in which case
+/// `Sub<Self>` outputs `MyOffset`, and `Sub<MyOffset>` is implemented as well.
+/// The offset type must be a single-field tuple struct with a signed integer inner type,
+/// whose field is visible from where the derive is used. Differences and offsets are converted
+/// through their magnitude, so any result that fits the target type is computed, e.g.,
+/// `i64::MIN` steps over the whole lower half of `u64`, and a difference that does not fit
+/// panics. The offset type is not generated, because `Add` and `Sub` expand independently and
+/// neither can own its definition; it can derive traits of its own, such as `Display`.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq)]
+/// struct Offset(i64);
+///
+/// #[derive(Debug, PartialEq, Copy, Clone, Add, Sub)]
+/// #[id(delta = Offset)]
+/// struct Position(u32);
+///
+/// assert_eq!(Position(3) - Position(5), Offset(-2));
+/// assert_eq!(Position(3) + Offset(-2), Position(1));
+/// assert_eq!(Position(3) - Offset(-2), Position(5));
+/// ```
+#[proc_macro_derive(Sub, attributes(id))]
 pub fn sub_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::sub_self("Sub", &input))
+    let input = id_input!(input, "Sub");
+    handle!(input, operation::sub_self(&input))
 }
 
-/// Implements `Sub<T>` where `T` is the type of identifier.
-#[proc_macro_derive(SubInner)]
+/// Implements `Sub<T>` and `Sub<&T>` where `T` is the type of identifier.
+#[proc_macro_derive(SubInner, attributes(id))]
 pub fn sub_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::sub_inner("SubInner", &input))
+    let input = id_input!(input, "SubInner");
+    handle!(input, operation::inner_op(&input, &operation::SUB))
 }
 
 /// Implements `SubAssign<Self>`.
-#[proc_macro_derive(SubAssign)]
+#[proc_macro_derive(SubAssign, attributes(id))]
 pub fn sub_assign_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::sub_assign_self("Sub", &input))
+    let input = id_input!(input, "SubAssign");
+    handle!(input, operation::assign_self_op(&input, &operation::SUB))
 }
 
-/// Implements `SubAssign<T>` where `T` is the type of identifier.
-#[proc_macro_derive(SubAssignInner)]
+/// Implements `SubAssign<T>` and `SubAssign<&T>` where `T` is the type of identifier.
+#[proc_macro_derive(SubAssignInner, attributes(id))]
 pub fn sub_assign_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::sub_assign_inner("SubInner", &input))
+    let input = id_input!(input, "SubAssignInner");
+    handle!(input, operation::assign_inner_op(&input, &operation::SUB))
 }
 
 /// Implements `Mul<Self>`.
-#[proc_macro_derive(Mul)]
+///
+/// The output is `Self` by default. It can be changed with `#[id(mul_output = Inner)]`
+/// to the inner type, or with `#[id(mul_output = MyType)]` to any type implementing `From`
+/// of the inner type. The same goes for `add_output`, `sub_output`, and `div_output`,
+/// which are supported by the respective derives and by [`Id`](derive.Id.html).
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, FromInner)]
+/// struct Area(u32);
+///
+/// #[derive(Debug, PartialEq, Mul)]
+/// #[id(mul_output = Area)]
+/// struct Length(u32);
+///
+/// assert_eq!(Length(3) * Length(4), Area(12));
+/// ```
+#[proc_macro_derive(Mul, attributes(id))]
 pub fn mul_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::mul_self("Mul", &input))
+    let input = id_input!(input, "Mul");
+    handle!(input, operation::self_op(&input, &operation::MUL))
 }
 
-/// Implements `Mul<T>` where `T` is the type of identifier.
-#[proc_macro_derive(MulInner)]
+/// Implements `Mul<T>` and `Mul<&T>` where `T` is the type of identifier.
+#[proc_macro_derive(MulInner, attributes(id))]
 pub fn mul_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::mul_inner("MulInner", &input))
+    let input = id_input!(input, "MulInner");
+    handle!(input, operation::inner_op(&input, &operation::MUL))
 }
 
 /// Implements `MulAssign<Self>`.
-#[proc_macro_derive(MulAssign)]
+#[proc_macro_derive(MulAssign, attributes(id))]
 pub fn mul_assign_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::mul_assign_self("MulAssign", &input))
+    let input = id_input!(input, "MulAssign");
+    handle!(input, operation::assign_self_op(&input, &operation::MUL))
 }
 
-/// Implements `MulAssign<T>` where `T` is the type of identifier.
-#[proc_macro_derive(MulAssignInner)]
+/// Implements `MulAssign<T>` and `MulAssign<&T>` where `T` is the type of identifier.
+#[proc_macro_derive(MulAssignInner, attributes(id))]
 pub fn mul_assign_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::mul_assign_inner("MulAssignInner", &input))
+    let input = id_input!(input, "MulAssignInner");
+    handle!(input, operation::assign_inner_op(&input, &operation::MUL))
 }
 
-/// Implements `Div<Self>`.
-#[proc_macro_derive(Div)]
+/// Implements `Div<Self>` and a `checked_div` method, which returns `None` when dividing by zero.
+///
+/// With `#[id(div = "checked")]`, division itself is checked: `Div` has `Option<Self>` as its
+/// output, and `DivAssign` cannot be implemented. The default policy is `div = "panic"`,
+/// which panics on division by zero, as the inner type does.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Div)]
+/// #[id(div = "checked")]
+/// struct MyId(u32);
+///
+/// assert_eq!(MyId(12) / MyId(3), Some(MyId(4)));
+/// assert_eq!(MyId(12) / MyId(0), None);
+/// ```
+#[proc_macro_derive(Div, attributes(id))]
 pub fn div_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::div_self("Div", &input))
+    let input = id_input!(input, "Div");
+    handle!(input, operation::div_self(&input))
 }
 
-/// Implements `Div<T>` where `T` is the type of identifier.
-#[proc_macro_derive(DivInner)]
+/// Implements `Div<T>` and `Div<&T>` where `T` is the type of identifier, and
+/// a `checked_div_inner` method.
+///
+/// Follows the division policy described in [`Div`](derive.Div.html).
+#[proc_macro_derive(DivInner, attributes(id))]
 pub fn div_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::div_inner("DivInner", &input))
+    let input = id_input!(input, "DivInner");
+    handle!(input, operation::div_inner(&input))
 }
 
 /// Implements `DivAssign<Self>`.
-#[proc_macro_derive(DivAssign)]
+#[proc_macro_derive(DivAssign, attributes(id))]
 pub fn div_assign_self(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::div_assign_self("DivAssign", &input))
+    let input = id_input!(input, "DivAssign");
+    handle!(input, operation::assign_self_op(&input, &operation::DIV))
 }
 
-/// Implements `DivAssign<T>` where `T` is the type of identifier.
-#[proc_macro_derive(DivAssignInner)]
+/// Implements `DivAssign<T>` and `DivAssign<&T>` where `T` is the type of identifier.
+#[proc_macro_derive(DivAssignInner, attributes(id))]
 pub fn div_assign_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::div_assign_inner("DivAssignInner", &input))
+    let input = id_input!(input, "DivAssignInner");
+    handle!(input, operation::assign_inner_op(&input, &operation::DIV))
+}
+
+/// Implements `Add<Name>` and `Mul<Name>` for the inner type `T`, with `Name` as the output,
+/// so that `base + id` works as well as `id + base`. Only these two operators are implemented,
+/// as subtraction and division do not commute. The inner type must be a concrete type,
+/// as the impls are for the inner type rather than the identifier.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Clone, Copy, AddInner, CommutativeInner)]
+/// struct PageId(u32);
+///
+/// assert_eq!(10 + PageId(2), PageId(2) + 10);
+/// assert_eq!(3 * PageId(2), PageId(6));
+/// ```
+#[proc_macro_derive(CommutativeInner, attributes(id))]
+pub fn commutative_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "CommutativeInner");
+    handle!(input, operation::commutative_inner(&input))
+}
+
+/// Implements `BitAnd<Self>`, `BitOr<Self>`, and `BitXor<Self>`, their compound assignment
+/// counterparts, and `Not`, for identifiers backed by integers.
+#[proc_macro_derive(BitOps, attributes(id))]
+pub fn bit_ops(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "BitOps");
+    handle!(input, operation::bit_ops(&input))
+}
+
+/// Implements `const_add`, `const_sub`, `const_mul`, and `const_div`, which, unlike the operator
+/// traits, can be called in const contexts on stable Rust.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Clone, Copy, ConstOps)]
+/// struct MyId(u32);
+///
+/// const FIRST: MyId = MyId(10);
+/// const NEXT: MyId = FIRST.const_add(MyId(1));
+/// assert_eq!(NEXT.0, 11);
+/// ```
+#[proc_macro_derive(ConstOps, attributes(id))]
+pub fn const_ops(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "ConstOps");
+    handle!(input, operation::const_ops(&input))
 }
 
 /// Implements `From<T>` where `T` is the type of identifier.
-#[proc_macro_derive(FromInner)]
+///
+/// If `T` is `Wrapping<U>` or `Saturating<U>`, `From<U>` is implemented as well.
+///
+/// With `#[id(opaque)]`, `From<T>` is left out, and the identifier gets `from_raw` and
+/// `into_raw` methods instead, which are `pub(crate)` unless overridden with `vis`.
+/// This way, only the owning crate can mint identifiers, while the rest of the program
+/// can merely pass them around. The option applies to [`Convert`](derive.Convert.html),
+/// [`OpaqueId`](derive.OpaqueId.html), and [`Id`](derive.Id.html) as well, and to every other
+/// generated method creating identifiers from inner values or their encodings, such as
+/// `from_le_bytes`, `decode_varint`, `from_arrow_array`, or `from_doc_id`.
+///
+/// Trait impls cannot be restricted, so identifiers can still be read back from the forms
+/// they are written to by [`FromStr`](derive.FromStr.html), which `OpaqueId` implements,
+/// [`Serde`](derive.Serde.html), and storage integrations, such as tantivy fast fields.
+/// These are meant for identifiers that the owning crate handed out, and can be checked
+/// with `validate`, described below. To leave parsing out, derive `Convert` instead of
+/// `OpaqueId`.
+///
+/// ```compile_fail
+/// # use id_derive::*;
+/// #[derive(FromInner)]
+/// #[id(opaque)]
+/// pub struct UserId(u32);
+///
+/// let id = UserId::from(1);
+/// ```
+///
+/// With `#[id(ffi)]`, the identifier must be `#[repr(transparent)]`, which makes it
+/// ABI-compatible with the inner type, so that it can be passed to and returned from
+/// `extern "C"` functions, and cbindgen emits it as a typedef of the inner type. It also gets
+/// `const` `from_raw` and `into_raw` methods, which are `pub` unless overridden with `vis`,
+/// or restricted as described above when combined with `opaque`; being `const`, they require
+/// an inner type without a destructor, as C types are. Handles crossing a C boundary thus
+/// stay strongly typed on the Rust side. The option applies to
+/// [`Convert`](derive.Convert.html), [`OpaqueId`](derive.OpaqueId.html), and
+/// [`Id`](derive.Id.html) as well.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Clone, Copy, FromInner)]
+/// #[id(ffi)]
+/// #[repr(transparent)]
+/// pub struct SessionHandle(u64);
+///
+/// pub extern "C" fn session_next(handle: SessionHandle) -> SessionHandle {
+///     SessionHandle::from_raw(handle.into_raw() + 1)
+/// }
+///
+/// const FIRST: SessionHandle = SessionHandle::from_raw(1);
+/// assert_eq!(session_next(FIRST), SessionHandle(2));
+/// ```
+///
+/// With `#[id(renamed_from = "OldName")]`, a `#[deprecated]` type alias `OldName` is generated
+/// with the visibility of the identifier type, so that code still using the former name keeps
+/// compiling, with a warning pointing to the new one, while a rename is rolled out. Since the
+/// alias is the same type, values convert between the two names without any `From` impls.
+/// The option applies to [`Convert`](derive.Convert.html), [`OpaqueId`](derive.OpaqueId.html),
+/// and [`Id`](derive.Id.html) as well.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, FromInner)]
+/// #[id(renamed_from = "OldDocId")]
+/// pub struct DocId(u32);
+///
+/// #[allow(deprecated)]
+/// fn legacy_lookup(id: OldDocId) -> DocId {
+///     id
+/// }
+///
+/// assert_eq!(legacy_lookup(DocId::from(3)), DocId(3));
+/// ```
+///
+/// With `#[id(validate(check, error = Error))]`, `TryFrom<T>` is implemented instead of `From<T>`,
+/// rejecting the inner values for which `check`, a function taking `&T` and returning
+/// `Result<(), Error>`, fails. [`FromStr`](derive.FromStr.html) and
+/// [`Serde`](derive.Serde.html) run the same check when parsing and deserializing, while
+/// the other methods creating identifiers from inner values are restricted as with `opaque`.
+/// Arithmetic is not checked.
+///
+/// ```
+/// # use id_derive::*;
+/// # use std::convert::TryFrom;
+/// #[derive(Debug, PartialEq)]
+/// struct ZeroId;
+///
+/// fn non_zero(inner: &u32) -> Result<(), ZeroId> {
+///     if *inner == 0 { Err(ZeroId) } else { Ok(()) }
+/// }
+///
+/// #[derive(Debug, PartialEq, FromInner)]
+/// #[id(validate(non_zero, error = ZeroId))]
+/// struct UserId(u32);
+///
+/// assert_eq!(UserId::try_from(7), Ok(UserId(7)));
+/// assert_eq!(UserId::try_from(0), Err(ZeroId));
+/// ```
+#[proc_macro_derive(FromInner, attributes(id))]
 pub fn from_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::from_inner("FromInner", &input))
+    let input = id_input!(input, "FromInner");
+    handle!(input, operation::from_inner(&input))
 }
 
 /// Implements `From<Self>` for `T` where `T` is the type of identifier.
-#[proc_macro_derive(IntoInner)]
+///
+/// If `T` is `Wrapping<U>` or `Saturating<U>`, `From<Self>` is implemented for `U` as well.
+#[proc_macro_derive(IntoInner, attributes(id))]
 pub fn into_inner(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    handle!(operation::into_inner("IntoInner", &input))
+    let input = id_input!(input, "IntoInner");
+    handle!(input, operation::into_inner(&input))
+}
+
+/// Implements `from_ref` and `from_mut`, which view references to inner values as references
+/// to identifiers without copying, and `as_inner_ref`, which returns a reference to the inner
+/// value. This lets data structures holding raw inner values be used through identifiers in
+/// place, e.g., to look up `&UserId` in a `HashMap<&str, u32>`. Likewise, `wrap_slice` and
+/// `unwrap_slice`, and their `_mut` variants, view `&[T]` as `&[Self]` and back, e.g., for
+/// memory-mapped or FFI-provided buffers, and `wrap_vec` and `unwrap_vec` convert between
+/// `Vec<T>` and `Vec<Self>` reusing the allocation, e.g., to bulk-load identifiers decoded as
+/// raw integers. None of them go through the elements.
+///
+/// It also generates a `<Name>Slice` companion type, an unsized wrapper of `[Name]`, whose
+/// `from_raw` views a `&[T]` as a `&<Name>Slice` without copying, so that buffers of raw inner
+/// values, such as identifier arrays in memory-mapped index files, can be traversed with
+/// the typed API at no cost at load time. It dereferences to `[Name]`, so all slice methods
+/// apply, and `as_raw` returns the inner values back. Traits such as `Debug` can be derived for
+/// it with `#[id(companion_derive(...))]`. Like the other constructors, `from_raw` and
+/// `from_raw_mut` are restricted for opaque and validated identifiers, as described below.
+///
+/// The identifier must be `#[repr(transparent)]`, which guarantees that it has the same layout
+/// as the inner type, and deriving `Transparent` without it is a compile error. There is no
+/// checked fallback for other layouts, as a cast that might fail at run time would not serve
+/// buffers that must be viewed in place.
+///
+/// With `#[id(opaque)]` or `#[id(validate(...))]`, the methods that create identifiers from
+/// inner values, or give mutable access to the inner values of identifiers, such as
+/// `unwrap_slice_mut`, are `pub(crate)`, unless set otherwise with `#[id(vis = "...")]`, so that
+/// other crates cannot use them to bypass the restriction.
+///
+/// ```
+/// # use id_derive::Transparent;
+/// use std::collections::HashMap;
+///
+/// #[derive(Debug, PartialEq, Transparent)]
+/// #[repr(transparent)]
+/// struct UserId(u32);
+///
+/// let mut users = HashMap::new();
+/// users.insert("alice", 7);
+/// let alice: &UserId = UserId::from_ref(&users["alice"]);
+/// assert_eq!(alice, &UserId(7));
+/// assert_eq!(alice.as_inner_ref(), &7);
+///
+/// let mut raw = 1;
+/// UserId::from_mut(&mut raw).0 += 1;
+/// assert_eq!(raw, 2);
+///
+/// let raw = [4, 5];
+/// assert_eq!(UserId::wrap_slice(&raw), [UserId(4), UserId(5)]);
+///
+/// let ids = UserId::wrap_vec(vec![1, 2, 3]);
+/// assert_eq!(ids, [UserId(1), UserId(2), UserId(3)]);
+/// assert_eq!(UserId::unwrap_vec(ids), [1, 2, 3]);
+///
+/// let postings: &[u32] = &[2, 3, 5, 8];
+/// let ids = UserIdSlice::from_raw(postings);
+/// assert!(ids.contains(&UserId(5)));
+/// assert_eq!(ids.as_raw().as_ptr(), postings.as_ptr());
+/// ```
+#[proc_macro_derive(Transparent, attributes(id))]
+pub fn transparent(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Transparent");
+    handle!(input, operation::transparent(&input))
+}
+
+/// Implements `to_le_bytes`, `to_be_bytes`, `from_le_bytes`, and `from_be_bytes`,
+/// delegating to the inner integer.
+///
+/// With `#[id(key)]`, which requires an integer inner type, it also implements `to_key_bytes`
+/// and `from_key_bytes`, an encoding meant for keys of ordered key-value stores, such as
+/// `RocksDB` or `sled`. The encoding is order-preserving: the bytes are big-endian, with the sign
+/// bit flipped for signed integers, so that comparing them lexicographically orders them
+/// the same as the identifiers, and range scans over keys visit identifiers in order. `to_key`
+/// wraps the bytes in a `<Name>Key` companion type implementing `AsRef<[u8]>` and
+/// `Borrow<[u8]>`, which can be passed directly to the store.
+///
+/// ```
+/// # use id_derive::Bytes;
+/// #[derive(Debug, PartialEq, Clone, Copy, Bytes)]
+/// #[id(key)]
+/// struct Offset(i32);
+///
+/// let mut keys = vec![Offset(3).to_key(), Offset(-1).to_key(), Offset(0).to_key()];
+/// keys.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+/// let ids: Vec<_> = keys.iter().map(OffsetKey::id).collect();
+/// assert_eq!(ids, [Offset(-1), Offset(0), Offset(3)]);
+/// assert_eq!(Offset(-1).to_key_bytes(), [0x7f, 0xff, 0xff, 0xff]);
+/// assert_eq!(Offset::from_key_bytes([0x80, 0, 0, 0]), Offset(0));
+/// ```
+#[proc_macro_derive(Bytes, attributes(id))]
+pub fn bytes(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Bytes");
+    handle!(input, operation::bytes(&input))
+}
+
+/// Implements LEB128 variable-length encoding, in which small identifiers take fewer bytes,
+/// for compact streams of identifiers in index files and wire formats. `encode_varint` appends
+/// the encoding to a `Vec<u8>` and `decode_varint` decodes it from the start of a byte slice,
+/// returning the number of bytes read, while `write_varint` and `read_varint` work with
+/// `std::io` writers and readers. Signed integers are zigzag-encoded, so that small negative
+/// values are short as well. `VARINT_MAX_LEN` is the maximum length of the encoding.
+/// The inner type must be a primitive integer.
+///
+/// Sorted identifiers, such as postings lists, are stored more compactly as gaps between
+/// consecutive identifiers. `delta_encode` adapts an iterator of sorted identifiers into one of
+/// the gaps, as the unsigned counterpart of the inner type, and `delta_decode` reverses it.
+/// `encode_deltas` appends the gaps of sorted identifiers to a buffer as varints,
+/// and `decode_deltas` decodes a whole buffer of them back.
+///
+/// ```
+/// # use id_derive::Varint;
+/// #[derive(Debug, PartialEq, Clone, Copy, Varint)]
+/// struct DocId(u32);
+///
+/// let mut buf = Vec::new();
+/// assert_eq!(DocId(5).encode_varint(&mut buf), 1);
+/// assert_eq!(DocId(300).encode_varint(&mut buf), 2);
+/// assert_eq!(buf, [5, 0xac, 0x02]);
+/// assert_eq!(DocId::decode_varint(&buf[1..]), Some((DocId(300), 2)));
+///
+/// let mut reader = &buf[..];
+/// assert_eq!(DocId::read_varint(&mut reader)?, DocId(5));
+/// assert_eq!(DocId::read_varint(&mut reader)?, DocId(300));
+/// assert!(DocId::read_varint(&mut reader).is_err());
+///
+/// let postings = [DocId(3), DocId(7), DocId(7), DocId(1000)];
+/// let gaps: Vec<u32> = DocId::delta_encode(postings).collect();
+/// assert_eq!(gaps, [3, 4, 0, 993]);
+/// assert!(DocId::delta_decode(gaps).eq(postings));
+///
+/// let mut buf = Vec::new();
+/// assert_eq!(DocId::encode_deltas(postings, &mut buf), 4);
+/// assert_eq!(buf.len(), 5);
+/// assert_eq!(DocId::decode_deltas(&buf), Some(postings.to_vec()));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[proc_macro_derive(Varint, attributes(id))]
+pub fn varint(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Varint");
+    handle!(input, operation::varint(&input))
+}
+
+/// Implements `to_base32_crockford` and `from_base32_crockford`, converting the inner value
+/// to and from Crockford's Base32, which is compact and easy to read out and type. Encoding
+/// uses uppercase digits, while decoding ignores case and hyphens, and reads the easily
+/// confused `O` as `0`, and `I` and `L` as `1`. The inner type must be an unsigned integer.
+///
+/// To use Base32 as the text form of the identifier, `#[id(base32)]` makes
+/// [`Display`](derive.Display.html) write it and [`FromStr`](derive.FromStr.html) parse it,
+/// along with any prefix, display format, and `parse(...)` options, while `FromStr` fails
+/// with a `<Name>ParseError`.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Base32, Display, FromStr)]
+/// #[id(prefix = "INV-", base32)]
+/// struct InvoiceId(u64);
+///
+/// assert_eq!(InvoiceId(1_234_567).to_base32_crockford(), "15NM7");
+/// assert_eq!(InvoiceId::from_base32_crockford("1-5nm7"), Some(InvoiceId(1_234_567)));
+/// assert_eq!(InvoiceId(1_234_567).to_string(), "INV-15NM7");
+/// assert_eq!("INV-l5nm7".parse::<InvoiceId>().unwrap(), InvoiceId(1_234_567));
+/// ```
+#[proc_macro_derive(Base32, attributes(id))]
+pub fn base32(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Base32");
+    handle!(input, operation::base32(&input))
+}
+
+/// Implements `std::iter::Step`, which allows for iterating over ranges of identifiers,
+/// e.g., `for id in MyId(0)..MyId(10)`.
+///
+/// Requires the `step_trait` feature and a nightly compiler with `#![feature(step_trait)]`
+/// enabled in the crate using the derive.
+#[cfg(feature = "step_trait")]
+#[proc_macro_derive(Step, attributes(id))]
+pub fn step(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Step");
+    handle!(input, operation::step(&input))
+}
+
+/// Implements `range`, `range_inclusive`, `to`, `to_inclusive`, and `successors`, returning
+/// iterators over identifiers. This works on stable, as opposed to [`Step`](derive.Step.html).
+///
+/// The returned iterators are companion types named `<Name>Range` and `<Name>RangeInclusive`.
+/// Like ranges of the inner type, they implement `DoubleEndedIterator` and `FusedIterator`,
+/// and `ExactSizeIterator` where the inner range does, i.e., for inner types narrower than
+/// 64 bits, or narrower than 32 bits for `<Name>RangeInclusive`. The iterator returned by
+/// `successors` is a `FusedIterator`. They are also values in their own right, so that APIs
+/// can take a span of identifiers: both have `start`, `end`, `contains`, `len`, `is_empty`,
+/// and `iter`, which iterates without consuming the range, and convert from `Range<Name>` and
+/// `RangeInclusive<Name>` respectively.
+///
+/// ```
+/// # use id_derive::Range;
+/// #[derive(Debug, Clone, Copy, PartialEq, Range)]
+/// struct PageId(u32);
+///
+/// fn first_unvisited(pages: &PageIdRange, visited: &[PageId]) -> Option<PageId> {
+///     pages.iter().find(|page| !visited.contains(page))
+/// }
+///
+/// let pages = PageIdRange::from(PageId(10)..PageId(20));
+/// assert!(pages.contains(PageId(19)));
+/// assert_eq!(pages.len(), 10);
+/// assert_eq!(first_unvisited(&pages, &[PageId(10)]), Some(PageId(11)));
+/// assert_eq!(pages.start(), PageId(10));
+/// ```
+///
+/// With the `rayon` feature, both also implement `rayon::iter::IntoParallelIterator`, splitting
+/// the range like a range of the inner type, for work done independently for each identifier.
+///
+/// ```
+/// # #[cfg(feature = "rayon")]
+/// # {
+/// # use id_derive::Range;
+/// use rayon::prelude::*;
+///
+/// #[derive(Clone, Copy, Range)]
+/// struct NodeId(u32);
+///
+/// let total: u64 = NodeId::range(NodeId(0), NodeId(1000))
+///     .into_par_iter()
+///     .map(|id| u64::from(id.0))
+///     .sum();
+/// assert_eq!(total, 499_500);
+/// # }
+/// ```
+///
+/// Generated methods are `pub`, and companion types share the visibility of the identifier.
+/// Both can be restricted with `#[id(vis = "pub(crate)")]`, which applies to every inherent
+/// item generated by the derives in this crate.
+///
+/// Companion types, such as these iterators or the parse errors, can be given additional
+/// derives with `#[id(companion_derive(Hash, PartialOrd))]`. Traits that a companion type
+/// already implements are skipped.
+///
+/// With `#[id(module = user_id)]`, companion types are placed in a generated `user_id` module
+/// and drop the identifier name, e.g., `user_id::Range` and `user_id::ParseError`. The module
+/// imports the parent module with `use super::*`, so the identifier must be declared at module
+/// level rather than inside a function. Since each derive generates its own module, `module`
+/// is meant for types with a single derive generating companion types.
+///
+/// ```
+/// # use id_derive::Range;
+/// #[derive(Clone, Copy, Range)]
+/// #[id(module = doc_id)]
+/// pub struct DocId(u32);
+///
+/// # fn main() {
+/// let range: doc_id::Range = DocId::range(DocId(1), DocId(3));
+/// assert_eq!(range.count(), 2);
+/// # }
+/// ```
+#[proc_macro_derive(Range, attributes(id))]
+pub fn range(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Range");
+    handle!(input, operation::range(&input))
+}
+
+/// Implements set operations over slices of identifiers sorted in ascending order and without
+/// duplicates, such as postings lists: `intersect` and `union`, returning the sorted result in
+/// a new vector, and `gallop`, a variant of `binary_search` whose running time is logarithmic
+/// in the returned position, for merging a short list into a long one. `intersect` gallops
+/// through the longer slice when one is much shorter than the other, and merges them otherwise.
+/// Identifiers are compared by their inner values, and must implement `Clone`.
+///
+/// ```
+/// # use id_derive::Sorted;
+/// #[derive(Debug, PartialEq, Clone, Copy, Sorted)]
+/// struct DocId(u32);
+///
+/// let a = [DocId(1), DocId(4), DocId(9)];
+/// let b = [DocId(2), DocId(4), DocId(8), DocId(9)];
+/// assert_eq!(DocId::intersect(&a, &b), [DocId(4), DocId(9)]);
+/// assert_eq!(
+///     DocId::union(&a, &b),
+///     [DocId(1), DocId(2), DocId(4), DocId(8), DocId(9)]
+/// );
+/// assert_eq!(DocId::gallop(&b, &DocId(8)), Ok(2));
+/// assert_eq!(DocId::gallop(&b, &DocId(5)), Err(2));
+/// ```
+#[proc_macro_derive(Sorted, attributes(id))]
+pub fn sorted(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Sorted");
+    handle!(input, operation::sorted(&input))
+}
+
+/// Implements `get_in` and `get_in_mut`, which look up the element of a slice at the position
+/// given by the identifier, so that existing untyped containers, such as `Vec<Document>`
+/// indexed by document IDs, can be accessed through identifiers. They return `None` for
+/// identifiers out of the bounds of the slice, or not representable as `usize`, such as
+/// negative ones. The inner type must be an integer.
+///
+/// ```
+/// # use id_derive::IndexSlice;
+/// #[derive(Clone, Copy, IndexSlice)]
+/// struct DocId(u32);
+///
+/// let mut titles = vec!["intro", "usage"];
+/// assert_eq!(DocId(1).get_in(&titles), Some(&"usage"));
+/// assert_eq!(DocId(2).get_in(&titles), None);
+/// *DocId(0).get_in_mut(&mut titles).unwrap() = "overview";
+/// assert_eq!(titles, ["overview", "usage"]);
+/// ```
+#[proc_macro_derive(IndexSlice, attributes(id))]
+pub fn index_slice(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "IndexSlice");
+    handle!(input, operation::index_slice(&input))
+}
+
+/// Generates an `Atomic<Name>` companion type, e.g., `AtomicUserId` for `UserId(u32)`, which
+/// wraps the matching atomic integer, such as `AtomicU32`, and whose `new`, `load`, `store`,
+/// `swap`, `compare_exchange`, `fetch_add`, `fetch_max`, and `into_inner` take and return
+/// identifiers. The inner type must be a primitive integer, and the identifier cannot be
+/// generic.
+///
+/// ```
+/// # use id_derive::Atomic;
+/// use std::sync::atomic::Ordering;
+///
+/// #[derive(Debug, PartialEq, Clone, Copy, Atomic)]
+/// struct UserId(u32);
+///
+/// let last = AtomicUserId::new(UserId(1));
+/// assert_eq!(last.fetch_add(1, Ordering::Relaxed), UserId(1));
+/// last.fetch_max(UserId(7), Ordering::Relaxed);
+/// assert_eq!(last.load(Ordering::Relaxed), UserId(7));
+/// ```
+#[proc_macro_derive(Atomic, attributes(id))]
+pub fn atomic(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Atomic");
+    handle!(input, operation::atomic(&input))
+}
+
+/// Generates a `<Name>Pool` companion type, a thread-safe pool of identifiers with `acquire`
+/// and `release`. New identifiers are handed out in increasing order, starting at the default
+/// inner value or at the identifier passed to `starting_at`, and released identifiers are
+/// reused first, most recently released first. The inner type must be a primitive integer,
+/// and the identifier cannot be generic.
+///
+/// ```
+/// # use id_derive::Pool;
+/// #[derive(Debug, PartialEq, Clone, Copy, Pool)]
+/// struct SessionId(u32);
+///
+/// let pool = SessionIdPool::new();
+/// let first = pool.acquire();
+/// assert_eq!(pool.acquire(), SessionId(1));
+/// pool.release(first);
+/// assert_eq!(pool.acquire(), SessionId(0));
+/// ```
+#[proc_macro_derive(Pool, attributes(id))]
+pub fn pool(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Pool");
+    handle!(input, operation::pool(&input))
+}
+
+/// Generates a `<Name>DashMap<V>` companion type, a concurrent map wrapping
+/// `dashmap::DashMap`, whose `insert`, `get`, `get_mut`, `remove`, and `contains_key` take
+/// identifiers, so that state keyed by identifiers can be shared across threads without
+/// converting keys at every call. The entries are keyed by the inner values, and
+/// `into_inner` returns the underlying map. The inner type must implement `Hash` and `Eq`,
+/// and the identifier cannot be generic.
+///
+/// Requires the `dashmap` feature.
+///
+/// ```
+/// # use id_derive::DashMap;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// #[derive(Clone, Copy, DashMap)]
+/// struct SessionId(u64);
+///
+/// let sessions = Arc::new(SessionIdDashMap::new());
+/// let writer = Arc::clone(&sessions);
+/// thread::spawn(move || writer.insert(SessionId(7), "alice")).join().unwrap();
+/// assert_eq!(sessions.get(&SessionId(7)).as_deref(), Some(&"alice"));
+/// assert_eq!(sessions.remove(&SessionId(7)), Some("alice"));
+/// assert!(sessions.is_empty());
+/// ```
+#[cfg(feature = "dashmap")]
+#[proc_macro_derive(DashMap, attributes(id))]
+pub fn dashmap(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "DashMap");
+    handle!(input, operation::dashmap(&input))
+}
+
+/// Generates a `<Name>RoaringSet` companion type, a compressed set of identifiers wrapping
+/// `roaring::RoaringBitmap` for `u32` inner types and `roaring::RoaringTreemap` for `u64` ones,
+/// suited to large, sparse sets, such as postings or deletion lists. Its `insert`, `remove`,
+/// `contains`, `min`, `max`, and iterators work with identifiers, and the operators `|`, `&`,
+/// `^`, and `-`, and their assigning forms, compute unions, intersections, symmetric
+/// differences, and differences of sets. `as_bitmap` and `into_bitmap` return the underlying
+/// bitmap, e.g., to serialize it. The identifier cannot be generic.
+///
+/// Requires the `roaring` feature.
+///
+/// ```
+/// # use id_derive::Roaring;
+/// #[derive(Debug, PartialEq, Roaring)]
+/// struct DocId(u32);
+///
+/// let mut matches: DocIdRoaringSet = vec![DocId(1), DocId(5), DocId(1_000_000)]
+///     .into_iter()
+///     .collect();
+/// let deleted: DocIdRoaringSet = vec![DocId(5)].into_iter().collect();
+/// matches -= &deleted;
+/// assert!(matches.insert(DocId(7)));
+/// assert!(!matches.contains(&DocId(5)));
+/// assert_eq!(matches.len(), 3);
+/// assert_eq!(
+///     matches.iter().collect::<Vec<_>>(),
+///     [DocId(1), DocId(7), DocId(1_000_000)]
+/// );
+/// assert!((&matches & &deleted).is_empty());
+/// ```
+#[cfg(feature = "roaring")]
+#[proc_macro_derive(Roaring, attributes(id))]
+pub fn roaring(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Roaring");
+    handle!(input, operation::roaring(&input))
+}
+
+/// Generates a `<Name>Generator` companion type, a thread-safe generator of monotonic,
+/// time-ordered identifiers: version 7 UUIDs for a `Uuid` inner type and ULIDs for a `Ulid`
+/// inner type. The most significant 48 bits hold the milliseconds since the Unix epoch,
+/// and the remaining ones are random for the first identifier generated in a millisecond
+/// and incremented for the following ones, so each identifier is greater than the previous.
+///
+/// `new` reads the system clock, while `with_clock` takes any source of milliseconds and
+/// a seed for the random bits, which makes the generated identifiers reproducible in tests.
+/// The random bits come from a fast non-cryptographic generator, so the identifiers must not
+/// be relied upon to be unguessable.
+///
+/// ```
+/// # use id_derive::Generator;
+/// use uuid::Uuid;
+///
+/// #[derive(Debug, PartialEq, PartialOrd, Generator)]
+/// struct EventId(Uuid);
+///
+/// let generator = EventIdGenerator::with_clock(|| 1_700_000_000_000, 42);
+/// let first = generator.generate();
+/// let second = generator.generate();
+/// assert!(first < second);
+/// assert_eq!(first.0.get_version_num(), 7);
+/// ```
+#[proc_macro_derive(Generator, attributes(id))]
+pub fn generator(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Generator");
+    handle!(input, operation::generator(&input))
+}
+
+/// Implements `set_label`, `label`, and `remove_label`, which associate human-readable labels
+/// with identifiers, and `Debug`, which shows the label after the inner value when present,
+/// in place of `derive(Debug)`. This helps to tell apart identifiers in debug output, such as
+/// graph dumps, that would otherwise be bare integers.
+///
+/// The labels are kept in a registry shared by all values of the type, which lives for the
+/// rest of the program, so labels should be removed with `remove_label` once identifiers are
+/// no longer in use. The inner type must implement `Hash`, `Eq`, and `Debug`, and the identifier
+/// cannot be generic.
+///
+/// ```
+/// # use id_derive::Label;
+/// #[derive(Clone, Copy, Label)]
+/// struct NodeId(u32);
+///
+/// NodeId::set_label(NodeId(0), "root node");
+/// assert_eq!(format!("{:?}", NodeId(0)), r#"NodeId(0, "root node")"#);
+/// assert_eq!(format!("{:?}", NodeId(1)), "NodeId(1)");
+/// ```
+#[proc_macro_derive(Label, attributes(id))]
+pub fn label(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Label");
+    handle!(input, operation::label(&input))
+}
+
+/// Implements `test(n)`, returning the `n`-th test identifier, and `test_sequence`, returning
+/// an iterator over `test(1)`, `test(2)`, and so on, so that tests share one convention for
+/// fake identifiers. The methods are compiled only under `cfg(test)` or the `test-util`
+/// feature of the crate using the derive, which lets integration tests and other crates use
+/// them when the feature is declared and enabled. Crates without a `test-util` feature get
+/// no `unexpected_cfgs` warning for it.
+///
+/// The inner value of `test(n)` is `n` for integer inner types, panicking if it does not fit,
+/// `<snake_case_name>-<n>` for string inner types, and `n` as a 128-bit value for `Uuid` and
+/// `Ulid` inner types.
+///
+/// ```
+/// # use id_derive::Fixture;
+/// #[derive(Debug, PartialEq, Fixture)]
+/// pub struct OrderId(u64);
+///
+/// #[derive(Debug, PartialEq, Fixture)]
+/// pub struct SkuId(String);
+///
+/// #[cfg(test)]
+/// fn sample_orders() -> Vec<(OrderId, SkuId)> {
+///     // [(OrderId(1), SkuId("sku_id-1")), (OrderId(2), SkuId("sku_id-2"))]
+///     OrderId::test_sequence()
+///         .zip(SkuId::test_sequence())
+///         .take(2)
+///         .collect()
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(Fixture, attributes(id))]
+pub fn fixture(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Fixture");
+    handle!(input, operation::fixture(&input))
+}
+
+/// Implements `timestamp_millis`, returning the creation time encoded in the identifier
+/// in milliseconds since the Unix epoch, and `created_at`, returning it as a `SystemTime`.
+///
+/// The inner type must be a `Uuid`, assumed to be of version 7, a `Ulid`, or an integer
+/// with the `#[id(snowflake(epoch = ..., shift = ...))]` option. A snowflake holds
+/// the milliseconds since `epoch` in the bits above the lowest `shift` ones; `epoch`
+/// defaults to 0 and `shift` to 22, the layout of Twitter snowflakes.
+///
+/// ```
+/// # use id_derive::Timestamp;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// #[derive(Timestamp)]
+/// #[id(snowflake(epoch = 1_288_834_974_657))]
+/// struct TweetId(u64);
+///
+/// let tweet = TweetId(1_212_161_512_736_399_360);
+/// assert_eq!(tweet.timestamp_millis(), 1_577_836_800_165);
+/// assert_eq!(
+///     tweet.created_at(),
+///     UNIX_EPOCH + Duration::from_millis(1_577_836_800_165)
+/// );
+/// ```
+#[proc_macro_derive(Timestamp, attributes(id))]
+pub fn timestamp(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Timestamp");
+    handle!(input, operation::timestamp(&input))
+}
+
+/// Implements `next`, `prev`, `checked_next`, and `checked_prev`. The inner type must be an
+/// integer.
+#[proc_macro_derive(Succ, attributes(id))]
+pub fn succ(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Succ");
+    handle!(input, operation::succ(&input))
+}
+
+/// Implements `abs_diff` and its alias `distance`. For primitive integers, they return the
+/// unsigned counterpart of the inner type, as `i32::abs_diff` does, so that the distance
+/// between any two identifiers fits. Other inner types, such as `f64`, return the inner type.
+///
+/// ```
+/// # use id_derive::Distance;
+/// #[derive(Clone, Copy, Distance)]
+/// struct Level(i8);
+///
+/// assert_eq!(Level(i8::MIN).distance(Level(i8::MAX)), 255_u8);
+/// ```
+#[proc_macro_derive(Distance, attributes(id))]
+pub fn distance(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Distance");
+    handle!(input, operation::distance(&input))
+}
+
+/// Implements `offset`, `checked_offset`, and `wrapping_offset`, moving the identifier by
+/// a signed amount. The inner type must be an integer.
+#[proc_macro_derive(Offset, attributes(id))]
+pub fn offset(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Offset");
+    handle!(input, operation::offset(&input))
+}
+
+/// Implements `is_zero`, `is_min`, and `is_max` predicates on identifiers backed by integers.
+/// Floating-point and wrapped inner types are rejected.
+#[proc_macro_derive(Bounds, attributes(id))]
+pub fn bounds(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Bounds");
+    handle!(input, operation::bounds(&input))
+}
+
+/// Implements `pow` and `checked_pow`, raising the inner integer to a power.
+#[proc_macro_derive(Pow, attributes(id))]
+pub fn pow(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "Pow");
+    handle!(input, operation::pow(&input))
+}
+
+/// Implements `leading_zeros`, `trailing_zeros`, `count_ones`, and the `BITS` constant,
+/// forwarded to the inner integer. Useful for identifiers that encode packed structure.
+/// Floating-point and wrapped inner types are rejected.
+#[proc_macro_derive(BitInfo, attributes(id))]
+pub fn bit_info(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "BitInfo");
+    handle!(input, operation::bit_info(&input))
+}
+
+/// Implements [`BitOps`](derive.BitOps.html) and set operations for bitmask identifiers,
+/// such as permission or capability masks, whose bits are independent flags: `contains`,
+/// `insert`, `remove`, and `toggle` take masks with any number of bits set, `union`,
+/// `intersection`, and `difference` compose masks in const contexts, and `iter` returns each
+/// set bit as a separate mask. The inner type must be an unsigned integer.
+///
+/// ```
+/// # use id_derive::MaskId;
+/// #[derive(MaskId, Debug, PartialEq, Clone, Copy)]
+/// struct Permission(u8);
+///
+/// const READ: Permission = Permission(0b001);
+/// const WRITE: Permission = Permission(0b010);
+/// const EXECUTE: Permission = Permission(0b100);
+/// const READ_WRITE: Permission = READ.union(WRITE);
+///
+/// let mut granted = READ_WRITE;
+/// assert!(granted.contains(&READ));
+/// granted.remove(WRITE);
+/// granted.toggle(EXECUTE);
+/// assert_eq!(granted, READ | EXECUTE);
+/// assert_eq!(granted.iter().collect::<Vec<_>>(), vec![READ, EXECUTE]);
+/// ```
+#[proc_macro_derive(MaskId, attributes(id))]
+pub fn mask_id(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "MaskId");
+    handle!(input, operation::bit_ops(&input), operation::mask(&input))
 }
 
 /// Equivalent to `derive(IntoInner, FromInner)`.
-#[proc_macro_derive(Convert)]
+///
+/// Conversions to other identifier types sharing the inner type can be requested with
+/// `#[id(cast_to(OtherId))]`, which implements a `to_other_id` method. This makes intentional
+/// conversions between identifier families visible, instead of going through the inner value.
+/// The target type must implement `From` of the inner type.
+///
+/// Conversely, `#[id(from_id(LegacyId))]` implements `From<LegacyId>`, which eases incremental
+/// renames of identifier types. The inner type must implement `From<LegacyId>`.
+///
+/// Both attributes are supported by [`OpaqueId`](derive.OpaqueId.html) and [`Id`](derive.Id.html)
+/// as well.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Convert)]
+/// struct UserId(u32);
+///
+/// #[derive(Debug, PartialEq, Convert)]
+/// #[id(cast_to(UserId))]
+/// struct LegacyUserId(u32);
+///
+/// #[derive(Debug, PartialEq, Convert)]
+/// #[id(from_id(LegacyUserId))]
+/// struct AccountId(u32);
+///
+/// assert_eq!(LegacyUserId(7).to_user_id(), UserId(7));
+/// assert_eq!(AccountId::from(LegacyUserId(7)), AccountId(7));
+/// ```
+#[proc_macro_derive(Convert, attributes(id))]
 pub fn convert(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    let from = operation::from_inner("Convert", &input);
-    let into = operation::into_inner("Convert", &input);
-    handle!(from, into)
+    let input = id_input!(input, "Convert");
+    let from = operation::from_inner(&input);
+    let into = operation::into_inner(&input);
+    let cast = operation::cast(&input);
+    let from_id = operation::from_id(&input);
+    handle!(input, from, into, cast, from_id)
+}
+
+/// Implements traits for identifiers that are meant to be opaque keys, e.g., database keys,
+/// for which arithmetic makes no sense.
+///
+/// Equivalent to `derive(Convert, FromStr)`, plus `Display` (without `Binary`),
+/// and `PartialEq`, `Eq`, `PartialOrd`, `Ord`, and `Hash` forwarded to the inner type.
+/// For `f32` and `f64` inner types, only `PartialEq` and `PartialOrd` are forwarded.
+/// If the `serde` feature is enabled, [`Serde`](derive.Serde.html) is implemented as well.
+/// Other integrations can be requested as described for [`Id`](derive.Id.html).
+///
+/// With `#[id(opaque)]`, identifiers can still be parsed and deserialized by other crates,
+/// as described in [`FromInner`](derive.FromInner.html).
+#[proc_macro_derive(OpaqueId, attributes(id))]
+pub fn opaque_id(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "OpaqueId");
+    handle!(
+        input,
+        operation::from_inner(&input),
+        operation::into_inner(&input),
+        operation::fmt_display(&input),
+        operation::from_str(&input),
+        operation::compare(&input),
+        operation::cast(&input),
+        operation::from_id(&input),
+        integration::integrations(&input, &[Integration::Serde])
+    )
+}
+
+/// Implements the traits needed to extract identifiers from URL paths in web frameworks,
+/// such as `axum` or `actix-web`: `Display` (without `Binary`), `FromStr`,
+/// and `serde::Deserialize` from a string.
+///
+/// Parsing fails with a `<Name>ParseError` companion type, as with `#[id(parse_error)]`
+/// described in [`FromStr`](derive.FromStr.html). Deserialization fails with the same message.
+///
+/// Requires the `serde` feature.
+#[cfg(feature = "serde")]
+#[proc_macro_derive(PathId, attributes(id))]
+pub fn path_id(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = id_input!(input, "PathId");
+    handle!(
+        input,
+        operation::fmt_display(&input),
+        operation::from_str_with_error(&input),
+        operation::deserialize_str(&input)
+    )
 }
 
 /// Implement all available traits.
-#[proc_macro_derive(Id)]
+///
+/// The traits are chosen based on the inner type: integers get all of them, `f32` and `f64`
+/// get all but `Binary`, and well-known types without arithmetic, such as `String`, `&str`,
+/// `Cow<str>`, `Uuid`, `Ulid`, `bool`, and `char`, get the conversions and `Display`, but no
+/// operators, so that a single derive works for most identifiers. With `#[id(strict)]`,
+/// all operators are implemented regardless of the inner type, and a type without arithmetic
+/// is reported as an error.
+///
+/// ```
+/// # use id_derive::Id;
+/// #[derive(Id, Debug, PartialEq, Clone)]
+/// struct Username(String);
+///
+/// let name = Username::from(String::from("alice"));
+/// assert_eq!(name.to_string(), "alice");
+/// assert_eq!(String::from(name), "alice");
+/// ```
+///
+/// With `#[id(debug_overflow_checks)]`, the generated arithmetic asserts that it does not overflow
+/// in builds with debug assertions, even if overflow checks are disabled with
+/// `overflow-checks = false`. The attribute applies to the individual arithmetic derives as well.
+///
+/// ```should_panic
+/// # use id_derive::Id;
+/// #[derive(Id, Debug, PartialEq, Copy, Clone)]
+/// #[id(debug_overflow_checks)]
+/// struct MyId(u8);
+///
+/// let _ = MyId(255) + 1;
+/// ```
+///
+/// With `#[id(inline = "always")]`, every generated method, in both inherent and trait impls,
+/// is marked `#[inline(always)]`, e.g., to guarantee that conversions and arithmetic vanish
+/// in hot paths. `"never"` marks them `#[inline(never)]` instead, to minimize code size,
+/// and `"default"` marks them `#[inline]`, leaving the decision to the compiler while allowing
+/// inlining across crates. The attribute applies to all derives in this crate.
+///
+/// ```
+/// # use id_derive::Id;
+/// #[derive(Id, Debug, PartialEq, Copy, Clone)]
+/// #[id(inline = "always")]
+/// struct SlotId(u32);
+///
+/// assert_eq!(SlotId(1) + 2, SlotId(3));
+/// ```
+///
+/// With `#[id(no_id_arithmetic)]`, operators taking another identifier as the right-hand side,
+/// such as `Add<Self>` or `SubAssign<Self>`, are left out, and only the variants taking
+/// the inner type remain. Operators with a custom `delta` or output type are still implemented.
+///
+/// ```compile_fail
+/// # use id_derive::Id;
+/// #[derive(Id, Debug, PartialEq, Copy, Clone)]
+/// #[id(no_id_arithmetic)]
+/// struct MyId(u32);
+///
+/// let _ = MyId(1) + MyId(2);
+/// ```
+///
+/// Impls of third-party traits can be requested with `#[id(integrations(...))]`, listing any of
+/// `serde`, `speedy`, `miniserde`, `hash32`, `schemars`, `metrics`, `opentelemetry`, and
+/// `http`. Each integration requires the cargo feature of the same name, and requesting one
+/// whose feature is not enabled is a compile error, rather than silently leaving the impls out.
+/// The integrations are the same as those implemented by the standalone derives, such as
+/// [`Serde`](derive.Serde.html).
+///
+/// With `#[id(shard)]`, a `shard(num_shards)` method maps the identifier to a shard below
+/// `num_shards`, by taking the inner value modulo `num_shards`. With `#[id(shard =
+/// "multiplicative")]`, the inner value is instead multiplied by a large odd constant and
+/// scaled to `num_shards`, which spreads out identifiers sharing a common stride, such as
+/// only even ones. Either way, the inner value is cast to `u64`, keeping the low 64 bits
+/// of wider types. The shard is a `u32` unless `#[id(shard_output = Type)]` sets a type
+/// implementing `From<u32>`.
+///
+/// ```
+/// # use id_derive::Id;
+/// #[derive(Debug, PartialEq)]
+/// struct ShardId(u32);
+///
+/// impl From<u32> for ShardId {
+///     fn from(shard: u32) -> Self {
+///         Self(shard)
+///     }
+/// }
+///
+/// #[derive(Id, Debug, PartialEq, Copy, Clone)]
+/// #[id(shard, shard_output = ShardId)]
+/// struct UserId(u64);
+///
+/// assert_eq!(UserId(10).shard(4), ShardId(2));
+/// ```
+///
+/// With `#[id(from_hash)]`, a `from_hash(&value)` constructor sets the inner value to a hash
+/// of `value`, cast to the inner type, for identifiers derived from content. Unlike hashing
+/// with the randomly seeded `RandomState`, the hash function is pinned: 64-bit FNV-1a by
+/// default, or `FxHash` with `#[id(from_hash = "fx")]`, which is faster on long inputs.
+/// Integers are hashed as little-endian bytes, and `usize` as `u64`, so the same value has
+/// the same identifier on every run and platform, as long as its `Hash` impl does not change.
+/// Neither function resists deliberately constructed collisions.
+///
+/// ```
+/// # use id_derive::Id;
+/// #[derive(Id, Debug, PartialEq, Copy, Clone)]
+/// #[id(from_hash)]
+/// struct TagId(u64);
+///
+/// assert_eq!(TagId::from_hash("rust"), TagId::from_hash(&String::from("rust")));
+/// assert_ne!(TagId::from_hash("rust"), TagId::from_hash("go"));
+/// ```
+///
+/// With `#[id(content_hash = "blake3")]`, which requires the `blake3` feature, a `from_bytes`
+/// constructor sets the inner value to the first bytes of the BLAKE3 digest of the given
+/// bytes, for cache keys and deduplication. Unlike `from_hash`, finding two inputs with
+/// the same identifier takes a brute-force search, but the truncated digest is only as strong
+/// as the inner type is wide: among `k` inputs, the chance of any two colliding is about
+/// `k² / 2^(n + 1)` for an `n`-bit inner type. For `u64`, that is one in a million after about
+/// six million inputs and one in two after about five billion, while `u128` holds up to
+/// about `2^54` inputs at one in a million.
+///
+/// With `#[id(scramble(key = ...))]`, `to_public` maps the identifier to a public value of
+/// the inner type, and `from_public` maps it back. The mapping is a four-round Feistel network
+/// over the inner value, keyed by the given `u64` expression, so sequential identifiers, such
+/// as database keys, can be exposed without revealing their order or how many there are,
+/// and recovered without a lookup table. The inner type must be an unsigned integer of fixed
+/// width, from `u8` to `u128`. The permutation is not a cipher: it hides the order from casual
+/// observers, but someone with many pairs of identifiers and public values may recover it.
+///
+/// ```
+/// # use id_derive::Id;
+/// const PUBLIC_KEY: u64 = 0x5eed_1234_abcd_9876;
+///
+/// #[derive(Id, Debug, PartialEq, Copy, Clone)]
+/// #[id(scramble(key = PUBLIC_KEY))]
+/// struct OrderId(u32);
+///
+/// let public = OrderId(1).to_public();
+/// assert_ne!(public + 1, OrderId(2).to_public());
+/// assert_eq!(OrderId::from_public(public), OrderId(1));
+/// ```
+#[proc_macro_derive(Id, attributes(id))]
 pub fn id(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    let derive_name = "Id";
+    let input = id_input!(input, "Id");
     handle!(
-        operation::from_inner(derive_name, &input),
-        operation::into_inner(derive_name, &input),
-        operation::add_self(derive_name, &input),
-        operation::add_inner(derive_name, &input),
-        operation::add_assign_self(derive_name, &input),
-        operation::add_assign_inner(derive_name, &input),
-        operation::sub_self(derive_name, &input),
-        operation::sub_inner(derive_name, &input),
-        operation::sub_assign_self(derive_name, &input),
-        operation::sub_assign_inner(derive_name, &input),
-        operation::mul_self(derive_name, &input),
-        operation::mul_inner(derive_name, &input),
-        operation::mul_assign_self(derive_name, &input),
-        operation::mul_assign_inner(derive_name, &input),
-        operation::div_self(derive_name, &input),
-        operation::div_inner(derive_name, &input),
-        operation::div_assign_self(derive_name, &input),
-        operation::div_assign_inner(derive_name, &input),
-        operation::display(derive_name, &input)
+        input,
+        operation::from_inner(&input),
+        operation::into_inner(&input),
+        operation::arithmetic(&input),
+        operation::display(&input),
+        operation::cast(&input),
+        operation::from_id(&input),
+        operation::shard(&input),
+        operation::from_hash(&input),
+        operation::content_hash(&input),
+        operation::scramble(&input),
+        integration::integrations(&input, &[])
     )
 }
+
+/// Implements conversions of an enum-backed identifier, i.e., a fieldless enum with an integer
+/// `#[repr(...)]`, whose variants form a closed set of identifiers, such as regions or
+/// built-in roles. `discriminant` returns the discriminant of a variant in a `const` context,
+/// and `TryFrom<u32>` and `TryFrom<u64>` map wire values back to variants, failing with
+/// the generated `Unknown<Name>` error, e.g., `UnknownRegionId`, which holds the rejected
+/// value. As with other companion types, the error can be placed in a module with
+/// `#[id(module = ...)]`. `ALL` holds the variants in declaration order, and `iter` returns
+/// an iterator over them, for exhaustive loops over the closed set.
+///
+/// ```
+/// # use id_derive::EnumId;
+/// use std::convert::TryFrom;
+///
+/// #[derive(EnumId, Debug, PartialEq, Clone, Copy)]
+/// #[repr(u8)]
+/// enum RegionId {
+///     Europe = 1,
+///     Asia = 2,
+/// }
+///
+/// const ASIA: u8 = RegionId::Asia.discriminant();
+/// assert_eq!(ASIA, 2);
+/// assert_eq!(RegionId::try_from(1_u32), Ok(RegionId::Europe));
+/// assert_eq!(RegionId::try_from(300_u64), Err(UnknownRegionId(300)));
+/// assert_eq!(
+///     UnknownRegionId(3).to_string(),
+///     "unknown RegionId discriminant 3"
+/// );
+/// assert_eq!(RegionId::ALL, [RegionId::Europe, RegionId::Asia]);
+/// assert_eq!(RegionId::iter().map(RegionId::discriminant).sum::<u8>(), 3);
+/// ```
+#[proc_macro_derive(EnumId, attributes(id))]
+pub fn enum_id(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let input = enum_id_input!(input, "EnumId");
+    handle!(input, operation::enum_id(&input))
+}
+
+/// Expands to a test module, named after the identifier, e.g., `user_id_laws`, checking
+/// the laws promised by the derives for pairs of sample inner values:
+/// - `convert`: `From<Inner>` and `From<Name>` for the inner type are inverse,
+/// - `display`: `Display` output parses back with `FromStr`,
+/// - `arithmetic`: adding and subtracting an inner value are inverse, unless they overflow,
+/// - `hash`: identifiers are equal if their inner values are, and equal ones hash equally.
+///
+/// All laws are checked by default, and `laws(...)` selects some. The samples default to
+/// a few small integers converted with `From<u8>`, and can be set with `samples(...)` to
+/// any expressions converting into the inner type. The identifier must implement `Debug`
+/// and `PartialEq`, and be declared at module level, as the module uses `super::*`.
+///
+/// ```
+/// # use id_derive::*;
+/// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Id, FromStr)]
+/// pub struct UserId(u32);
+///
+/// id_laws!(UserId(u32));
+///
+/// #[derive(Debug, OpaqueId)]
+/// pub struct Name(String);
+///
+/// id_laws!(Name(String), samples("alice", "bob"), laws(convert, display, hash));
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn id_laws(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as laws::LawsInput);
+    proc_macro::TokenStream::from(laws::laws(&input))
+}
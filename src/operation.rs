@@ -1,314 +1,5034 @@
-use proc_macro2::TokenStream;
-use quote::{quote, quote_spanned};
-use syn::{Data, DeriveInput, Fields, FieldsUnnamed, Ident, Type};
-
-fn implement_operation<F>(name: &Ident, fields: &FieldsUnnamed, operation: F) -> TokenStream
-where
-    F: Fn(&Ident, &Type) -> TokenStream,
-{
-    if fields.unnamed.len() > 1 {
-        quote_spanned! {name.span()=>
-            compile_error!("Only single-field structs supported at the moment");
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{GenericParam, Ident, LitStr, Type};
+
+#[cfg(feature = "itoa")]
+use crate::attr::DisplayTemplate;
+#[cfg(feature = "serde")]
+use crate::attr::SerdeMode;
+use crate::attr::{
+    CheckDigit, DivPolicy, OpOutput, Scramble, ShardHash, Snowflake, StableHash, Validate,
+};
+#[cfg(feature = "serde")]
+use crate::inner::BorrowedStr;
+use crate::inner::{non_numeric, InnerKind, TimeOrdered};
+use crate::input::{EnumIdInput, IdInput, IdItem};
+
+/// Arithmetic operator, along with its compound assignment counterpart.
+pub struct Operator {
+    name: &'static str,
+    method: &'static str,
+    symbol: &'static str,
+    verb: &'static str,
+    overflows: bool,
+}
+
+pub const ADD: Operator = Operator {
+    name: "Add",
+    method: "add",
+    symbol: "+",
+    verb: "add",
+    overflows: true,
+};
+
+pub const SUB: Operator = Operator {
+    name: "Sub",
+    method: "sub",
+    symbol: "-",
+    verb: "subtract",
+    overflows: true,
+};
+
+pub const MUL: Operator = Operator {
+    name: "Mul",
+    method: "mul",
+    symbol: "*",
+    verb: "multiply",
+    overflows: true,
+};
+
+pub const DIV: Operator = Operator {
+    name: "Div",
+    method: "div",
+    symbol: "/",
+    verb: "divide",
+    overflows: true,
+};
+
+pub const BITAND: Operator = Operator {
+    name: "BitAnd",
+    method: "bitand",
+    symbol: "&",
+    verb: "and",
+    overflows: false,
+};
+
+pub const BITOR: Operator = Operator {
+    name: "BitOr",
+    method: "bitor",
+    symbol: "|",
+    verb: "or",
+    overflows: false,
+};
+
+pub const BITXOR: Operator = Operator {
+    name: "BitXor",
+    method: "bitxor",
+    symbol: "^",
+    verb: "xor",
+    overflows: false,
+};
+
+impl Operator {
+    fn name(&self) -> Ident {
+        Ident::new(self.name, Span::call_site())
+    }
+
+    fn method(&self) -> Ident {
+        Ident::new(self.method, Span::call_site())
+    }
+
+    fn assign_name(&self) -> Ident {
+        format_ident!("{}Assign", self.name)
+    }
+
+    fn assign_method(&self) -> Ident {
+        format_ident!("{}_assign", self.method)
+    }
+
+    fn symbol(&self) -> TokenStream {
+        self.symbol.parse().expect("invalid operator symbol")
+    }
+
+    fn assign_symbol(&self) -> TokenStream {
+        format!("{}=", self.symbol)
+            .parse()
+            .expect("invalid operator symbol")
+    }
+
+    /// Returns the custom output of `Op<Self>` set with an option such as `mul_output`.
+    fn custom_output<'a>(&self, input: &'a IdInput) -> Option<&'a OpOutput> {
+        input.config.output(self.method).map(|(_, output)| output)
+    }
+
+    /// Returns the output type of `Op<Self>`, and the expression converting the inner `value`
+    /// resulting from the operation into it.
+    fn self_output(&self, input: &IdInput, value: &TokenStream) -> (TokenStream, TokenStream) {
+        let ty = input.inner_ty;
+        match self.custom_output(input) {
+            None => (quote!(Self), quote!(Self(#value))),
+            Some(OpOutput::Inner) => (quote!(#ty), quote!(#value)),
+            Some(OpOutput::Type(output)) => (
+                quote!(#output),
+                quote!(<#output as ::std::convert::From<#ty>>::from(#value)),
+            ),
+        }
+    }
+
+    fn overflowing_method(&self) -> Ident {
+        format_ident!("overflowing_{}", self.method)
+    }
+
+    fn overflow_message(&self) -> String {
+        format!("attempt to {} with overflow", self.verb)
+    }
+
+    /// Applies the operator to `lhs` and `rhs`, asserting in debug builds that it does not
+    /// overflow if `#[id(debug_overflow_checks)]` is given.
+    fn apply(&self, input: &IdInput, lhs: &TokenStream, rhs: &TokenStream) -> TokenStream {
+        if self.overflows && input.overflow_checks() {
+            debug_checked(
+                lhs,
+                rhs,
+                &self.overflowing_method(),
+                &self.overflow_message(),
+            )
+        } else {
+            let symbol = self.symbol();
+            quote!(#lhs #symbol #rhs)
+        }
+    }
+
+    /// Applies the compound assignment operator to `lhs` and `rhs`.
+    fn apply_assign(&self, input: &IdInput, lhs: &TokenStream, rhs: &TokenStream) -> TokenStream {
+        if self.overflows && input.overflow_checks() {
+            let value = self.apply(input, lhs, rhs);
+            quote!(#lhs = #value)
+        } else {
+            let symbol = self.assign_symbol();
+            quote!(#lhs #symbol #rhs)
         }
-    } else {
-        let field = &fields.unnamed.first().unwrap();
-        let field_ty = &field.ty;
-        operation(name, field_ty)
-    }
-}
-
-fn single_derive<F>(
-    derive_name: &'static str,
-    input: &DeriveInput,
-    operation: F,
-) -> syn::Result<TokenStream>
-where
-    F: Fn(&Ident, &Type) -> TokenStream,
-{
-    let name = &input.ident;
-    match &input.data {
-        Data::Struct(data) => match &data.fields {
-            Fields::Unnamed(fields) => Ok(implement_operation(name, fields, operation)),
-            Fields::Unit => Err(syn::Error::new(
-                name.span(),
-                format!("Unit struct cannot derive {}", derive_name),
-            )),
-            _ => Err(syn::Error::new(
-                name.span(),
-                format!(
-                    "Deriving from {} by a struct with named fields is not yet implemented",
-                    derive_name
-                ),
-            )),
-        },
-        Data::Enum(data) => Err(syn::Error::new(
-            data.enum_token.span,
-            format!("Cannot derive {} for enum, expected struct.", derive_name),
-        )),
-        Data::Union(data) => Err(syn::Error::new(
-            data.union_token.span,
-            format!("Cannot derive {} for union, expected struct.", derive_name),
-        )),
     }
 }
 
-pub fn into_inner(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
-        quote! {
-            impl ::std::convert::From<#name> for #ty {
-                fn from(inner: #name) -> Self {
-                    inner.0
-                }
+/// Calls the `overflowing` method on `lhs` and `rhs`, and `debug_assert!`s that no overflow
+/// happened. This catches overflows in debug builds even if overflow checks are disabled.
+fn debug_checked(
+    lhs: &TokenStream,
+    rhs: &TokenStream,
+    overflowing: &impl quote::ToTokens,
+    message: &impl quote::ToTokens,
+) -> TokenStream {
+    quote! {{
+        let (value, overflow) = #lhs.#overflowing(#rhs);
+        ::std::debug_assert!(!overflow, #message);
+        value
+    }}
+}
+
+/// Implements `Op<Self>` for the operator `op`, with `Self` as the output unless customized
+/// with an option such as `mul_output`.
+pub fn self_op(input: &IdInput, op: &Operator) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let (op_name, method) = (op.name(), op.method());
+    let value = op.apply(input, &quote!(self.0), &quote!(rhs.0));
+    let (output, value) = op.self_output(input, &value);
+    Ok(quote! {
+        impl #impl_generics ::std::ops::#op_name for #name #ty_generics #where_clause {
+            type Output = #output;
+            fn #method(self, rhs: Self) -> Self::Output {
+                #value
             }
         }
     })
 }
 
-pub fn from_inner(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
-        quote! {
-            impl ::std::convert::From<#ty> for #name {
-                fn from(inner: #ty) -> Self {
-                    Self(inner)
-                }
+/// Implements `Op<T>` and `Op<&T>` for the operator `op`, where `T` is the inner type.
+pub fn inner_op(input: &IdInput, op: &Operator) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (op_name, method) = (op.name(), op.method());
+    let value = op.apply(input, &quote!(self.0), &quote!(rhs));
+    let by_ref = inner_ref_op(input, op);
+    Ok(quote! {
+        impl #impl_generics ::std::ops::#op_name<#ty> for #name #ty_generics #where_clause {
+            type Output = Self;
+            fn #method(self, rhs: #ty) -> Self::Output {
+                Self(#value)
             }
         }
+        #by_ref
     })
 }
 
-pub fn mul_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
-        quote! {
-            impl ::std::ops::Mul for #name {
-                type Output = Self;
-                fn mul(self, rhs: Self) -> Self::Output {
-                    Self(self.0 * rhs.0)
-                }
+/// Implements `OpAssign<Self>` for the operator `op`.
+pub fn assign_self_op(input: &IdInput, op: &Operator) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    check_assign_policy(input, op)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let (op_name, method) = (op.assign_name(), op.assign_method());
+    let assign = op.apply_assign(input, &quote!(self.0), &quote!(rhs.0));
+    Ok(quote! {
+        impl #impl_generics ::std::ops::#op_name for #name #ty_generics #where_clause {
+            fn #method(&mut self, rhs: Self) {
+                #assign;
             }
         }
     })
 }
 
-pub fn mul_inner(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
-        quote! {
-            impl ::std::ops::Mul<#ty> for #name {
-                type Output = Self;
-                fn mul(self, rhs: #ty) -> Self::Output {
-                    Self(self.0 * rhs)
-                }
+/// Implements `OpAssign<T>` and `OpAssign<&T>` for the operator `op`, where `T` is the inner
+/// type.
+pub fn assign_inner_op(input: &IdInput, op: &Operator) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    check_assign_policy(input, op)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (op_name, method) = (op.assign_name(), op.assign_method());
+    let assign = op.apply_assign(input, &quote!(self.0), &quote!(rhs));
+    let by_ref = assign_inner_ref_op(input, op);
+    Ok(quote! {
+        impl #impl_generics ::std::ops::#op_name<#ty> for #name #ty_generics #where_clause {
+            fn #method(&mut self, rhs: #ty) {
+                #assign;
             }
         }
+        #by_ref
     })
 }
 
-pub fn mul_assign_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
-        quote! {
-            impl ::std::ops::MulAssign for #name {
-                fn mul_assign(&mut self, rhs: Self) {
-                    self.0 *= rhs.0;
-                }
+/// Implements `BitAnd`, `BitOr`, and `BitXor` of identifiers, their compound assignment
+/// counterparts, and `Not`.
+pub fn bit_ops(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    if let InnerKind::Float = input.inner_kind {
+        return Err(syn::Error::new_spanned(
+            input.inner_ty,
+            "BitOps requires an integer inner type",
+        ));
+    }
+    let mut tokens = TokenStream::new();
+    for op in &[BITAND, BITOR, BITXOR] {
+        tokens.extend(self_op(input, op)?);
+        tokens.extend(assign_self_op(input, op)?);
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    tokens.extend(quote! {
+        impl #impl_generics ::std::ops::Not for #name #ty_generics #where_clause {
+            type Output = Self;
+            fn not(self) -> Self::Output {
+                Self(!self.0)
+            }
+        }
+    });
+    Ok(tokens)
+}
+
+/// Implements set operations on identifiers whose bits are flags: `contains`, `insert`,
+/// `remove`, `toggle`, the const `union`, `intersection`, and `difference`, and `iter`,
+/// which returns the set bits as separate identifiers.
+pub fn mask(input: &IdInput) -> syn::Result<TokenStream> {
+    let ty = input.inner_ty;
+    let unsigned = matches!(
+        ty,
+        Type::Path(path)
+            if ["u8", "u16", "u32", "u64", "u128", "usize"]
+                .iter()
+                .any(|primitive| path.path.is_ident(primitive))
+    );
+    if !unsigned {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "MaskId requires an unsigned integer inner type, such as `u32` or `u64`",
+        ));
+    }
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns `true` if no bits are set.
+            #vis const fn is_empty(&self) -> ::std::primitive::bool {
+                self.0 == 0
+            }
+
+            /// Returns `true` if all bits set in `other` are set in `self`.
+            #vis const fn contains(&self, other: &Self) -> ::std::primitive::bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Sets the bits set in `other`.
+            #vis fn insert(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+
+            /// Clears the bits set in `other`.
+            #vis fn remove(&mut self, other: Self) {
+                self.0 &= !other.0;
+            }
+
+            /// Flips the bits set in `other`.
+            #vis fn toggle(&mut self, other: Self) {
+                self.0 ^= other.0;
+            }
+
+            /// Returns the bits set in either `self` or `other`.
+            #[must_use]
+            #vis const fn union(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+
+            /// Returns the bits set in both `self` and `other`.
+            #[must_use]
+            #vis const fn intersection(self, other: Self) -> Self {
+                Self(self.0 & other.0)
+            }
+
+            /// Returns the bits set in `self` but not in `other`.
+            #[must_use]
+            #vis const fn difference(self, other: Self) -> Self {
+                Self(self.0 & !other.0)
+            }
+
+            /// Returns an iterator over the set bits, from the least significant, each as
+            /// an identifier with that single bit set.
+            #vis fn iter(&self) -> impl ::std::iter::Iterator<Item = Self> {
+                let mut rest = self.0;
+                ::std::iter::from_fn(move || {
+                    if rest == 0 {
+                        return ::std::option::Option::None;
+                    }
+                    let bit = rest & rest.wrapping_neg();
+                    rest ^= bit;
+                    ::std::option::Option::Some(Self(bit))
+                })
             }
         }
     })
 }
 
-pub fn mul_assign_inner(
-    derive_name: &'static str,
-    input: &DeriveInput,
-) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
+/// Implements `Add<Name>` and `Mul<Name>` for the inner type, with the identifier as
+/// the output, so that the inner value can be the left-hand side of commutative operators.
+pub fn commutative_inner(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let impls = [ADD, MUL].iter().map(|op| {
+        let (op_name, method) = (op.name(), op.method());
+        let value = op.apply(input, &quote!(self), &quote!(rhs.0));
         quote! {
-            impl ::std::ops::MulAssign<#ty> for #name {
-                fn mul_assign(&mut self, rhs: #ty) {
-                    self.0 *= rhs;
+            impl #impl_generics ::std::ops::#op_name<#name #ty_generics> for #ty #where_clause {
+                type Output = #name #ty_generics;
+                fn #method(self, rhs: #name #ty_generics) -> Self::Output {
+                    #name(#value)
                 }
             }
         }
-    })
+    });
+    Ok(quote!(#(#impls)*))
 }
 
-pub fn div_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
-        quote! {
-            impl ::std::ops::Div for #name {
-                type Output = Self;
-                fn div(self, rhs: Self) -> Self::Output {
-                    Self(self.0 / rhs.0)
+/// Returns the generics of impls taking a reference to the inner type, which add the lifetime
+/// `'rhs` of the reference and require the inner type to be `Clone`.
+fn inner_ref_generics(input: &IdInput) -> syn::Generics {
+    let ty = input.inner_ty;
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, syn::parse_quote!('rhs));
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ty: ::std::clone::Clone));
+    generics
+}
+
+/// Implements `Op<&T>` for the operator `op`, where `T` is the inner type, by cloning
+/// the right-hand side and delegating to `Op<T>`.
+fn inner_ref_op(input: &IdInput, op: &Operator) -> TokenStream {
+    let generics = inner_ref_generics(input);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (op_name, method) = (op.name(), op.method());
+    quote! {
+        impl #impl_generics ::std::ops::#op_name<&'rhs #ty> for #name #ty_generics #where_clause {
+            type Output = <Self as ::std::ops::#op_name<#ty>>::Output;
+            fn #method(self, rhs: &'rhs #ty) -> Self::Output {
+                ::std::ops::#op_name::#method(self, ::std::clone::Clone::clone(rhs))
+            }
+        }
+    }
+}
+
+/// Implements `OpAssign<&T>` for the operator `op`, where `T` is the inner type, by cloning
+/// the right-hand side and delegating to `OpAssign<T>`.
+fn assign_inner_ref_op(input: &IdInput, op: &Operator) -> TokenStream {
+    let generics = inner_ref_generics(input);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (op_name, method) = (op.assign_name(), op.assign_method());
+    quote! {
+        impl #impl_generics ::std::ops::#op_name<&'rhs #ty> for #name #ty_generics #where_clause {
+            fn #method(&mut self, rhs: &'rhs #ty) {
+                ::std::ops::#op_name::#method(self, ::std::clone::Clone::clone(rhs));
+            }
+        }
+    }
+}
+
+/// Reports an error if the `OpAssign` impl of `op` cannot be generated for the input,
+/// which is the case for `DivAssign` with the `checked` division policy.
+fn check_assign_policy(input: &IdInput, op: &Operator) -> syn::Result<()> {
+    if op.name == DIV.name && input.config.div == DivPolicy::Checked {
+        return Err(syn::Error::new(
+            input.config.div_span.unwrap_or_else(Span::call_site),
+            "DivAssign cannot be implemented with `div = \"checked\"`, \
+             because a checked division may fail",
+        ));
+    }
+    Ok(())
+}
+
+/// Implements `Div<Self>` following the division policy, and a `checked_div` method.
+pub fn div_self(input: &IdInput) -> syn::Result<TokenStream> {
+    checked_div(input, false)
+}
+
+/// Implements `Div<T>` following the division policy, where `T` is the inner type,
+/// and a `checked_div_inner` method.
+pub fn div_inner(input: &IdInput) -> syn::Result<TokenStream> {
+    checked_div(input, true)
+}
+
+fn checked_div(input: &IdInput, inner: bool) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (rhs_ty, rhs, method) = if inner {
+        (quote!(#ty), quote!(rhs), quote!(checked_div_inner))
+    } else {
+        (
+            quote!(#name #ty_generics),
+            quote!(rhs.0),
+            quote!(checked_div),
+        )
+    };
+    let checked = match &input.inner_kind {
+        InnerKind::Wrapper { wrapper, .. } => {
+            quote!(self.0 .0.checked_div((#rhs).0).map(|value| Self(#wrapper(value))))
+        }
+        InnerKind::Float => {
+            return match input.config.div {
+                DivPolicy::Panic if inner => inner_op(input, &DIV),
+                DivPolicy::Panic => self_op(input, &DIV),
+                DivPolicy::Checked => Err(syn::Error::new_spanned(
+                    input.inner_ty,
+                    "`div = \"checked\"` is not supported for floating-point inner types",
+                )),
+            };
+        }
+        InnerKind::Other => quote!(self.0.checked_div(#rhs).map(Self)),
+    };
+    let div = match input.config.div {
+        DivPolicy::Panic if inner => inner_op(input, &DIV)?,
+        DivPolicy::Panic => self_op(input, &DIV)?,
+        DivPolicy::Checked => {
+            let (output, value) = if inner {
+                (quote!(Self), quote!(id))
+            } else {
+                DIV.self_output(input, &quote!(id.0))
+            };
+            let by_ref = if inner {
+                inner_ref_op(input, &DIV)
+            } else {
+                TokenStream::new()
+            };
+            quote! {
+                impl #impl_generics ::std::ops::Div<#rhs_ty> for #name #ty_generics #where_clause {
+                    type Output = ::std::option::Option<#output>;
+                    fn div(self, rhs: #rhs_ty) -> Self::Output {
+                        self.#method(rhs).map(|id| #value)
+                    }
                 }
+                #by_ref
+            }
+        }
+    };
+    Ok(quote! {
+        #div
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Divides by `rhs`, returning `None` if `rhs` is zero.
+            #vis fn #method(self, rhs: #rhs_ty) -> ::std::option::Option<Self> {
+                #checked
             }
         }
     })
 }
 
-pub fn div_inner(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
+/// Implements all operators with all their variants, as done by the `Id` umbrella derive.
+/// With the `checked` division policy, `DivAssign` is left out. With `no_id_arithmetic`,
+/// operators taking another identifier are left out, unless their output is customized.
+/// Unless `strict` is given, no operators are implemented for well-known inner types
+/// without arithmetic, such as `String` or `Uuid`.
+pub fn arithmetic(input: &IdInput) -> syn::Result<TokenStream> {
+    if !input.config.strict && non_numeric(input.inner_ty).is_some() {
+        return Ok(TokenStream::new());
+    }
+    let id_arithmetic = !input.config.no_id_arithmetic;
+    let mut impls = TokenStream::new();
+    for op in &[ADD, SUB, MUL] {
+        if op.name == SUB.name && input.config.delta.is_some() {
+            impls.extend(sub_self(input)?);
+        } else if id_arithmetic || op.custom_output(input).is_some() {
+            impls.extend(self_op(input, op)?);
+        }
+        impls.extend(inner_op(input, op)?);
+        if id_arithmetic {
+            impls.extend(assign_self_op(input, op)?);
+        }
+        impls.extend(assign_inner_op(input, op)?);
+    }
+    impls.extend(add_delta(input)?);
+    if id_arithmetic || DIV.custom_output(input).is_some() {
+        impls.extend(div_self(input)?);
+    }
+    impls.extend(div_inner(input)?);
+    if input.config.div == DivPolicy::Panic {
+        if id_arithmetic {
+            impls.extend(assign_self_op(input, &DIV)?);
+        }
+        impls.extend(assign_inner_op(input, &DIV)?);
+    }
+    Ok(impls)
+}
+
+/// Implements `const_add`, `const_sub`, `const_mul`, and `const_div` methods, which can be used
+/// in const contexts, as opposed to the operator traits.
+pub fn const_ops(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let methods = [ADD, SUB, MUL, DIV].iter().map(|op| {
+        let method = format_ident!("const_{}", op.method);
+        let value = match &input.inner_kind {
+            InnerKind::Wrapper {
+                wrapper,
+                saturating,
+                ..
+            } => {
+                let kind = if *saturating {
+                    "saturating"
+                } else {
+                    "wrapping"
+                };
+                let op_method = format_ident!("{}_{}", kind, op.method);
+                quote!(#wrapper(self.0 .0.#op_method(rhs.0 .0)))
+            }
+            InnerKind::Float | InnerKind::Other => op.apply(input, &quote!(self.0), &quote!(rhs.0)),
+        };
+        let doc = format!(
+            "Equivalent to `{}`, but usable in const contexts.",
+            op.method
+        );
         quote! {
-            impl ::std::ops::Div<#ty> for #name {
-                type Output = Self;
-                fn div(self, rhs: #ty) -> Self::Output {
-                    Self(self.0 / rhs)
-                }
+            #[doc = #doc]
+            #vis const fn #method(self, rhs: Self) -> Self {
+                Self(#value)
             }
         }
+    });
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
     })
 }
 
-pub fn div_assign_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
-        quote! {
-            impl ::std::ops::DivAssign for #name {
-                fn div_assign(&mut self, rhs: Self) {
-                    self.0 /= rhs.0;
+/// Implements `From<Self>` for the inner type, and for the wrapped integer if the inner type
+/// is `Wrapping` or `Saturating`.
+pub fn into_inner(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let primitive = match &input.inner_kind {
+        InnerKind::Wrapper { primitive, .. } => quote! {
+            impl #impl_generics ::std::convert::From<#name #ty_generics> for #primitive #where_clause {
+                fn from(inner: #name #ty_generics) -> Self {
+                    inner.0 .0
                 }
             }
+        },
+        InnerKind::Float | InnerKind::Other => TokenStream::new(),
+    };
+    Ok(quote! {
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for #ty #where_clause {
+            fn from(inner: #name #ty_generics) -> Self {
+                inner.0
+            }
         }
+        #primitive
     })
 }
 
-pub fn div_assign_inner(
-    derive_name: &'static str,
-    input: &DeriveInput,
-) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
+/// Implements `From` of the inner type, and of the wrapped integer if the inner type
+/// is `Wrapping` or `Saturating`, as well as `from_raw` and `into_raw` with `#[id(ffi)]`,
+/// and the deprecated alias requested with `#[id(renamed_from = "...")]`.
+pub fn from_inner(input: &IdInput) -> syn::Result<TokenStream> {
+    let name = input.ident;
+    if input.config.ffi && !input.transparent {
+        return Err(syn::Error::new(
+            name.span(),
+            format!(
+                "`#[id(ffi)]` requires `#[repr(transparent)]`, so that {name} has the same ABI \
+                 as the inner type"
+            ),
+        ));
+    }
+    let conversions = if input.config.opaque {
+        raw_conversions(input, &input.unchecked_vis(&input.item_vis()))
+    } else {
+        inner_conversions(input)
+    };
+    let renamed = input.config.renamed_from.as_ref().map(|old| {
+        let vis = input.vis;
+        let mut generics = input.generics.clone();
+        generics.where_clause = None;
+        for param in &mut generics.params {
+            match param {
+                GenericParam::Type(param) => param.bounds.clear(),
+                GenericParam::Lifetime(param) => param.bounds.clear(),
+                GenericParam::Const(_) => {}
+            }
+        }
+        let (_, ty_generics, _) = input.generics.split_for_impl();
+        let doc = format!("Former name of [`{name}`].");
+        let note = format!("renamed to `{name}`");
         quote! {
-            impl ::std::ops::DivAssign<#ty> for #name {
-                fn div_assign(&mut self, rhs: #ty) {
-                    self.0 /= rhs;
+            #[doc = #doc]
+            #[deprecated(note = #note)]
+            #[allow(dead_code)]
+            #vis type #old #generics = #name #ty_generics;
+        }
+    });
+    Ok(quote! {
+        #conversions
+        #renamed
+    })
+}
+
+/// Implements `From` of the inner type, or `TryFrom` with `#[id(validate(...))]`, together
+/// with `from_raw` and `into_raw` with `#[id(ffi)]`.
+fn inner_conversions(input: &IdInput) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let raw = if input.config.ffi {
+        raw_conversions(input, &input.item_vis())
+    } else {
+        TokenStream::new()
+    };
+    let primitive = match &input.inner_kind {
+        InnerKind::Wrapper {
+            wrapper, primitive, ..
+        } => quote! {
+            impl #impl_generics ::std::convert::From<#primitive> for #name #ty_generics #where_clause {
+                fn from(inner: #primitive) -> Self {
+                    Self(#wrapper(inner))
                 }
             }
+        },
+        InnerKind::Float | InnerKind::Other => TokenStream::new(),
+    };
+    if let Some(Validate { function, error }) = &input.config.validate {
+        return quote! {
+            impl #impl_generics ::std::convert::TryFrom<#ty> for #name #ty_generics #where_clause {
+                type Error = #error;
+                fn try_from(inner: #ty) -> ::std::result::Result<Self, Self::Error> {
+                    #function(&inner)?;
+                    ::std::result::Result::Ok(Self(inner))
+                }
+            }
+            #raw
+        };
+    }
+    quote! {
+        impl #impl_generics ::std::convert::From<#ty> for #name #ty_generics #where_clause {
+            fn from(inner: #ty) -> Self {
+                Self(inner)
+            }
         }
-    })
+        #primitive
+        #raw
+    }
+}
+
+/// Implements `from_raw` and `into_raw` with the given visibility, which are `const` with
+/// `#[id(ffi)]`.
+fn raw_conversions(input: &IdInput, vis: &TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let constness = if input.config.ffi {
+        quote!(const)
+    } else {
+        TokenStream::new()
+    };
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Creates an identifier from its raw inner value.
+            #vis #constness fn from_raw(inner: #ty) -> Self {
+                Self(inner)
+            }
+
+            /// Returns the raw inner value of the identifier.
+            #vis #constness fn into_raw(self) -> #ty {
+                self.0
+            }
+        }
+    }
 }
 
-pub fn add_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
+/// Implements a `to_<snake_case_name>` method for each type listed in `#[id(cast_to(...))]`,
+/// converting through the inner value.
+pub fn cast(input: &IdInput) -> syn::Result<TokenStream> {
+    if input.config.cast_to.is_empty() {
+        return Ok(TokenStream::new());
+    }
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let methods = input.config.cast_to.iter().map(|target| {
+        let target_name = &target
+            .segments
+            .last()
+            .expect("parsed path has at least one segment")
+            .ident;
+        let method = format_ident!(
+            "to_{}",
+            snake_case(&target_name.to_string()),
+            span = target_name.span()
+        );
+        let doc = format!("Converts into `{target_name}` with the same inner value.");
         quote! {
-            impl ::std::ops::Add for #name {
-                type Output = Self;
-                fn add(self, rhs: Self) -> Self::Output {
-                    Self(self.0 + rhs.0)
-                }
+            #[doc = #doc]
+            #vis fn #method(self) -> #target {
+                ::std::convert::From::from(self.0)
             }
         }
+    });
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
     })
 }
 
-pub fn add_inner(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
+/// Implements `From` for each identifier type listed in `#[id(from_id(...))]`,
+/// converting through the inner value.
+pub fn from_id(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let impls = input.config.from_id.iter().map(|source| {
         quote! {
-            impl ::std::ops::Add<#ty> for #name {
-                type Output = Self;
-                fn add(self, rhs: #ty) -> Self::Output {
-                    Self(self.0 + rhs)
+            impl #impl_generics ::std::convert::From<#source> for #name #ty_generics #where_clause {
+                fn from(id: #source) -> Self {
+                    Self(<#ty as ::std::convert::From<#source>>::from(id))
                 }
             }
         }
-    })
+    });
+    Ok(quote!(#(#impls)*))
 }
 
-pub fn add_assign_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
-        quote! {
-            impl ::std::ops::AddAssign for #name {
-                fn add_assign(&mut self, rhs: Self) {
-                    self.0 += rhs.0
+/// Implements `from_ref`, `from_mut`, and `as_inner_ref`, casting between references to the
+/// inner type and to the identifier, as well as `wrap_slice` and `wrap_vec` and their
+/// counterparts, converting slices and vectors in place, which requires `#[repr(transparent)]`.
+pub fn transparent(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    if !input.transparent {
+        return Err(syn::Error::new(
+            name.span(),
+            format!(
+                "Deriving {} requires `#[repr(transparent)]`, so that {name} has the same layout \
+                 as the inner type",
+                input.derive_name
+            ),
+        ));
+    }
+    let slice = transparent_slice(input);
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Views a reference to an inner value as a reference to an identifier, without copying.
+            #unchecked_vis fn from_ref(inner: &#ty) -> &Self {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so both have
+                // the same layout.
+                unsafe { &*(inner as *const #ty).cast::<Self>() }
+            }
+
+            /// Views a mutable reference to an inner value as a mutable reference to an
+            /// identifier, without copying.
+            #unchecked_vis fn from_mut(inner: &mut #ty) -> &mut Self {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so both have
+                // the same layout.
+                unsafe { &mut *(inner as *mut #ty).cast::<Self>() }
+            }
+
+            /// Returns a reference to the inner value.
+            #vis fn as_inner_ref(&self) -> &#ty {
+                &self.0
+            }
+
+            /// Views a slice of inner values as a slice of identifiers, without copying.
+            #unchecked_vis fn wrap_slice(inner: &[#ty]) -> &[Self] {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so slices
+                // of both have the same layout.
+                unsafe { ::std::slice::from_raw_parts(inner.as_ptr().cast::<Self>(), inner.len()) }
+            }
+
+            /// Views a mutable slice of inner values as a mutable slice of identifiers,
+            /// without copying.
+            #unchecked_vis fn wrap_slice_mut(inner: &mut [#ty]) -> &mut [Self] {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so slices
+                // of both have the same layout.
+                unsafe {
+                    ::std::slice::from_raw_parts_mut(inner.as_mut_ptr().cast::<Self>(), inner.len())
+                }
+            }
+
+            /// Views a slice of identifiers as a slice of inner values, without copying.
+            #vis fn unwrap_slice(ids: &[Self]) -> &[#ty] {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so slices
+                // of both have the same layout.
+                unsafe { ::std::slice::from_raw_parts(ids.as_ptr().cast::<#ty>(), ids.len()) }
+            }
+
+            /// Views a mutable slice of identifiers as a mutable slice of inner values,
+            /// without copying.
+            #unchecked_vis fn unwrap_slice_mut(ids: &mut [Self]) -> &mut [#ty] {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so slices
+                // of both have the same layout.
+                unsafe {
+                    ::std::slice::from_raw_parts_mut(ids.as_mut_ptr().cast::<#ty>(), ids.len())
+                }
+            }
+
+            /// Converts a vector of inner values into a vector of identifiers, reusing
+            /// the allocation.
+            #unchecked_vis fn wrap_vec(inner: ::std::vec::Vec<#ty>) -> ::std::vec::Vec<Self> {
+                let mut inner = ::std::mem::ManuallyDrop::new(inner);
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so both have
+                // the same size and alignment, and the allocation can be taken over as is.
+                unsafe {
+                    ::std::vec::Vec::from_raw_parts(
+                        inner.as_mut_ptr().cast::<Self>(),
+                        inner.len(),
+                        inner.capacity(),
+                    )
+                }
+            }
+
+            /// Converts a vector of identifiers into a vector of inner values, reusing
+            /// the allocation.
+            #vis fn unwrap_vec(ids: ::std::vec::Vec<Self>) -> ::std::vec::Vec<#ty> {
+                let mut ids = ::std::mem::ManuallyDrop::new(ids);
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so both have
+                // the same size and alignment, and the allocation can be taken over as is.
+                unsafe {
+                    ::std::vec::Vec::from_raw_parts(
+                        ids.as_mut_ptr().cast::<#ty>(),
+                        ids.len(),
+                        ids.capacity(),
+                    )
                 }
             }
         }
+
+        #slice
     })
 }
 
-pub fn add_assign_inner(
-    derive_name: &'static str,
-    input: &DeriveInput,
-) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
-        quote! {
-            impl ::std::ops::AddAssign<#ty> for #name {
-                fn add_assign(&mut self, rhs: #ty) {
-                    self.0 += rhs
-                }
+/// Generates the `<Name>Slice` companion type, an unsized `#[repr(transparent)]` wrapper of
+/// `[Name]`, which views buffers of inner values as typed slices of identifiers.
+fn transparent_slice(input: &IdInput) -> TokenStream {
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let generics = input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (vis, companion_vis) = input.companion_item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let slice = input.companion_name("", "Slice");
+    let doc = format!(
+        "A slice of [`{name}`] identifiers, which can view a buffer of inner values in place."
+    );
+    let slice_derive = companion_derive(input, &[]);
+    let mut iter_generics = generics.clone();
+    iter_generics.params.insert(0, syn::parse_quote!('__slice));
+    let (iter_impl_generics, _, _) = iter_generics.split_for_impl();
+    let ids = quote!([#name #ty_generics]);
+    input.in_companion_module(quote! {
+        #[doc = #doc]
+        #[repr(transparent)]
+        #slice_derive
+        #companion_vis struct #slice #generics (#ids) #where_clause;
+
+        impl #impl_generics #slice #ty_generics #where_clause {
+            /// Views a slice of inner values as a slice of identifiers, without copying.
+            #unchecked_vis fn from_raw(raw: &[#ty]) -> &Self {
+                Self::from_ids(#name::wrap_slice(raw))
+            }
+
+            /// Views a mutable slice of inner values as a mutable slice of identifiers,
+            /// without copying.
+            #unchecked_vis fn from_raw_mut(raw: &mut [#ty]) -> &mut Self {
+                Self::from_ids_mut(#name::wrap_slice_mut(raw))
+            }
+
+            /// Views a slice of identifiers as this type, without copying.
+            #vis fn from_ids(ids: &#ids) -> &Self {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so pointers
+                // to both have the same metadata, and the slices the same layout.
+                unsafe { &*(ids as *const #ids as *const Self) }
+            }
+
+            /// Views a mutable slice of identifiers as this type, without copying.
+            #vis fn from_ids_mut(ids: &mut #ids) -> &mut Self {
+                // SAFETY: `Self` is `#[repr(transparent)]` over its only field, so pointers
+                // to both have the same metadata, and the slices the same layout.
+                unsafe { &mut *(ids as *mut #ids as *mut Self) }
+            }
+
+            /// Returns the identifiers as a slice.
+            #vis fn as_ids(&self) -> &#ids {
+                &self.0
+            }
+
+            /// Returns the inner values of the identifiers as a slice, without copying.
+            #vis fn as_raw(&self) -> &[#ty] {
+                #name::unwrap_slice(&self.0)
+            }
+        }
+
+        impl #impl_generics ::std::ops::Deref for #slice #ty_generics #where_clause {
+            type Target = #ids;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl #impl_generics ::std::ops::DerefMut for #slice #ty_generics #where_clause {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl #impl_generics ::std::convert::AsRef<#ids> for #slice #ty_generics #where_clause {
+            fn as_ref(&self) -> &#ids {
+                &self.0
+            }
+        }
+
+        impl #iter_impl_generics ::std::iter::IntoIterator for &'__slice #slice #ty_generics
+        #where_clause
+        {
+            type Item = &'__slice #name #ty_generics;
+            type IntoIter = ::std::slice::Iter<'__slice, #name #ty_generics>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
             }
         }
     })
 }
 
-pub fn sub_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
-        quote! {
-            impl ::std::ops::Sub for #name {
-                type Output = Self;
-                fn sub(self, rhs: Self) -> Self::Output {
-                    Self(self.0 - rhs.0)
-                }
+/// Returns the `#[derive(...)]` attribute applying `#[id(companion_derive(...))]` to a companion
+/// type, leaving out the traits in `implemented`, which the companion type already implements.
+fn companion_derive(input: &impl IdItem, implemented: &[&str]) -> TokenStream {
+    let derives: Vec<_> = input
+        .config()
+        .companion_derive
+        .iter()
+        .filter(|path| {
+            path.segments
+                .last()
+                .is_none_or(|segment| !implemented.contains(&segment.ident.to_string().as_str()))
+        })
+        .collect();
+    if derives.is_empty() {
+        TokenStream::new()
+    } else {
+        quote!(#[derive(#(#derives),*)])
+    }
+}
+
+/// Converts a `CamelCase` type name to `snake_case`, keeping acronyms together,
+/// e.g., `HTTPRequestId` becomes `http_request_id`.
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (idx, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && idx > 0 {
+            let prev = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                snake.push('_');
             }
         }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+pub fn add_self(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let add = self_op(input, &ADD)?;
+    let add_delta = add_delta(input)?;
+    Ok(quote! {
+        #add
+        #add_delta
     })
 }
 
-pub fn sub_inner(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
-        quote! {
-            impl ::std::ops::Sub<#ty> for #name {
-                type Output = Self;
-                fn sub(self, rhs: #ty) -> Self::Output {
-                    Self(self.0 - rhs)
+/// Implements `Add<T>`, where `T` is the `delta` type, if one is configured.
+fn add_delta(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let forward = delta_step(input, &ADD);
+    let backward = delta_step(input, &SUB);
+    Ok(input
+        .config
+        .delta
+        .as_ref()
+        .map_or_else(TokenStream::new, |delta| {
+            quote! {
+                impl #impl_generics ::std::ops::Add<#delta> for #name #ty_generics #where_clause {
+                    type Output = Self;
+                    fn add(self, rhs: #delta) -> Self::Output {
+                        let magnitude = rhs.0.unsigned_abs();
+                        if rhs.0 >= 0 {
+                            Self(#forward)
+                        } else {
+                            Self(#backward)
+                        }
+                    }
                 }
             }
+        }))
+}
+
+/// Applies `op` to `self.0` and `magnitude`, the absolute value of an offset, converted to
+/// the inner type. A magnitude just past the range of the inner type, such as that of
+/// `i64::MIN` for `i64` identifiers, is applied in two steps, since the result may still fit.
+fn delta_step(input: &IdInput, op: &Operator) -> TokenStream {
+    let ty = input.inner_ty;
+    let step = op.apply(input, &quote!(self.0), &quote!(magnitude));
+    let last = op.apply(input, &quote!(value), &quote!(1));
+    quote! {
+        if let ::std::result::Result::Ok(magnitude) =
+            <#ty as ::std::convert::TryFrom<_>>::try_from(magnitude)
+        {
+            #step
+        } else {
+            let magnitude = <#ty as ::std::convert::TryFrom<_>>::try_from(magnitude - 1)
+                .expect("offset out of range of the identifier type");
+            let value = #step;
+            #last
         }
-    })
+    }
 }
 
-pub fn sub_assign_self(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
+pub fn sub_self(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let config = &input.config;
+    Ok(if let Some(delta) = &config.delta {
+        let forward = delta_step(input, &ADD);
+        let backward = delta_step(input, &SUB);
         quote! {
-            impl ::std::ops::SubAssign for #name {
-                fn sub_assign(&mut self, rhs: Self) {
-                    self.0 -= rhs.0
+            impl #impl_generics ::std::ops::Sub for #name #ty_generics #where_clause {
+                type Output = #delta;
+                fn sub(self, rhs: Self) -> Self::Output {
+                    let magnitude = self.0.abs_diff(rhs.0);
+                    if self.0 >= rhs.0 {
+                        #delta(::std::convert::TryFrom::try_from(magnitude)
+                            .expect("difference out of range of the offset type"))
+                    } else {
+                        // Negating `magnitude - 1` first reaches the minimum of the offset type.
+                        let mut delta = #delta(::std::convert::TryFrom::try_from(magnitude - 1)
+                            .expect("difference out of range of the offset type"));
+                        delta.0 = -delta.0 - 1;
+                        delta
+                    }
+                }
+            }
+            impl #impl_generics ::std::ops::Sub<#delta> for #name #ty_generics #where_clause {
+                type Output = Self;
+                fn sub(self, rhs: #delta) -> Self::Output {
+                    let magnitude = rhs.0.unsigned_abs();
+                    if rhs.0 >= 0 {
+                        Self(#backward)
+                    } else {
+                        Self(#forward)
+                    }
                 }
             }
         }
+    } else {
+        self_op(input, &SUB)?
     })
 }
 
-pub fn sub_assign_inner(
-    derive_name: &'static str,
-    input: &DeriveInput,
-) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, ty| {
-        quote! {
-            impl ::std::ops::SubAssign<#ty> for #name {
-                fn sub_assign(&mut self, rhs: #ty) {
-                    self.0 -= rhs
+pub fn display(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let display = fmt_display(input)?;
+    if matches!(input.inner_kind, InnerKind::Float) || non_numeric(input.inner_ty).is_some() {
+        return Ok(display);
+    }
+    Ok(quote! {
+        #display
+        impl #impl_generics ::std::fmt::Binary for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>)
+                -> ::std::result::Result<(), ::std::fmt::Error>
+            {
+                if f.alternate() {
+                    ::std::write!(f, "{:#b}", self.0)
+                } else {
+                    ::std::write!(f, "{:b}", self.0)
                 }
             }
         }
     })
 }
 
-pub fn display(derive_name: &'static str, input: &DeriveInput) -> syn::Result<TokenStream> {
-    single_derive(derive_name, input, |name, _| {
-        quote! {
-            impl ::std::fmt::Display for #name {
-                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>)
-                    -> ::std::result::Result<(), ::std::fmt::Error>
-                {
-                    write!(f, "{}", self.0)
-                }
-            }
-            impl ::std::fmt::Binary for #name {
-                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>)
-                    -> ::std::result::Result<(), ::std::fmt::Error>
-                {
-                    if f.alternate() {
-                        write!(f, "{:#b}", self.0)
+pub fn fmt_display(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let value = if let Some(with_check_digit) = check_digit(input)? {
+        quote!({
+            #with_check_digit
+
+            struct CheckDigitDisplay(::std::primitive::u128);
+
+            impl ::std::fmt::Display for CheckDigitDisplay {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    // Zero is followed by its check digit, which is zero as well.
+                    if self.0 == 0 {
+                        f.pad_integral(true, "", "00")
                     } else {
-                        write!(f, "{:b}", self.0)
+                        ::std::fmt::Display::fmt(&self.0, f)
                     }
                 }
             }
+
+            CheckDigitDisplay(with_check_digit(self.0))
+        })
+    } else if input.config.base32.is_some() {
+        let codec = crockford_base32(input)?;
+        let ty = input.inner_ty;
+        quote!({
+            #codec
+
+            struct Base32Display(#ty);
+
+            impl ::std::fmt::Display for Base32Display {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.pad(encode_base32(self.0, &mut [0; 26]))
+                }
+            }
+
+            Base32Display(self.0)
+        })
+    } else {
+        quote!(self.0)
+    };
+    let write = if let Some(display) = &input.config.display {
+        let format = &display.format;
+        quote!(::std::write!(f, #format, #value))
+    } else if let Some(prefix) = &input.config.prefix {
+        quote!(::std::write!(f, "{}{}", #prefix, #value))
+    } else {
+        quote!(::std::write!(f, "{}", #value))
+    };
+    Ok(quote! {
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>)
+                -> ::std::result::Result<(), ::std::fmt::Error>
+            {
+                #write
+            }
+        }
+    })
+}
+
+/// Returns the expression converting `value` of the unsigned integer type `ty` to `u128`,
+/// or `message` as the error for other types, including `u128` unless `allow_u128`.
+fn widen_unsigned(ty: &Type, allow_u128: bool, message: &str) -> syn::Result<TokenStream> {
+    match ty {
+        Type::Path(path)
+            if ["u8", "u16", "u32", "u64"]
+                .iter()
+                .any(|primitive| path.path.is_ident(primitive))
+                || (allow_u128 && path.path.is_ident("u128")) =>
+        {
+            Ok(quote!(::std::primitive::u128::from(value)))
+        }
+        Type::Path(path) if path.path.is_ident("usize") => {
+            Ok(quote!(value as ::std::primitive::u128))
+        }
+        _ => Err(syn::Error::new_spanned(ty, message)),
+    }
+}
+
+/// Returns the definitions of local functions converting the inner value to and from
+/// Crockford's Base32: `encode_base32`, writing the digits into a buffer and returning them,
+/// and `decode_base32`, returning `None` for invalid digits or values out of range.
+fn crockford_base32(input: &IdInput) -> syn::Result<TokenStream> {
+    let ty = input.inner_ty;
+    let widen = widen_unsigned(
+        ty,
+        true,
+        "Base32 requires an unsigned integer inner type, such as `u32` or `u64`",
+    )?;
+    Ok(quote! {
+        fn encode_base32(value: #ty, buf: &mut [::std::primitive::u8; 26]) -> &str {
+            const ALPHABET: &[::std::primitive::u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+            let mut rest = #widen;
+            let mut start = buf.len();
+            loop {
+                start -= 1;
+                buf[start] = ALPHABET[(rest % 32) as ::std::primitive::usize];
+                rest /= 32;
+                if rest == 0 {
+                    break;
+                }
+            }
+            ::std::str::from_utf8(&buf[start..]).unwrap_or_default()
+        }
+
+        fn decode_base32(s: &str) -> ::std::option::Option<#ty> {
+            let mut value: ::std::primitive::u128 = 0;
+            let mut digits = 0;
+            for c in s.bytes().filter(|&c| c != b'-') {
+                let digit = match c.to_ascii_uppercase() {
+                    c @ b'0'..=b'9' => c - b'0',
+                    b'O' => 0,
+                    b'I' | b'L' => 1,
+                    c @ b'A'..=b'H' => c - b'A' + 10,
+                    c @ b'J'..=b'K' => c - b'J' + 18,
+                    c @ b'M'..=b'N' => c - b'M' + 20,
+                    c @ b'P'..=b'T' => c - b'P' + 22,
+                    c @ b'V'..=b'Z' => c - b'V' + 27,
+                    _ => return ::std::option::Option::None,
+                };
+                value = value
+                    .checked_mul(32)?
+                    .checked_add(::std::primitive::u128::from(digit))?;
+                digits += 1;
+            }
+            if digits == 0 {
+                return ::std::option::Option::None;
+            }
+            ::std::convert::TryFrom::try_from(value).ok()
+        }
+    })
+}
+
+/// Implements `to_base32_crockford` and `from_base32_crockford`.
+pub fn base32(input: &IdInput) -> syn::Result<TokenStream> {
+    let codec = crockford_base32(input)?;
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the inner value in Crockford's Base32, with uppercase digits.
+            #vis fn to_base32_crockford(&self) -> ::std::string::String {
+                #codec
+                ::std::string::String::from(encode_base32(self.0, &mut [0; 26]))
+            }
+
+            /// Parses an identifier from Crockford's Base32, ignoring case and hyphens,
+            /// and reading `O` as `0`, and `I` and `L` as `1`. Returns `None` if `s` has no
+            /// digits, has other characters, or encodes a value out of range.
+            #unchecked_vis fn from_base32_crockford(s: &str) -> ::std::option::Option<Self> {
+                #codec
+                decode_base32(s).map(Self)
+            }
+        }
+    })
+}
+
+/// With `#[id(check_digit = "...")]`, returns the definition of a local function
+/// `with_check_digit` returning the inner value with its check digit appended, as a `u128`.
+fn check_digit(input: &IdInput) -> syn::Result<Option<TokenStream>> {
+    let Some(CheckDigit::Luhn) = input.config.check_digit else {
+        return Ok(None);
+    };
+    let ty = input.inner_ty;
+    let widen = widen_unsigned(
+        ty,
+        false,
+        "`check_digit` requires an unsigned integer inner type of at most 64 bits",
+    )?;
+    Ok(Some(quote! {
+        fn with_check_digit(value: #ty) -> ::std::primitive::u128 {
+            let value = #widen;
+            let (mut rest, mut sum, mut double) = (value, 0, true);
+            while rest > 0 {
+                let digit = rest % 10 * if double { 2 } else { 1 };
+                sum += digit / 10 + digit % 10;
+                rest /= 10;
+                double = !double;
+            }
+            value * 10 + (10 - sum % 10) % 10
+        }
+    }))
+}
+
+pub fn from_str(input: &IdInput) -> syn::Result<TokenStream> {
+    if input.config.parse_error
+        || input.config.affixes().is_some()
+        || input.config.check_digit.is_some()
+        || input.config.base32.is_some()
+        || input.config.validate.is_some()
+    {
+        return from_str_with_error(input);
+    }
+    let parse = parse_str(input, &TokenStream::new());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let try_from = try_from_str(input);
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = <#ty as ::std::str::FromStr>::Err;
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let inner = { #parse };
+                <#ty as ::std::str::FromStr>::from_str(inner).map(Self)
+            }
+        }
+
+        #try_from
+    })
+}
+
+/// Implements `TryFrom<&str>` and `TryFrom<String>` with `FromStr`, sharing its error,
+/// which is `Infallible` for `String` inner types without a prefix or display format.
+/// `TryFrom<String>` is left out for `String` inner types, for which it would conflict with
+/// the blanket impl over `From<String>`, as implemented by `FromInner`.
+fn try_from_str(input: &IdInput) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let try_from_string = if is_string(input.inner_ty) {
+        TokenStream::new()
+    } else {
+        quote! {
+            #[allow(clippy::infallible_try_from)]
+            impl #impl_generics ::std::convert::TryFrom<::std::string::String> for #name #ty_generics
+            #where_clause
+            {
+                type Error = <Self as ::std::str::FromStr>::Err;
+                fn try_from(s: ::std::string::String) -> ::std::result::Result<Self, Self::Error> {
+                    <Self as ::std::str::FromStr>::from_str(&s)
+                }
+            }
+        }
+    };
+    quote! {
+        #[allow(clippy::infallible_try_from)]
+        impl #impl_generics ::std::convert::TryFrom<&str> for #name #ty_generics #where_clause {
+            type Error = <Self as ::std::str::FromStr>::Err;
+            fn try_from(s: &str) -> ::std::result::Result<Self, Self::Error> {
+                <Self as ::std::str::FromStr>::from_str(s)
+            }
+        }
+
+        #try_from_string
+    }
+}
+
+/// Whether `ty` names `String`, possibly by a path.
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "String" && segment.arguments.is_empty()),
+        _ => false,
+    }
+}
+
+/// Returns the block turning the parsed string `s` into the string passed to the inner type,
+/// following the `prefix`, `display`, and `parse(...)` options. Evaluates `mismatch` as
+/// the error if the text around the inner value is not found.
+fn parse_str(input: &IdInput, mismatch: &TokenStream) -> TokenStream {
+    let options = &input.config.parse;
+    let mut steps = vec![quote!(let inner = s;)];
+    if options.trim {
+        steps.push(quote!(let inner = inner.trim();));
+    }
+    let (prefix, suffix) = input.config.affixes().unwrap_or_default();
+    let stripped = |stripped: TokenStream| {
+        quote! {
+            let inner = match #stripped {
+                ::std::option::Option::Some(inner) => inner,
+                ::std::option::Option::None => {
+                    return ::std::result::Result::Err(#mismatch);
+                }
+            };
+        }
+    };
+    if !prefix.is_empty() {
+        steps.push(stripped(if options.case_insensitive_prefix {
+            quote! {
+                inner
+                    .get(..#prefix.len())
+                    .filter(|start| start.eq_ignore_ascii_case(#prefix))
+                    .map(|_| &inner[#prefix.len()..])
+            }
+        } else {
+            quote!(inner.strip_prefix(#prefix))
+        }));
+    }
+    if !suffix.is_empty() {
+        steps.push(stripped(quote!(inner.strip_suffix(#suffix))));
+    }
+    if options.trim && !(prefix.is_empty() && suffix.is_empty()) {
+        steps.push(quote!(let inner = inner.trim();));
+    }
+    if options.allow_plus {
+        steps.push(quote!(let inner = inner.strip_prefix('+').unwrap_or(inner);));
+    }
+    quote!(#(#steps)* inner)
+}
+
+/// Implements `FromStr` returning a `<Name>ParseError` companion type, which retains
+/// the parsed string and the error of the inner type, and mentions the identifier type
+/// in its message.
+pub fn from_str_with_error(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (vis, companion_vis) = input.companion_item_vis();
+    let generics = input.generics;
+    let error = input.companion_name("", "ParseError");
+    let error_doc = format!("An error returned when parsing a [`{name}`] fails.");
+    let error_derive = companion_derive(input, &["Debug"]);
+    let message = format!("invalid {name} {{:?}}: {{}}");
+    let type_name = name.to_string();
+    let mismatch = |reason: &str| {
+        quote!(#error {
+            input: ::std::string::String::from(s),
+            cause: ::std::option::Option::None,
+            reason: #reason,
+            marker: ::std::marker::PhantomData,
+        })
+    };
+    let parse = parse_str(input, &mismatch(&mismatch_reason(input)));
+    let (split_check_digit, verify_check_digit) = check_digit_steps(input, &mismatch)?;
+    let parse_value = parse_inner_value(input, &error, &mismatch)?;
+    let validate = input.config.validate.as_ref().map(|Validate { function, .. }| {
+        let invalid = mismatch("rejected by validation");
+        quote! {
+            if #function(&value).is_err() {
+                return ::std::result::Result::Err(#invalid);
+            }
+        }
+    });
+    let cause = quote!(<#ty as ::std::str::FromStr>::Err);
+    let bounded_where_clause = |bound: TokenStream| {
+        let mut generics = input.generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#cause: #bound));
+        generics.where_clause
+    };
+    let try_from = try_from_str(input);
+    let round_trip = round_trip(input, &error, &vis);
+    let debug_where_clause = bounded_where_clause(quote!(::std::fmt::Debug));
+    let display_where_clause = bounded_where_clause(quote!(::std::fmt::Display));
+    let error_where_clause = bounded_where_clause(quote!(::std::error::Error + 'static));
+    Ok(input.in_companion_module(quote! {
+        #[doc = #error_doc]
+        #error_derive
+        #companion_vis struct #error #generics #where_clause {
+            input: ::std::string::String,
+            cause: ::std::option::Option<#cause>,
+            reason: &'static str,
+            marker: ::std::marker::PhantomData<fn() -> #ty>,
+        }
+
+        impl #impl_generics #error #ty_generics #where_clause {
+            /// Returns the string that failed to parse.
+            #vis fn input(&self) -> &str {
+                &self.input
+            }
+
+            /// Returns the name of the identifier type.
+            #vis fn type_name(&self) -> &'static str {
+                #type_name
+            }
+
+            /// Returns the error returned when parsing the inner type, or `None` if the string
+            /// does not match the expected prefix or display format, has an invalid check digit,
+            /// is not valid Base32, or is rejected by validation.
+            #vis fn cause(&self) -> ::std::option::Option<&#cause> {
+                self.cause.as_ref()
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Debug for #error #ty_generics #debug_where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.debug_struct(::std::stringify!(#error))
+                    .field("input", &self.input)
+                    .field("cause", &self.cause)
+                    .finish()
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Display for #error #ty_generics #display_where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match &self.cause {
+                    ::std::option::Option::Some(cause) => ::std::write!(f, #message, self.input, cause),
+                    ::std::option::Option::None => ::std::write!(f, #message, self.input, self.reason),
+                }
+            }
+        }
+
+        impl #impl_generics ::std::error::Error for #error #ty_generics #error_where_clause {
+            fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                self.cause
+                    .as_ref()
+                    .map(|cause| cause as &(dyn ::std::error::Error + 'static))
+            }
+        }
+
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = #error #ty_generics;
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let inner = { #parse };
+                #split_check_digit
+                #parse_value
+                #verify_check_digit
+                #validate
+                ::std::result::Result::Ok(Self(value))
+            }
+        }
+
+        #try_from
+        #round_trip
+    }))
+}
+
+/// Returns the step of `FromStr` parsing the inner `value` from the string `inner`, left
+/// after the surrounding text and check digit are stripped, with the inner type or as Base32.
+fn parse_inner_value(
+    input: &IdInput,
+    error: &Ident,
+    mismatch: &dyn Fn(&str) -> TokenStream,
+) -> syn::Result<TokenStream> {
+    let ty = input.inner_ty;
+    Ok(if input.config.base32.is_some() {
+        let codec = crockford_base32(input)?;
+        let invalid = mismatch("invalid Crockford Base32 value");
+        quote! {
+            #codec
+            let value = decode_base32(inner).ok_or_else(|| #invalid)?;
+        }
+    } else {
+        quote! {
+            let value = <#ty as ::std::str::FromStr>::from_str(inner).map_err(|cause| #error {
+                input: ::std::string::String::from(s),
+                cause: ::std::option::Option::Some(cause),
+                reason: "",
+                marker: ::std::marker::PhantomData,
+            })?;
+        }
+    })
+}
+
+/// Returns the steps of `FromStr` splitting off the check digit after the prefix and suffix
+/// are stripped, and verifying it after the inner value is parsed, if requested with
+/// `#[id(check_digit = "...")]`. `mismatch` returns the error with the given reason.
+fn check_digit_steps(
+    input: &IdInput,
+    mismatch: &dyn Fn(&str) -> TokenStream,
+) -> syn::Result<(TokenStream, TokenStream)> {
+    Ok(match check_digit(input)? {
+        Some(with_check_digit) => {
+            let missing = mismatch("missing check digit");
+            let invalid = mismatch("invalid check digit");
+            (
+                quote! {
+                    let (inner, check) = match inner.char_indices().last() {
+                        ::std::option::Option::Some((idx, check))
+                            if idx > 0 && check.is_ascii_digit() =>
+                        {
+                            (&inner[..idx], ::std::primitive::u128::from(check as u8 - b'0'))
+                        }
+                        _ => return ::std::result::Result::Err(#missing),
+                    };
+                },
+                quote! {
+                    #with_check_digit
+                    if with_check_digit(value) % 10 != check {
+                        return ::std::result::Result::Err(#invalid);
+                    }
+                },
+            )
+        }
+        None => (TokenStream::new(), TokenStream::new()),
+    })
+}
+
+/// Returns the reason of a parse error without a cause reported when the prefix or the text
+/// around the display placeholder is missing.
+fn mismatch_reason(input: &IdInput) -> String {
+    let expected = match (&input.config.display, &input.config.prefix) {
+        (Some(display), _) => format!("format {:?}", display.format.value()),
+        (None, prefix) => format!(
+            "prefix {:?}",
+            prefix.as_ref().map(LitStr::value).unwrap_or_default()
+        ),
+    };
+    format!("expected {expected}")
+}
+
+/// With a prefix, display format, check digit, or Base32, adds a test-only `assert_round_trip`
+/// method checking that `Display` output parses back.
+fn round_trip(input: &IdInput, error: &Ident, vis: &TokenStream) -> TokenStream {
+    if input.config.affixes().is_none()
+        && input.config.check_digit.is_none()
+        && input.config.base32.is_none()
+    {
+        return TokenStream::new();
+    }
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let message = format!("{{:?}} parses back as a different {name}");
+    // The bounds are higher-ranked so that they are checked when the method is called,
+    // rather than rejected upfront for a type that is not generic, e.g., without `Display`.
+    let mut assert_generics = input.generics.clone();
+    let predicates = &mut assert_generics.make_where_clause().predicates;
+    predicates.push(syn::parse_quote! {
+        for<'round_trip> #name #ty_generics: ::std::fmt::Display
+    });
+    predicates.push(syn::parse_quote!(for<'round_trip> #ty: ::std::cmp::PartialEq));
+    predicates.push(syn::parse_quote! {
+        for<'round_trip> #error #ty_generics: ::std::fmt::Display
+    });
+    let assert_where_clause = &assert_generics.where_clause;
+    quote! {
+        #[cfg(test)]
+        impl #impl_generics #name #ty_generics #assert_where_clause {
+            /// Asserts that the `Display` output of the identifier parses back to it.
+            #[allow(dead_code)]
+            #vis fn assert_round_trip(&self) {
+                let s = ::std::string::ToString::to_string(self);
+                match <Self as ::std::str::FromStr>::from_str(&s) {
+                    ::std::result::Result::Ok(parsed) => {
+                        ::std::assert!(parsed.0 == self.0, #message, s);
+                    }
+                    ::std::result::Result::Err(err) => {
+                        ::std::panic!("{:?} does not parse back: {}", s, err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Implements `serde::Deserialize` from a string parsed with `FromStr`, so that the errors
+/// are the same as when parsing.
+#[cfg(feature = "serde")]
+pub fn deserialize_str(input: &IdInput) -> syn::Result<TokenStream> {
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let mut de_generics = input.generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('de));
+    de_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! {
+            <#name #ty_generics as ::std::str::FromStr>::Err: ::std::fmt::Display
+        });
+    let (de_impl_generics, _, de_where_clause) = de_generics.split_for_impl();
+    let deserialize = validated(
+        input,
+        &quote! {
+            let s = <::std::string::String as ::serde::Deserialize<'de>>::deserialize(
+                deserializer,
+            )?;
+            s.parse().map_err(::serde::de::Error::custom)
+        },
+    );
+    Ok(quote! {
+        impl #de_impl_generics ::serde::Deserialize<'de> for #name #ty_generics #de_where_clause {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                #deserialize
+            }
+        }
+    })
+}
+
+/// Forwards comparisons and hashing to the inner type. Only `PartialEq` and `PartialOrd`
+/// are implemented for floating-point inner types.
+pub fn compare(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    if let InnerKind::Float = input.inner_kind {
+        return Ok(quote! {
+            impl #impl_generics ::std::cmp::PartialEq for #name #ty_generics #where_clause {
+                fn eq(&self, other: &Self) -> ::std::primitive::bool {
+                    self.0 == other.0
+                }
+            }
+            impl #impl_generics ::std::cmp::PartialOrd for #name #ty_generics #where_clause {
+                fn partial_cmp(&self, other: &Self) -> ::std::option::Option<::std::cmp::Ordering> {
+                    ::std::cmp::PartialOrd::partial_cmp(&self.0, &other.0)
+                }
+            }
+        });
+    }
+    Ok(quote! {
+        impl #impl_generics ::std::cmp::PartialEq for #name #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> ::std::primitive::bool {
+                self.0 == other.0
+            }
+        }
+        impl #impl_generics ::std::cmp::Eq for #name #ty_generics #where_clause {}
+        impl #impl_generics ::std::cmp::PartialOrd for #name #ty_generics #where_clause {
+            fn partial_cmp(&self, other: &Self) -> ::std::option::Option<::std::cmp::Ordering> {
+                ::std::option::Option::Some(::std::cmp::Ord::cmp(self, other))
+            }
+        }
+        impl #impl_generics ::std::cmp::Ord for #name #ty_generics #where_clause {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                ::std::cmp::Ord::cmp(&self.0, &other.0)
+            }
+        }
+        impl #impl_generics ::std::hash::Hash for #name #ty_generics #where_clause {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                ::std::hash::Hash::hash(&self.0, state)
+            }
+        }
+    })
+}
+
+#[cfg(feature = "serde")]
+pub fn serde(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let mut de_generics = input.generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('de));
+    let borrowed = BorrowedStr::of(ty);
+    let mut deserialize_inner =
+        quote!(<#ty as ::serde::Deserialize<'de>>::deserialize(deserializer).map(Self));
+    if let Some(BorrowedStr { lifetime, cow }) = borrowed {
+        de_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!('de: #lifetime));
+        if cow {
+            deserialize_inner = deserialize_cow_str();
+        }
+    }
+    let (de_impl_generics, _, de_where_clause) = de_generics.split_for_impl();
+    let (serialize_str, deserialize) = match input.config.serde {
+        SerdeMode::Inner => (TokenStream::new(), deserialize_inner),
+        SerdeMode::HumanReadable => (
+            quote! {
+                if serializer.is_human_readable() {
+                    return serializer.collect_str(self);
+                }
+            },
+            quote! {
+                if deserializer.is_human_readable() {
+                    let s = <::std::string::String as ::serde::Deserialize<'de>>::deserialize(
+                        deserializer,
+                    )?;
+                    s.parse().map_err(::serde::de::Error::custom)
+                } else {
+                    #deserialize_inner
+                }
+            },
+        ),
+    };
+    let deserialize = validated(input, &deserialize);
+    let packed = if input.config.serde_packed {
+        packed_serde(input)
+    } else {
+        TokenStream::new()
+    };
+    Ok(quote! {
+        #packed
+        impl #impl_generics ::serde::Serialize for #name #ty_generics #where_clause {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                #serialize_str
+                ::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+        impl #de_impl_generics ::serde::Deserialize<'de> for #name #ty_generics #de_where_clause {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                #deserialize
+            }
+        }
+    })
+}
+
+/// Implements `const fn discriminant` and `TryFrom<u32>` and `TryFrom<u64>` for an enum-backed
+/// identifier, failing with the `Unknown<Name>` companion error for values that are not
+/// discriminants of any variant, as well as `ALL` and `iter` listing the variants.
+pub fn enum_id(input: &EnumIdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let repr = &input.repr;
+    let variants = &input.variants;
+    let (vis, companion_vis) = input.companion_item_vis();
+    let error = input.companion_name("Unknown", "");
+    let error_doc = format!("An error returned when converting a value that is not a discriminant of any [`{name}`] variant.");
+    let error_derive = companion_derive(input, &["Clone", "Copy", "Debug", "PartialEq", "Eq"]);
+    let unknown = format!("unknown {name} discriminant {{}}");
+    let count = variants.len();
+    Ok(input.in_companion_module(quote! {
+        #[doc = #error_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #error_derive
+        #companion_vis struct #error(#vis ::std::primitive::u64);
+
+        impl ::std::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::write!(f, #unknown, self.0)
+            }
+        }
+
+        impl ::std::error::Error for #error {}
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// All variants, in declaration order.
+            #vis const ALL: [Self; #count] = [#(Self::#variants),*];
+
+            /// Returns an iterator over all variants, in declaration order.
+            #vis fn iter() -> impl ::std::iter::Iterator<Item = Self> {
+                ::std::iter::IntoIterator::into_iter(Self::ALL)
+            }
+
+            /// Returns the discriminant of the variant.
+            #vis const fn discriminant(self) -> #repr {
+                self as #repr
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<::std::primitive::u64> for #name #ty_generics
+        #where_clause
+        {
+            type Error = #error;
+
+            fn try_from(value: ::std::primitive::u64) -> ::std::result::Result<Self, #error> {
+                let discriminant = <#repr as ::std::convert::TryFrom<::std::primitive::u64>>::try_from(value)
+                    .map_err(|_| #error(value))?;
+                #(
+                    if discriminant == Self::#variants as #repr {
+                        return ::std::result::Result::Ok(Self::#variants);
+                    }
+                )*
+                ::std::result::Result::Err(#error(value))
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<::std::primitive::u32> for #name #ty_generics
+        #where_clause
+        {
+            type Error = #error;
+
+            fn try_from(value: ::std::primitive::u32) -> ::std::result::Result<Self, #error> {
+                <Self as ::std::convert::TryFrom<::std::primitive::u64>>::try_from(
+                    ::std::primitive::u64::from(value),
+                )
+            }
+        }
+    }))
+}
+
+/// Implements `serde::Serialize` and `serde::Deserialize` for an enum-backed identifier,
+/// represented by the discriminant rather than the variant name, and rejecting unknown
+/// discriminants.
+#[cfg(feature = "serde")]
+pub fn enum_serde(input: &EnumIdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let repr = &input.repr;
+    let variants = &input.variants;
+    let mut de_generics = input.generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('de));
+    let (de_impl_generics, _, de_where_clause) = de_generics.split_for_impl();
+    let unknown = format!("unknown {name} discriminant {{}}");
+    Ok(quote! {
+        impl #impl_generics ::serde::Serialize for #name #ty_generics #where_clause {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                let discriminant: #repr = match *self {
+                    #(Self::#variants => Self::#variants as #repr,)*
+                };
+                ::serde::Serialize::serialize(&discriminant, serializer)
+            }
+        }
+        impl #de_impl_generics ::serde::Deserialize<'de> for #name #ty_generics #de_where_clause {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                let discriminant = <#repr as ::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                #(
+                    if discriminant == Self::#variants as #repr {
+                        return ::std::result::Result::Ok(Self::#variants);
+                    }
+                )*
+                ::std::result::Result::Err(::serde::de::Error::custom(::std::format_args!(
+                    #unknown,
+                    discriminant
+                )))
+            }
+        }
+    })
+}
+
+/// Implements `serialize_packed` and `deserialize_packed`, representing a sequence of
+/// identifiers as a byte buffer of the little-endian inner values, for use with
+/// `#[serde(serialize_with = "...", deserialize_with = "...")]`.
+#[cfg(feature = "serde")]
+fn packed_serde(input: &IdInput) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let vis = input.item_vis();
+    let expecting = format!("a byte buffer of packed {name} identifiers");
+    let misaligned = format!("packed {name} buffer of {{}} bytes is not a multiple of {{}}");
+    let validate = input.config.validate.as_ref().map(
+        |Validate { function, .. }| quote!(#function(&inner).map_err(::serde::de::Error::custom)?;),
+    );
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Serializes `ids` as a byte buffer of the little-endian inner values.
+            #vis fn serialize_packed<S>(ids: &[Self], serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                let mut bytes = ::std::vec::Vec::with_capacity(ids.len() * ::std::mem::size_of::<#ty>());
+                for id in ids {
+                    bytes.extend_from_slice(&id.0.to_le_bytes());
+                }
+                serializer.serialize_bytes(&bytes)
+            }
+
+            /// Deserializes identifiers serialized with `serialize_packed`.
+            #vis fn deserialize_packed<'de, D>(deserializer: D) -> ::std::result::Result<::std::vec::Vec<Self>, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                struct PackedVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for PackedVisitor {
+                    type Value = ::std::vec::Vec<::std::primitive::u8>;
+
+                    fn expecting(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        f.write_str(#expecting)
+                    }
+
+                    fn visit_bytes<E>(self, v: &[::std::primitive::u8]) -> ::std::result::Result<Self::Value, E>
+                    where
+                        E: ::serde::de::Error,
+                    {
+                        ::std::result::Result::Ok(v.to_vec())
+                    }
+
+                    fn visit_byte_buf<E>(self, v: ::std::vec::Vec<::std::primitive::u8>) -> ::std::result::Result<Self::Value, E>
+                    where
+                        E: ::serde::de::Error,
+                    {
+                        ::std::result::Result::Ok(v)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> ::std::result::Result<Self::Value, A::Error>
+                    where
+                        A: ::serde::de::SeqAccess<'de>,
+                    {
+                        let mut bytes = ::std::vec::Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                        while let ::std::option::Option::Some(byte) = seq.next_element()? {
+                            bytes.push(byte);
+                        }
+                        ::std::result::Result::Ok(bytes)
+                    }
+                }
+
+                let bytes = deserializer.deserialize_bytes(PackedVisitor)?;
+                let width = ::std::mem::size_of::<#ty>();
+                if bytes.len() % width != 0 {
+                    return ::std::result::Result::Err(::serde::de::Error::custom(::std::format_args!(
+                        #misaligned,
+                        bytes.len(),
+                        width
+                    )));
+                }
+                bytes
+                    .chunks_exact(width)
+                    .map(|chunk| {
+                        let inner = <#ty>::from_le_bytes(
+                            ::std::convert::TryInto::try_into(chunk).expect("chunk has the width of the inner type"),
+                        );
+                        #validate
+                        ::std::result::Result::Ok(Self(inner))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Returns the body of `Deserialize::deserialize` evaluating `deserialize` to the identifier,
+/// which is then checked with the `validate` function, if any.
+#[cfg(feature = "serde")]
+fn validated(input: &IdInput, deserialize: &TokenStream) -> TokenStream {
+    match &input.config.validate {
+        Some(Validate { function, .. }) => quote! {
+            let id: Self = { #deserialize }?;
+            #function(&id.0).map_err(::serde::de::Error::custom)?;
+            ::std::result::Result::Ok(id)
+        },
+        None => deserialize.clone(),
+    }
+}
+
+/// Deserializes a `Cow<str>` inner value that borrows from the input when possible,
+/// as opposed to the `Deserialize` impl of `Cow`, which always allocates.
+#[cfg(feature = "serde")]
+fn deserialize_cow_str() -> TokenStream {
+    quote! {
+        struct CowStrVisitor;
+
+        impl<'v> ::serde::de::Visitor<'v> for CowStrVisitor {
+            type Value = ::std::borrow::Cow<'v, str>;
+
+            fn expecting(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E: ::serde::de::Error>(self, v: &'v str) -> ::std::result::Result<Self::Value, E> {
+                ::std::result::Result::Ok(::std::borrow::Cow::Borrowed(v))
+            }
+
+            fn visit_str<E: ::serde::de::Error>(self, v: &str) -> ::std::result::Result<Self::Value, E> {
+                ::std::result::Result::Ok(::std::borrow::Cow::Owned(v.to_owned()))
+            }
+
+            fn visit_string<E: ::serde::de::Error>(
+                self,
+                v: ::std::string::String,
+            ) -> ::std::result::Result<Self::Value, E> {
+                ::std::result::Result::Ok(::std::borrow::Cow::Owned(v))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor).map(Self)
+    }
+}
+
+/// Implements `hash32::Hash` by hashing the inner value, as `Hash` does.
+#[cfg(feature = "hash32")]
+pub fn hash32(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ty: ::hash32::Hash));
+    let where_clause = &generics.where_clause;
+    Ok(quote! {
+        impl #impl_generics ::hash32::Hash for #name #ty_generics #where_clause {
+            fn hash<H: ::hash32::Hasher>(&self, state: &mut H) {
+                <#ty as ::hash32::Hash>::hash(&self.0, state)
+            }
+        }
+    })
+}
+
+/// Implements `write_to`, writing the `Display` output without allocating and formatting
+/// the inner integer with `itoa`, and with the `arrayvec` feature, `to_array_string`.
+#[cfg(feature = "itoa")]
+pub fn itoa(input: &IdInput) -> syn::Result<TokenStream> {
+    let ty = input.inner_ty;
+    let max_digits: usize = match ty {
+        Type::Path(path) => match path.path.get_ident().map(ToString::to_string).as_deref() {
+            Some("u8") => 3,
+            Some("i8") => 4,
+            Some("u16") => 5,
+            Some("i16") => 6,
+            Some("u32") => 10,
+            Some("i32") => 11,
+            Some("u64" | "i64" | "usize" | "isize") => 20,
+            Some("u128") => 39,
+            Some("i128") => 40,
+            _ => 0,
+        },
+        _ => 0,
+    };
+    if max_digits == 0 {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "Itoa requires a primitive integer inner type, such as `u32` or `i64`",
+        ));
+    }
+    if let Some(base32) = &input.config.base32 {
+        return Err(syn::Error::new(
+            base32.span(),
+            "Itoa writes the inner value in decimal, and cannot be combined with `base32`",
+        ));
+    }
+    if input.config.check_digit.is_some() {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "Itoa writes the inner value alone, and cannot be combined with `check_digit`",
+        ));
+    }
+    let (prefix, suffix) = input.config.affixes().unwrap_or_default();
+    let write = match &input.config.display {
+        Some(display) if !display.spec.is_empty() => {
+            let format = &display.format;
+            quote!(::std::write!(w, #format, self.0))
+        }
+        _ => {
+            let write_prefix = (!prefix.is_empty()).then(|| quote!(w.write_str(#prefix)?;));
+            let write_suffix = (!suffix.is_empty()).then(|| quote!(w.write_str(#suffix)?;));
+            quote! {
+                #write_prefix
+                w.write_str(::itoa::Buffer::new().format(self.0))?;
+                #write_suffix
+                ::std::result::Result::Ok(())
+            }
+        }
+    };
+    let vis = input.item_vis();
+    let width = input
+        .config
+        .display
+        .as_ref()
+        .map_or(0, DisplayTemplate::width);
+    let capacity = prefix.len() + suffix.len() + max_digits.max(width);
+    let to_array_string = if cfg!(feature = "arrayvec") {
+        quote! {
+            /// Returns the `Display` output in a string on the stack, which fits the longest
+            /// output.
+            #vis fn to_array_string(&self) -> ::arrayvec::ArrayString<#capacity> {
+                let mut s = ::arrayvec::ArrayString::new();
+                let _ = self.write_to(&mut s);
+                s
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Writes the `Display` output to `w` without allocating.
+            #vis fn write_to<W: ::std::fmt::Write>(&self, mut w: W) -> ::std::fmt::Result {
+                #write
+            }
+
+            #to_array_string
+        }
+    })
+}
+
+/// Implements `to_arrow_array`, `from_arrow_array`, and `arrow_data_type`, converting
+/// between identifiers and Arrow arrays of the inner type.
+#[cfg(feature = "arrow")]
+pub fn arrow(input: &IdInput) -> syn::Result<TokenStream> {
+    let ty = input.inner_ty;
+    let arrow_ty = match ty {
+        Type::Path(path) => {
+            path.path
+                .get_ident()
+                .and_then(|ident| match ident.to_string().as_str() {
+                    "u8" => Some("UInt8Type"),
+                    "u16" => Some("UInt16Type"),
+                    "u32" => Some("UInt32Type"),
+                    "u64" => Some("UInt64Type"),
+                    "i8" => Some("Int8Type"),
+                    "i16" => Some("Int16Type"),
+                    "i32" => Some("Int32Type"),
+                    "i64" => Some("Int64Type"),
+                    "f32" => Some("Float32Type"),
+                    "f64" => Some("Float64Type"),
+                    _ => None,
+                })
+        }
+        _ => None,
+    };
+    let arrow_ty = arrow_ty.ok_or_else(|| {
+        syn::Error::new_spanned(
+            ty,
+            "Arrow requires a fixed-width primitive inner type, such as `u64` or `i32`",
+        )
+    })?;
+    let arrow_ty = format_ident!("{}", arrow_ty);
+    let arrow_ty = quote!(::arrow::datatypes::#arrow_ty);
+    let array = quote!(::arrow::array::PrimitiveArray<#arrow_ty>);
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the Arrow data type of arrays of identifiers.
+            #vis fn arrow_data_type() -> ::arrow::datatypes::DataType {
+                <#arrow_ty as ::arrow::datatypes::ArrowPrimitiveType>::DATA_TYPE
+            }
+
+            /// Returns an Arrow array of the inner values of `ids`, without nulls.
+            #vis fn to_arrow_array(ids: &[Self]) -> #array {
+                <#array>::from_iter_values(ids.iter().map(|id| id.0))
+            }
+
+            /// Returns the identifiers in an Arrow array, with `None` for nulls.
+            #unchecked_vis fn from_arrow_array(array: &#array) -> ::std::vec::Vec<::std::option::Option<Self>> {
+                array.iter().map(|inner| inner.map(Self)).collect()
+            }
+        }
+    })
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `metrics::SharedString` with the `Display`
+/// output, so that identifiers can be passed as label values.
+#[cfg(feature = "metrics")]
+pub fn metrics(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#name #ty_generics: ::std::fmt::Display));
+    let where_clause = &generics.where_clause;
+    Ok(quote! {
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for ::metrics::SharedString
+        #where_clause
+        {
+            fn from(id: #name #ty_generics) -> Self {
+                Self::from(&id)
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<&#name #ty_generics> for ::metrics::SharedString
+        #where_clause
+        {
+            fn from(id: &#name #ty_generics) -> Self {
+                Self::from(::std::string::ToString::to_string(id))
+            }
+        }
+    })
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `opentelemetry::Value`: an integer or float
+/// value when the inner type converts losslessly and the `Display` output is the plain number,
+/// and a string value with the `Display` output otherwise.
+#[cfg(feature = "opentelemetry")]
+pub fn opentelemetry(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let config = &input.config;
+    let plain = config.prefix.is_none()
+        && config.display.is_none()
+        && config.check_digit.is_none()
+        && config.base32.is_none();
+    let (primitive, value) = match &input.inner_kind {
+        InnerKind::Wrapper { primitive, .. } => (*primitive, quote!(id.0 .0)),
+        _ => (input.inner_ty, quote!(id.0)),
+    };
+    let is = |names: &[&str]| matches!(primitive, Type::Path(path) if names.iter().any(|name| path.path.is_ident(name)));
+    let mut generics = input.generics.clone();
+    let convert = if plain && is(&["i8", "i16", "i32", "i64", "u8", "u16", "u32"]) {
+        quote!(::opentelemetry::Value::I64(::std::primitive::i64::from(#value)))
+    } else if plain && is(&["f32", "f64"]) {
+        quote!(::opentelemetry::Value::F64(::std::primitive::f64::from(#value)))
+    } else {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#name #ty_generics: ::std::fmt::Display));
+        quote!(::opentelemetry::Value::String(::std::convert::From::from(
+            ::std::string::ToString::to_string(id)
+        )))
+    };
+    let where_clause = &generics.where_clause;
+    Ok(quote! {
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for ::opentelemetry::Value
+        #where_clause
+        {
+            fn from(id: #name #ty_generics) -> Self {
+                Self::from(&id)
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<&#name #ty_generics> for ::opentelemetry::Value
+        #where_clause
+        {
+            fn from(id: &#name #ty_generics) -> Self {
+                #convert
+            }
+        }
+    })
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `http::HeaderValue` with the `Display`
+/// output, and `TryFrom<&HeaderValue>` for `Name` with `FromStr`, failing with the
+/// `<Name>HeaderError` companion error.
+#[cfg(feature = "http")]
+pub fn http(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let generics = input.generics;
+    let (_, companion_vis) = input.companion_item_vis();
+    let error = input.companion_name("", "HeaderError");
+    let error_doc =
+        format!("An error returned when converting a header value into a [`{name}`] fails.");
+    let error_derive = companion_derive(input, &["Debug"]);
+    let message = format!("invalid {name} header value: {{}}");
+    let expect = format!("{name} must display as a valid header value");
+    let cause = quote!(<#name #ty_generics as ::std::str::FromStr>::Err);
+    let bounded_where_clause = |bounds: &[TokenStream]| {
+        let mut generics = input.generics.clone();
+        let predicates = &mut generics.make_where_clause().predicates;
+        for bound in bounds {
+            predicates.push(syn::parse_quote!(#bound));
+        }
+        generics.where_clause
+    };
+    let from_str = quote!(#name #ty_generics: ::std::str::FromStr);
+    let where_clause = bounded_where_clause(std::slice::from_ref(&from_str));
+    let display_where_clause =
+        bounded_where_clause(&[quote!(#name #ty_generics: ::std::fmt::Display)]);
+    let debug_where_clause =
+        bounded_where_clause(&[from_str.clone(), quote!(#cause: ::std::fmt::Debug)]);
+    let error_display_where_clause =
+        bounded_where_clause(&[from_str.clone(), quote!(#cause: ::std::fmt::Display)]);
+    let error_where_clause =
+        bounded_where_clause(&[from_str, quote!(#cause: ::std::error::Error + 'static)]);
+    Ok(input.in_companion_module(quote! {
+        #[doc = #error_doc]
+        #error_derive
+        #companion_vis enum #error #generics #where_clause {
+            /// The header value contains characters other than visible ASCII.
+            NotVisibleAscii(::http::header::ToStrError),
+            /// The header value does not parse as an identifier.
+            Parse(#cause),
+        }
+
+        impl #impl_generics ::std::fmt::Debug for #error #ty_generics #debug_where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match self {
+                    Self::NotVisibleAscii(cause) => f.debug_tuple("NotVisibleAscii").field(cause).finish(),
+                    Self::Parse(cause) => f.debug_tuple("Parse").field(cause).finish(),
+                }
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Display for #error #ty_generics #error_display_where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match self {
+                    Self::NotVisibleAscii(cause) => ::std::write!(f, #message, cause),
+                    Self::Parse(cause) => ::std::write!(f, #message, cause),
+                }
+            }
+        }
+
+        impl #impl_generics ::std::error::Error for #error #ty_generics #error_where_clause {
+            fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                match self {
+                    Self::NotVisibleAscii(cause) => ::std::option::Option::Some(cause),
+                    Self::Parse(cause) => ::std::option::Option::Some(cause),
+                }
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for ::http::HeaderValue
+        #display_where_clause
+        {
+            fn from(id: #name #ty_generics) -> Self {
+                Self::from(&id)
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<&#name #ty_generics> for ::http::HeaderValue
+        #display_where_clause
+        {
+            fn from(id: &#name #ty_generics) -> Self {
+                <Self as ::std::convert::TryFrom<::std::string::String>>::try_from(
+                    ::std::string::ToString::to_string(id),
+                )
+                .expect(#expect)
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<&::http::HeaderValue> for #name #ty_generics
+        #where_clause
+        {
+            type Error = #error #ty_generics;
+
+            fn try_from(value: &::http::HeaderValue) -> ::std::result::Result<Self, Self::Error> {
+                let value = value.to_str().map_err(#error::NotVisibleAscii)?;
+                ::std::str::FromStr::from_str(value).map_err(#error::Parse)
+            }
+        }
+    }))
+}
+
+/// Implements `fake::Dummy<fake::Faker>` by faking the inner value, which is redrawn until it
+/// passes the check given with `#[id(validate(...))]`, if any.
+#[cfg(feature = "fake")]
+pub fn fake(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ty: ::fake::Dummy<::fake::Faker>));
+    let where_clause = &generics.where_clause;
+    let dummy = quote!(<#ty as ::fake::Dummy<::fake::Faker>>::dummy_with_rng(config, rng));
+    let body = if let Some(Validate { function, .. }) = &input.config.validate {
+        quote! {
+            loop {
+                let inner = #dummy;
+                if #function(&inner).is_ok() {
+                    return Self(inner);
+                }
+            }
+        }
+    } else {
+        quote!(Self(#dummy))
+    };
+    Ok(quote! {
+        impl #impl_generics ::fake::Dummy<::fake::Faker> for #name #ty_generics #where_clause {
+            fn dummy_with_rng<R: ::fake::Rng + ?::std::marker::Sized>(
+                config: &::fake::Faker,
+                rng: &mut R,
+            ) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Implements `redb::Value` and `redb::Key` with the fixed-width, order-preserving key encoding
+/// of the `Bytes` derive.
+#[cfg(feature = "redb")]
+pub fn redb(input: &IdInput) -> syn::Result<TokenStream> {
+    require_key_encoding(input)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let encode = encode_key(input, &quote!(value));
+    let decode = decode_key(
+        input,
+        &quote! {
+            ::std::convert::TryInto::try_into(data)
+                .expect(::std::concat!("invalid length of ", ::std::stringify!(#name), " bytes"))
+        },
+    );
+    Ok(quote! {
+        impl #impl_generics ::redb::Value for #name #ty_generics #where_clause {
+            type SelfType<'a> = #name #ty_generics where Self: 'a;
+            type AsBytes<'a> = [u8; ::std::mem::size_of::<#ty>()] where Self: 'a;
+
+            fn fixed_width() -> ::std::option::Option<::std::primitive::usize> {
+                ::std::option::Option::Some(::std::mem::size_of::<#ty>())
+            }
+
+            fn from_bytes<'a>(data: &'a [u8]) -> #name #ty_generics
+            where
+                Self: 'a,
+            {
+                #decode
+            }
+
+            fn as_bytes<'a, 'b: 'a>(value: &'a #name #ty_generics) -> [u8; ::std::mem::size_of::<#ty>()]
+            where
+                Self: 'b,
+            {
+                #encode
+            }
+
+            fn type_name() -> ::redb::TypeName {
+                ::redb::TypeName::new(::std::stringify!(#name))
+            }
+        }
+
+        impl #impl_generics ::redb::Key for #name #ty_generics #where_clause {
+            fn compare(data1: &[u8], data2: &[u8]) -> ::std::cmp::Ordering {
+                ::std::cmp::Ord::cmp(data1, data2)
+            }
+        }
+    })
+}
+
+/// Implements `heed::BytesEncode` and `heed::BytesDecode` for the identifier type as its own
+/// codec, with the order-preserving key encoding of the `Bytes` derive.
+#[cfg(feature = "heed")]
+pub fn heed(input: &IdInput) -> syn::Result<TokenStream> {
+    require_key_encoding(input)?;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, syn::parse_quote!('__heed));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let name = input.ident;
+    let encode = encode_key(input, &quote!(item));
+    let decode = decode_key(input, &quote!(::std::convert::TryInto::try_into(bytes)?));
+    Ok(quote! {
+        impl #impl_generics ::heed::BytesEncode<'__heed> for #name #ty_generics #where_clause {
+            type EItem = Self;
+
+            fn bytes_encode(
+                item: &'__heed Self,
+            ) -> ::std::result::Result<::std::borrow::Cow<'__heed, [u8]>, ::heed::BoxedError> {
+                ::std::result::Result::Ok(::std::borrow::Cow::Owned(::std::vec::Vec::from(#encode)))
+            }
+        }
+
+        impl #impl_generics ::heed::BytesDecode<'__heed> for #name #ty_generics #where_clause {
+            type DItem = Self;
+
+            fn bytes_decode(bytes: &'__heed [u8]) -> ::std::result::Result<Self, ::heed::BoxedError> {
+                ::std::result::Result::Ok(#decode)
+            }
+        }
+    })
+}
+
+/// Implements the tantivy interop matching the inner type: `from_doc_id` and `doc_id` for `u32`,
+/// the fast-field traits and `From<Name>` for `OwnedValue` for `u64` and `i64`, and in addition
+/// `from_doc_address` and `doc_address` for `u64`.
+#[cfg(feature = "tantivy")]
+pub fn tantivy(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let primitive = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.get_ident().map(Ident::to_string),
+        _ => None,
+    };
+    let doc_id = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Creates an identifier from a segment-local tantivy document ID.
+            #unchecked_vis const fn from_doc_id(doc_id: ::tantivy::DocId) -> Self {
+                Self(doc_id)
+            }
+
+            /// Returns the identifier as a segment-local tantivy document ID.
+            #vis const fn doc_id(self) -> ::tantivy::DocId {
+                self.0
+            }
+        }
+    };
+    let fast_value = quote! {
+        impl #impl_generics ::tantivy::columnar::MonotonicallyMappableToU64
+            for #name #ty_generics #where_clause
+        {
+            fn to_u64(self) -> ::std::primitive::u64 {
+                ::tantivy::columnar::MonotonicallyMappableToU64::to_u64(self.0)
+            }
+
+            fn from_u64(val: ::std::primitive::u64) -> Self {
+                Self(::tantivy::columnar::MonotonicallyMappableToU64::from_u64(val))
+            }
+        }
+
+        impl #impl_generics ::tantivy::fastfield::FastValue for #name #ty_generics #where_clause {
+            fn to_type() -> ::tantivy::schema::Type {
+                <#ty as ::tantivy::fastfield::FastValue>::to_type()
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#name #ty_generics>
+            for ::tantivy::schema::OwnedValue #where_clause
+        {
+            fn from(id: #name #ty_generics) -> Self {
+                Self::from(id.0)
+            }
+        }
+    };
+    match primitive.as_deref() {
+        Some("u32") => Ok(doc_id),
+        Some("i64") => Ok(fast_value),
+        Some("u64") => Ok(quote! {
+            #fast_value
+
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Creates an identifier from the address of a document in the index,
+                /// with the segment ordinal in the high 32 bits and the document ID
+                /// in the low 32 bits.
+                #unchecked_vis const fn from_doc_address(address: ::tantivy::DocAddress) -> Self {
+                    Self(((address.segment_ord as ::std::primitive::u64) << 32) | address.doc_id as ::std::primitive::u64)
+                }
+
+                /// Returns the address of the document encoded by `from_doc_address`.
+                #vis const fn doc_address(self) -> ::tantivy::DocAddress {
+                    ::tantivy::DocAddress {
+                        segment_ord: (self.0 >> 32) as ::tantivy::SegmentOrdinal,
+                        doc_id: self.0 as ::tantivy::DocId,
+                    }
+                }
+            }
+        }),
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            "Deriving Tantivy requires a `u32` inner type, which is a tantivy document ID, \
+             or a `u64` or `i64` inner type, which can be stored in a fast field",
+        )),
+    }
+}
+
+/// Implements `From<Name>` and `From<&Name>` for `egui::Id`, hashing the type name along with
+/// the inner value.
+#[cfg(feature = "egui")]
+pub fn egui(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ty: ::std::hash::Hash));
+    let where_clause = &generics.where_clause;
+    Ok(quote! {
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for ::egui::Id #where_clause {
+            fn from(id: #name #ty_generics) -> Self {
+                Self::from(&id)
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<&#name #ty_generics> for ::egui::Id #where_clause {
+            fn from(id: &#name #ty_generics) -> Self {
+                Self::new(::std::any::type_name::<#name #ty_generics>()).with(&id.0)
+            }
+        }
+    })
+}
+
+/// Implements the `bevy_reflect` traits with `impl_reflect!`, reflecting the identifier as a
+/// tuple struct and registering its `Hash` and `PartialEq` impls, along with `TypePath` naming
+/// the module of the identifier.
+#[cfg(feature = "bevy")]
+pub fn bevy(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_non_generic("as its reflected type path is the name of the type")?;
+    let name = input.ident;
+    let ty = input.inner_ty;
+    Ok(quote! {
+        ::bevy_reflect::impl_reflect! {
+            #[reflect(type_path = false, Hash, PartialEq)]
+            struct #name(#ty);
+        }
+
+        impl ::bevy_reflect::TypePath for #name {
+            fn type_path() -> &'static ::std::primitive::str {
+                ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#name))
+            }
+
+            fn short_type_path() -> &'static ::std::primitive::str {
+                ::std::stringify!(#name)
+            }
+
+            fn type_ident() -> ::std::option::Option<&'static ::std::primitive::str> {
+                ::std::option::Option::Some(::std::stringify!(#name))
+            }
+
+            fn crate_name() -> ::std::option::Option<&'static ::std::primitive::str> {
+                ::std::module_path!().split("::").next()
+            }
+
+            fn module_path() -> ::std::option::Option<&'static ::std::primitive::str> {
+                ::std::option::Option::Some(::std::module_path!())
+            }
+        }
+    })
+}
+
+/// Implements the gdext conversions `GodotConvert`, `ToGodot`, and `FromGodot`, passing
+/// string identifiers as `StringName` and other identifiers as their inner values.
+#[cfg(feature = "godot")]
+pub fn godot(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let meta = quote!(::godot::meta);
+    let result = quote!(::std::result::Result);
+    let validate = input.config.validate.as_ref().map(|Validate { function, .. }| {
+        quote! {
+            #function(&id.0)
+                .map_err(|err| #meta::error::ConvertError::new(::std::string::ToString::to_string(&err)))?;
+        }
+    });
+    let (where_clause, via, pass, to_godot, from_godot) = if is_string(ty) {
+        (
+            input.generics.where_clause.clone(),
+            quote!(::godot::builtin::StringName),
+            quote!(#meta::ByValue),
+            quote!(::godot::builtin::StringName::from(self.0.as_str())),
+            quote!(Self(::std::string::ToString::to_string(&via))),
+        )
+    } else {
+        let mut generics = input.generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#ty: #meta::ToGodot + #meta::FromGodot));
+        (
+            generics.where_clause,
+            quote!(<#ty as #meta::GodotConvert>::Via),
+            quote!(<#ty as #meta::ToGodot>::Pass),
+            quote!(#meta::ToGodot::to_godot(&self.0)),
+            quote!(Self(<#ty as #meta::FromGodot>::try_from_godot(via)?)),
+        )
+    };
+    Ok(quote! {
+        impl #impl_generics #meta::GodotConvert for #name #ty_generics #where_clause {
+            type Via = #via;
+        }
+
+        impl #impl_generics #meta::ToGodot for #name #ty_generics #where_clause {
+            type Pass = #pass;
+
+            fn to_godot(&self) -> #meta::ToArg<'_, Self::Via, Self::Pass> {
+                #to_godot
+            }
+        }
+
+        impl #impl_generics #meta::FromGodot for #name #ty_generics #where_clause {
+            fn try_from_godot(via: Self::Via) -> #result<Self, #meta::error::ConvertError> {
+                let id = #from_godot;
+                #validate
+                #result::Ok(id)
+            }
+        }
+    })
+}
+
+/// Implements `schemars::JsonSchema` with the schema of the inner type, matching the serde
+/// representation of identifiers.
+#[cfg(feature = "schemars")]
+pub fn schemars(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ty: ::schemars::JsonSchema));
+    let where_clause = &generics.where_clause;
+    Ok(quote! {
+        impl #impl_generics ::schemars::JsonSchema for #name #ty_generics #where_clause {
+            fn is_referenceable() -> ::std::primitive::bool {
+                <#ty as ::schemars::JsonSchema>::is_referenceable()
+            }
+
+            fn schema_name() -> ::std::string::String {
+                <#ty as ::schemars::JsonSchema>::schema_name()
+            }
+
+            fn schema_id() -> ::std::borrow::Cow<'static, str> {
+                <#ty as ::schemars::JsonSchema>::schema_id()
+            }
+
+            fn json_schema(
+                generator: &mut ::schemars::r#gen::SchemaGenerator,
+            ) -> ::schemars::schema::Schema {
+                <#ty as ::schemars::JsonSchema>::json_schema(generator)
+            }
+        }
+    })
+}
+
+#[cfg(feature = "speedy")]
+pub fn speedy(input: &IdInput) -> syn::Result<TokenStream> {
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let mut read_generics = input.generics.clone();
+    read_generics.params.insert(0, syn::parse_quote!('speedy));
+    read_generics
+        .params
+        .push(syn::parse_quote!(SpeedyContext: ::speedy::Context));
+    read_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ty: ::speedy::Readable<'speedy, SpeedyContext>));
+    let (read_impl_generics, _, read_where_clause) = read_generics.split_for_impl();
+    let mut write_generics = input.generics.clone();
+    write_generics
+        .params
+        .push(syn::parse_quote!(SpeedyContext: ::speedy::Context));
+    write_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ty: ::speedy::Writable<SpeedyContext>));
+    let (write_impl_generics, _, write_where_clause) = write_generics.split_for_impl();
+    Ok(quote! {
+        impl #read_impl_generics ::speedy::Readable<'speedy, SpeedyContext> for #name #ty_generics
+        #read_where_clause
+        {
+            fn read_from<R: ::speedy::Reader<'speedy, SpeedyContext>>(
+                reader: &mut R,
+            ) -> ::std::result::Result<Self, SpeedyContext::Error> {
+                <#ty as ::speedy::Readable<'speedy, SpeedyContext>>::read_from(reader).map(Self)
+            }
+
+            fn minimum_bytes_needed() -> ::std::primitive::usize {
+                <#ty as ::speedy::Readable<'speedy, SpeedyContext>>::minimum_bytes_needed()
+            }
+        }
+        impl #write_impl_generics ::speedy::Writable<SpeedyContext> for #name #ty_generics
+        #write_where_clause
+        {
+            fn write_to<W: ?Sized + ::speedy::Writer<SpeedyContext>>(
+                &self,
+                writer: &mut W,
+            ) -> ::std::result::Result<(), SpeedyContext::Error> {
+                <#ty as ::speedy::Writable<SpeedyContext>>::write_to(&self.0, writer)
+            }
+
+            fn bytes_needed(&self) -> ::std::result::Result<::std::primitive::usize, SpeedyContext::Error> {
+                <#ty as ::speedy::Writable<SpeedyContext>>::bytes_needed(&self.0)
+            }
+        }
+    })
+}
+
+/// Implements `miniserde::Serialize` and `miniserde::Deserialize`, delegating to the inner type.
+/// Deserialization forwards each scalar to the visitor of the inner type, so inner types
+/// deserialized from sequences or maps are not supported.
+#[cfg(feature = "miniserde")]
+pub fn miniserde(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let bounded_where_clause = |bound: TokenStream| {
+        let mut generics = input.generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#ty: #bound));
+        generics.where_clause
+    };
+    let ser_where_clause = bounded_where_clause(quote!(::miniserde::Serialize));
+    let de_where_clause = bounded_where_clause(quote!(::miniserde::Deserialize));
+    let forward = |method: TokenStream, args: TokenStream, params: TokenStream| {
+        quote! {
+            fn #method(&mut self, #params) -> ::miniserde::Result<()> {
+                let mut inner = ::std::option::Option::None;
+                <#ty as ::miniserde::Deserialize>::begin(&mut inner).#method(#args)?;
+                self.out = inner.map(#name);
+                ::std::result::Result::Ok(())
+            }
+        }
+    };
+    let null = forward(quote!(null), quote!(), quote!());
+    let boolean = forward(
+        quote!(boolean),
+        quote!(b),
+        quote!(b: ::std::primitive::bool),
+    );
+    let string = forward(quote!(string), quote!(s), quote!(s: &::std::primitive::str));
+    let negative = forward(
+        quote!(negative),
+        quote!(n),
+        quote!(n: ::std::primitive::i64),
+    );
+    let nonnegative = forward(
+        quote!(nonnegative),
+        quote!(n),
+        quote!(n: ::std::primitive::u64),
+    );
+    let float = forward(quote!(float), quote!(n), quote!(n: ::std::primitive::f64));
+    Ok(quote! {
+        impl #impl_generics ::miniserde::Serialize for #name #ty_generics #ser_where_clause {
+            fn begin(&self) -> ::miniserde::ser::Fragment<'_> {
+                <#ty as ::miniserde::Serialize>::begin(&self.0)
+            }
+        }
+
+        const _: () = {
+            ::miniserde::make_place!(Place);
+
+            impl #impl_generics ::miniserde::de::Visitor for Place<#name #ty_generics> #de_where_clause {
+                #null
+                #boolean
+                #string
+                #negative
+                #nonnegative
+                #float
+            }
+
+            impl #impl_generics ::miniserde::Deserialize for #name #ty_generics #de_where_clause {
+                fn begin(out: &mut ::std::option::Option<Self>) -> &mut dyn ::miniserde::de::Visitor {
+                    Place::new(out)
+                }
+            }
+        };
+    })
+}
+
+pub fn bytes(input: &IdInput) -> syn::Result<TokenStream> {
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let key = match (&input.config.key, &input.inner_kind) {
+        (None, _) => TokenStream::new(),
+        (Some(_), InnerKind::Other) => key_bytes(input),
+        (Some(key), _) => {
+            return Err(syn::Error::new(
+                key.span(),
+                "`key` requires an integer inner type, which has an order-preserving \
+                 byte encoding",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the memory representation of the inner value in little-endian byte order.
+            #vis fn to_le_bytes(self) -> [u8; ::std::mem::size_of::<#ty>()] {
+                self.0.to_le_bytes()
+            }
+
+            /// Returns the memory representation of the inner value in big-endian byte order.
+            #vis fn to_be_bytes(self) -> [u8; ::std::mem::size_of::<#ty>()] {
+                self.0.to_be_bytes()
+            }
+
+            /// Creates an identifier from its little-endian byte representation.
+            #unchecked_vis fn from_le_bytes(bytes: [u8; ::std::mem::size_of::<#ty>()]) -> Self {
+                Self(<#ty>::from_le_bytes(bytes))
+            }
+
+            /// Creates an identifier from its big-endian byte representation.
+            #unchecked_vis fn from_be_bytes(bytes: [u8; ::std::mem::size_of::<#ty>()]) -> Self {
+                Self(<#ty>::from_be_bytes(bytes))
+            }
+        }
+
+        #key
+    })
+}
+
+/// Implements `to_key_bytes`, `from_key_bytes`, and `to_key`, encoding integer identifiers
+/// as big-endian bytes with the sign bit flipped, whose lexicographic order matches the order
+/// of the identifiers, and generates the `<Name>Key` companion type holding such bytes.
+fn key_bytes(input: &IdInput) -> TokenStream {
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let generics = input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (vis, companion_vis) = input.companion_item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let key = input.companion_name("", "Key");
+    let doc = format!(
+        "The order-preserving byte encoding of a [`{name}`], for use as a key in ordered \
+         key-value stores."
+    );
+    let key_derive = companion_derive(
+        input,
+        &[
+            "Clone",
+            "Copy",
+            "Debug",
+            "PartialEq",
+            "Eq",
+            "PartialOrd",
+            "Ord",
+            "Hash",
+        ],
+    );
+    let encode = encode_key(input, &quote!(self));
+    let decode = decode_key(input, &quote!(bytes));
+    input.in_companion_module(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the inner value in big-endian byte order, with the sign bit flipped for
+            /// signed integers, so that comparing the bytes lexicographically, as ordered
+            /// key-value stores do, orders them the same as the identifiers.
+            #vis fn to_key_bytes(self) -> [u8; ::std::mem::size_of::<#ty>()] {
+                #encode
+            }
+
+            /// Creates an identifier from the bytes returned by `to_key_bytes`.
+            #unchecked_vis fn from_key_bytes(bytes: [u8; ::std::mem::size_of::<#ty>()]) -> Self {
+                #decode
+            }
+
+            /// Returns the bytes returned by `to_key_bytes` wrapped in a key type, which can be
+            /// passed directly to the key-value store.
+            #vis fn to_key(self) -> #key #ty_generics {
+                #key(self.to_key_bytes())
+            }
+        }
+
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #key_derive
+        #companion_vis struct #key #generics ([u8; ::std::mem::size_of::<#ty>()]) #where_clause;
+
+        impl #impl_generics #key #ty_generics #where_clause {
+            /// Returns the identifier encoded in the key.
+            #vis fn id(&self) -> #name #ty_generics {
+                <#name #ty_generics>::from_key_bytes(self.0)
+            }
+        }
+
+        impl #impl_generics ::std::convert::AsRef<[u8]> for #key #ty_generics #where_clause {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl #impl_generics ::std::borrow::Borrow<[u8]> for #key #ty_generics #where_clause {
+            fn borrow(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for #key #ty_generics
+        #where_clause
+        {
+            fn from(id: #name #ty_generics) -> Self {
+                id.to_key()
+            }
+        }
+    })
+}
+
+/// Returns the expression encoding the identifier `id` into the order-preserving key bytes:
+/// the inner integer in big-endian byte order, with the sign bit flipped if signed. XOR-ing
+/// with the minimum flips exactly the sign bit of a signed integer and nothing of an unsigned
+/// one.
+fn encode_key(input: &IdInput, id: &TokenStream) -> TokenStream {
+    let ty = input.inner_ty;
+    quote!((#id.0 ^ <#ty>::MIN).to_be_bytes())
+}
+
+/// Returns the expression decoding the identifier from the array `bytes` produced by the
+/// expression of `encode_key`.
+fn decode_key(input: &IdInput, bytes: &TokenStream) -> TokenStream {
+    let name = input.ident;
+    let ty = input.inner_ty;
+    quote!(#name(<#ty>::from_be_bytes(#bytes) ^ <#ty>::MIN))
+}
+
+/// Reports an error unless the inner type is a plain integer, which the order-preserving key
+/// encoding requires.
+#[cfg(any(feature = "redb", feature = "heed"))]
+fn require_key_encoding(input: &IdInput) -> syn::Result<()> {
+    input.require_numeric()?;
+    match input.inner_kind {
+        InnerKind::Other => Ok(()),
+        InnerKind::Float | InnerKind::Wrapper { .. } => Err(syn::Error::new_spanned(
+            input.inner_ty,
+            format!(
+                "Deriving {} requires an integer inner type, which has an order-preserving \
+                 byte encoding",
+                input.derive_name
+            ),
+        )),
+    }
+}
+
+/// Implements LEB128 variable-length encoding of integer identifiers: `encode_varint` and
+/// `decode_varint` over byte buffers, and `write_varint` and `read_varint` over I/O streams.
+/// Signed integers are zigzag-encoded first, so that small negative values stay short.
+pub fn varint(input: &IdInput) -> syn::Result<TokenStream> {
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (unsigned, signed) = varint_repr(ty)?;
+    let (zigzag, unzigzag) = if signed {
+        (
+            quote!(((self.0 << 1) ^ (self.0 >> (<#ty>::BITS - 1))) as #unsigned),
+            quote!(((value >> 1) as #ty) ^ -((value & 1) as #ty)),
+        )
+    } else {
+        (quote!(self.0), quote!(value))
+    };
+    let usize = quote!(::std::primitive::usize);
+    let io = quote!(::std::io);
+    let decode_step = varint_step(
+        &unsigned,
+        &quote!(return ::std::option::Option::None;),
+        &quote!(return ::std::option::Option::Some((Self(#unzigzag), len + 1));),
+    );
+    let read_step = varint_step(
+        &unsigned,
+        &quote! {
+            return ::std::result::Result::Err(#io::Error::new(
+                #io::ErrorKind::InvalidData,
+                ::std::concat!("varint overflows ", ::std::stringify!(#name)),
+            ));
+        },
+        &quote!(return ::std::result::Result::Ok(Self(#unzigzag));),
+    );
+    let push = varint_push(&zigzag);
+    let deltas = varint_deltas(input, &unsigned, signed);
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Maximum length in bytes of the varint encoding of an identifier.
+            #vis const VARINT_MAX_LEN: #usize = <#unsigned>::BITS.div_ceil(7) as #usize;
+
+            /// Appends the LEB128 encoding of the identifier to `buf`, returning the number
+            /// of bytes appended.
+            #vis fn encode_varint(self, buf: &mut ::std::vec::Vec<::std::primitive::u8>) -> #usize {
+                let start = buf.len();
+                #push
+                buf.len() - start
+            }
+
+            /// Decodes an identifier from the LEB128 encoding at the start of `bytes`, returning
+            /// it with the number of bytes read, or `None` if `bytes` ends before the encoding
+            /// does or the encoded value does not fit in the inner type.
+            #unchecked_vis fn decode_varint(
+                bytes: &[::std::primitive::u8],
+            ) -> ::std::option::Option<(Self, #usize)> {
+                let mut value: #unsigned = 0;
+                let mut shift = 0;
+                for (len, &byte) in bytes.iter().enumerate() {
+                    #decode_step
+                }
+                ::std::option::Option::None
+            }
+
+            /// Writes the LEB128 encoding of the identifier to `writer`.
+            ///
+            /// # Errors
+            ///
+            /// Returns any error of writing to `writer`.
+            #vis fn write_varint<W: #io::Write + ?::std::marker::Sized>(
+                self,
+                writer: &mut W,
+            ) -> #io::Result<()> {
+                let mut buf = ::std::vec::Vec::with_capacity(Self::VARINT_MAX_LEN);
+                self.encode_varint(&mut buf);
+                writer.write_all(&buf)
+            }
+
+            /// Reads an identifier in the LEB128 encoding from `reader`, consuming exactly
+            /// the bytes of the encoding.
+            ///
+            /// # Errors
+            ///
+            /// Returns any error of reading from `reader`, an error of kind `UnexpectedEof` if
+            /// the input ends before the encoding does, and one of kind `InvalidData` if
+            /// the encoded value does not fit in the inner type.
+            #unchecked_vis fn read_varint<R: #io::Read + ?::std::marker::Sized>(
+                reader: &mut R,
+            ) -> #io::Result<Self> {
+                let mut value: #unsigned = 0;
+                let mut shift = 0;
+                loop {
+                    let mut buf = [0; 1];
+                    reader.read_exact(&mut buf)?;
+                    let byte = buf[0];
+                    #read_step
+                }
+            }
+        }
+
+        #deltas
+    })
+}
+
+/// Returns the statements adding the low 7 bits of `byte` to the decoded `value` of type
+/// `unsigned`, which run `overflow` if the bits do not fit, and `done` after the last byte.
+fn varint_step(unsigned: &Ident, overflow: &TokenStream, done: &TokenStream) -> TokenStream {
+    quote! {
+        let low = <#unsigned>::from(byte & 0x7f);
+        if shift >= <#unsigned>::BITS || (low << shift) >> shift != low {
+            #overflow
+        }
+        value |= low << shift;
+        if byte & 0x80 == 0 {
+            #done
+        }
+        shift += 7;
+    }
+}
+
+/// Returns the statements appending the LEB128 encoding of the unsigned `value` to `buf`.
+fn varint_push(value: &TokenStream) -> TokenStream {
+    quote! {
+        let mut value = #value;
+        while value >= 0x80 {
+            buf.push((value & 0x7f) as ::std::primitive::u8 | 0x80);
+            value >>= 7;
+        }
+        buf.push(value as ::std::primitive::u8);
+    }
+}
+
+/// Implements gap encoding of sorted identifiers: `delta_encode` and `delta_decode` adapting
+/// iterators, and `encode_deltas` and `decode_deltas` storing the gaps as varints. The first
+/// identifier is stored as is, and the following ones as differences from their predecessors.
+fn varint_deltas(input: &IdInput, unsigned: &Ident, signed: bool) -> TokenStream {
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (to_gap, from_gap) = if signed {
+        (quote!(gap as #unsigned), quote!(gap as #ty))
+    } else {
+        (quote!(gap), quote!(gap))
+    };
+    let option = quote!(::std::option::Option);
+    let vec = quote!(::std::vec::Vec);
+    let push = varint_push(&quote!(gap));
+    let step = varint_step(
+        unsigned,
+        &quote!(return #option::None;),
+        &quote! {
+            gaps.push(value);
+            value = 0;
+            shift = 0;
+            continue;
+        },
+    );
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the gaps between consecutive identifiers of `ids`, which must be sorted
+            /// in ascending order, starting with the first identifier itself. Gaps between
+            /// close identifiers are small, which makes them compress well, e.g., as varints.
+            ///
+            /// Unsorted identifiers are reported with a panic in debug builds.
+            #vis fn delta_encode<I: ::std::iter::IntoIterator<Item = Self>>(
+                ids: I,
+            ) -> impl ::std::iter::Iterator<Item = #unsigned> {
+                let mut prev: #option<#ty> = #option::None;
+                ids.into_iter().map(move |id| {
+                    let gap = match prev {
+                        #option::Some(prev) => {
+                            ::std::debug_assert!(prev <= id.0, "delta-encoded identifiers must be sorted");
+                            id.0.wrapping_sub(prev)
+                        }
+                        #option::None => id.0,
+                    };
+                    prev = #option::Some(id.0);
+                    #to_gap
+                })
+            }
+
+            /// Returns the identifiers whose gaps, as returned by `delta_encode`, are `gaps`.
+            #unchecked_vis fn delta_decode<I: ::std::iter::IntoIterator<Item = #unsigned>>(
+                gaps: I,
+            ) -> impl ::std::iter::Iterator<Item = Self> {
+                let mut prev: #option<#ty> = #option::None;
+                gaps.into_iter().map(move |gap| {
+                    let gap = #from_gap;
+                    let id = prev.map_or(gap, |prev| prev.wrapping_add(gap));
+                    prev = #option::Some(id);
+                    Self(id)
+                })
+            }
+
+            /// Appends the gaps of `ids`, which must be sorted in ascending order, to `buf`
+            /// in the LEB128 encoding, returning the number of identifiers encoded.
+            ///
+            /// Unsorted identifiers are reported with a panic in debug builds.
+            #vis fn encode_deltas<I: ::std::iter::IntoIterator<Item = Self>>(
+                ids: I,
+                buf: &mut #vec<::std::primitive::u8>,
+            ) -> ::std::primitive::usize {
+                let mut count = 0;
+                for gap in Self::delta_encode(ids) {
+                    #push
+                    count += 1;
+                }
+                count
+            }
+
+            /// Decodes the identifiers encoded by `encode_deltas` in all of `bytes`, returning
+            /// `None` if `bytes` ends in the middle of a gap or a gap does not fit in the inner
+            /// type.
+            #unchecked_vis fn decode_deltas(bytes: &[::std::primitive::u8]) -> #option<#vec<Self>> {
+                let mut gaps = #vec::new();
+                let mut value: #unsigned = 0;
+                let mut shift = 0;
+                for &byte in bytes {
+                    #step
+                }
+                if shift == 0 {
+                    #option::Some(Self::delta_decode(gaps).collect())
+                } else {
+                    #option::None
+                }
+            }
+        }
+    }
+}
+
+/// Returns the unsigned integer type holding the varint-encoded value of the primitive
+/// integer `ty`, and whether `ty` is signed and needs zigzag encoding.
+fn varint_repr(ty: &Type) -> syn::Result<(Ident, bool)> {
+    unsigned_counterpart(ty).ok_or_else(|| {
+        syn::Error::new_spanned(
+            ty,
+            "Varint requires a primitive integer inner type, such as `u32` or `i64`",
+        )
+    })
+}
+
+/// Returns the unsigned counterpart of `ty` if it is a primitive integer, such as `u32` for
+/// `i32` or `u32`, and whether `ty` is signed.
+fn unsigned_counterpart(ty: &Type) -> Option<(Ident, bool)> {
+    const UNSIGNED: [&str; 6] = ["u8", "u16", "u32", "u64", "u128", "usize"];
+    const SIGNED: [&str; 6] = ["i8", "i16", "i32", "i64", "i128", "isize"];
+    let ident = match ty {
+        Type::Path(path) => path.path.get_ident(),
+        _ => None,
+    };
+    let position = |names: &[&str]| ident.and_then(|ident| names.iter().position(|n| ident == n));
+    if let Some(index) = position(&UNSIGNED) {
+        Some((format_ident!("{}", UNSIGNED[index]), false))
+    } else {
+        position(&SIGNED).map(|index| (format_ident!("{}", UNSIGNED[index]), true))
+    }
+}
+
+#[cfg(feature = "step_trait")]
+pub fn step(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    Ok(quote! {
+        impl #impl_generics ::std::iter::Step for #name #ty_generics #where_clause {
+            fn steps_between(
+                start: &Self,
+                end: &Self,
+            ) -> (::std::primitive::usize, ::std::option::Option<::std::primitive::usize>) {
+                <#ty as ::std::iter::Step>::steps_between(&start.0, &end.0)
+            }
+
+            fn forward_checked(start: Self, count: ::std::primitive::usize) -> ::std::option::Option<Self> {
+                <#ty as ::std::iter::Step>::forward_checked(start.0, count).map(Self)
+            }
+
+            fn backward_checked(start: Self, count: ::std::primitive::usize) -> ::std::option::Option<Self> {
+                <#ty as ::std::iter::Step>::backward_checked(start.0, count).map(Self)
+            }
+        }
+    })
+}
+
+pub fn range(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (vis, companion_vis) = input.companion_item_vis();
+    let generics = input.generics;
+    let range = input.companion_name("", "Range");
+    let range_inclusive = input.companion_name("", "RangeInclusive");
+    let range_doc = format!(
+        "A half-open range of [`{name}`] identifiers, which iterates over the identifiers \
+         it contains."
+    );
+    let range_inclusive_doc = format!(
+        "A closed range of [`{name}`] identifiers, which iterates over the identifiers \
+         it contains."
+    );
+    let range_derive = companion_derive(input, &["Clone", "Debug", "PartialEq", "Eq"]);
+    let iter = range_iterator(input, &range, false);
+    let iter_inclusive = range_iterator(input, &range_inclusive, true);
+    let span = range_span(input, &range, false);
+    let span_inclusive = range_span(input, &range_inclusive, true);
+    let par_iter = if cfg!(feature = "rayon") {
+        par_range(input, &range, &range_inclusive)
+    } else {
+        TokenStream::new()
+    };
+    Ok(input.in_companion_module(quote! {
+        #[doc = #range_doc]
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        #range_derive
+        #companion_vis struct #range #generics (::std::ops::Range<#ty>) #where_clause;
+
+        #iter
+
+        #span
+
+        #[doc = #range_inclusive_doc]
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        #range_derive
+        #companion_vis struct #range_inclusive #generics (::std::ops::RangeInclusive<#ty>) #where_clause;
+
+        #iter_inclusive
+
+        #span_inclusive
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns an iterator over identifiers from `start` (inclusive) to `end` (exclusive).
+            #vis fn range(start: Self, end: Self) -> #range #ty_generics {
+                #range(start.0..end.0)
+            }
+
+            /// Returns an iterator over identifiers from `start` to `end`, both inclusive.
+            #vis fn range_inclusive(start: Self, end: Self) -> #range_inclusive #ty_generics {
+                #range_inclusive(start.0..=end.0)
+            }
+
+            /// Returns an iterator over identifiers from `self` (inclusive) to `end` (exclusive).
+            #vis fn to(self, end: Self) -> #range #ty_generics {
+                Self::range(self, end)
+            }
+
+            /// Returns an iterator over identifiers from `self` to `end`, both inclusive.
+            #vis fn to_inclusive(self, end: Self) -> #range_inclusive #ty_generics {
+                Self::range_inclusive(self, end)
+            }
+
+            /// Returns an iterator yielding `self`, `self + 1`, `self + 2`, and so on,
+            /// until the maximum value of the inner type is reached.
+            #vis fn successors(self) -> impl ::std::iter::FusedIterator<Item = Self> {
+                ::std::iter::successors(
+                    ::std::option::Option::Some(self),
+                    |id| id.0.checked_add(1).map(Self),
+                )
+            }
+        }
+
+        #par_iter
+    }))
+}
+
+/// Implements `rayon::iter::IntoParallelIterator` for the range companion types, mapping
+/// the parallel iterator of the inner range to identifiers.
+fn par_range(input: &IdInput, range: &Ident, range_inclusive: &Ident) -> TokenStream {
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let par_iter = |range: &Ident, std_range: TokenStream| {
+        let mut generics = input.generics.clone();
+        let predicates = &mut generics.make_where_clause().predicates;
+        predicates
+            .push(syn::parse_quote!(#std_range: ::rayon::iter::IntoParallelIterator<Item = #ty>));
+        predicates.push(syn::parse_quote!(#name #ty_generics: ::std::marker::Send));
+        let where_clause = &generics.where_clause;
+        quote! {
+            impl #impl_generics ::rayon::iter::IntoParallelIterator for #range #ty_generics
+            #where_clause
+            {
+                type Iter = ::rayon::iter::Map<
+                    <#std_range as ::rayon::iter::IntoParallelIterator>::Iter,
+                    fn(#ty) -> #name #ty_generics,
+                >;
+                type Item = #name #ty_generics;
+
+                fn into_par_iter(self) -> Self::Iter {
+                    ::rayon::iter::ParallelIterator::map(
+                        ::rayon::iter::IntoParallelIterator::into_par_iter(self.0),
+                        #name,
+                    )
+                }
+            }
+        }
+    };
+    let par_range = par_iter(range, quote!(::std::ops::Range<#ty>));
+    let par_range_inclusive = par_iter(range_inclusive, quote!(::std::ops::RangeInclusive<#ty>));
+    quote!(#par_range #par_range_inclusive)
+}
+
+/// Implements `Iterator`, `DoubleEndedIterator`, and `FusedIterator` for a range companion
+/// type, as well as `ExactSizeIterator` if the `Range` or, if `inclusive`, `RangeInclusive`
+/// of the inner type implements it.
+fn range_iterator(input: &IdInput, range: &Ident, inclusive: bool) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let std_range = if inclusive {
+        quote!(::std::ops::RangeInclusive<#ty>)
+    } else {
+        quote!(::std::ops::Range<#ty>)
+    };
+    // Ranges of 64-bit and wider integers are not `ExactSizeIterator`. The bound is made
+    // higher-ranked so that, for such an inner type, the impl is skipped instead of rejected
+    // as a trivially unsatisfiable bound.
+    let mut exact_generics = input.generics.clone();
+    exact_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(for<'__range> #std_range: ::std::iter::ExactSizeIterator));
+    let exact_where_clause = &exact_generics.where_clause;
+    quote! {
+        impl #impl_generics ::std::iter::Iterator for #range #ty_generics #where_clause {
+            type Item = #name #ty_generics;
+            fn next(&mut self) -> ::std::option::Option<#name #ty_generics> {
+                self.0.next().map(#name)
+            }
+            fn size_hint(&self) -> (::std::primitive::usize, ::std::option::Option<::std::primitive::usize>) {
+                self.0.size_hint()
+            }
+        }
+
+        impl #impl_generics ::std::iter::DoubleEndedIterator for #range #ty_generics #where_clause {
+            fn next_back(&mut self) -> ::std::option::Option<#name #ty_generics> {
+                self.0.next_back().map(#name)
+            }
+        }
+
+        impl #impl_generics ::std::iter::FusedIterator for #range #ty_generics #where_clause {}
+
+        impl #impl_generics ::std::iter::ExactSizeIterator for #range #ty_generics
+        #exact_where_clause
+        {
+        }
+    }
+}
+
+/// Implements the methods treating a range companion type as a span of identifiers, and
+/// `From` of the matching `Range` or, if `inclusive`, `RangeInclusive` of identifiers.
+fn range_span(input: &IdInput, range: &Ident, inclusive: bool) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let (vis, _) = input.companion_item_vis();
+    let (std_range, start, end, end_doc, from) = if inclusive {
+        (
+            quote!(::std::ops::RangeInclusive),
+            quote!(*self.0.start()),
+            quote!(*self.0.end()),
+            "Returns the last identifier in the range.",
+            quote! {
+                let (start, end) = range.into_inner();
+                Self(start.0..=end.0)
+            },
+        )
+    } else {
+        (
+            quote!(::std::ops::Range),
+            quote!(self.0.start),
+            quote!(self.0.end),
+            "Returns the identifier right after the range.",
+            quote!(Self(range.start.0..range.end.0)),
+        )
+    };
+    quote! {
+        impl #impl_generics #range #ty_generics #where_clause {
+            /// Returns the first identifier in the range, or in what remains of it if partially
+            /// iterated.
+            #vis fn start(&self) -> #name #ty_generics {
+                #name(#start)
+            }
+
+            #[doc = #end_doc]
+            #vis fn end(&self) -> #name #ty_generics {
+                #name(#end)
+            }
+
+            /// Returns `true` if `id` is in the range.
+            #vis fn contains(&self, id: #name #ty_generics) -> ::std::primitive::bool {
+                self.0.contains(&id.0)
+            }
+
+            /// Returns the number of identifiers in the range, saturating at `usize::MAX`.
+            #vis fn len(&self) -> ::std::primitive::usize {
+                self.0.size_hint().0
+            }
+
+            /// Returns `true` if the range contains no identifiers.
+            #vis fn is_empty(&self) -> ::std::primitive::bool {
+                self.0.is_empty()
+            }
+
+            /// Returns an iterator over the identifiers in the range, leaving the range intact.
+            #vis fn iter(&self) -> Self {
+                ::std::clone::Clone::clone(self)
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#std_range<#name #ty_generics>>
+            for #range #ty_generics #where_clause
+        {
+            fn from(range: #std_range<#name #ty_generics>) -> Self {
+                #from
+            }
+        }
+    }
+}
+
+/// Implements `gallop`, `intersect`, and `union` over slices of identifiers sorted in ascending
+/// order of their inner values.
+pub fn sorted(input: &IdInput) -> syn::Result<TokenStream> {
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let result = quote!(::std::result::Result);
+    let vec = quote!(::std::vec::Vec);
+    let ordering = quote!(::std::cmp::Ordering);
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Searches the sorted `ids` for `id` like `binary_search`, but first doubles
+            /// the searched prefix until it contains `id`, which takes time logarithmic in
+            /// the returned position rather than in the length of `ids`. Returns `Ok` with
+            /// the position of `id` if found, and `Err` with the position where it would be
+            /// inserted otherwise.
+            #vis fn gallop(
+                ids: &[Self],
+                id: &Self,
+            ) -> #result<::std::primitive::usize, ::std::primitive::usize> {
+                let mut bound = 1;
+                while bound < ids.len() && ids[bound - 1].0 < id.0 {
+                    bound *= 2;
+                }
+                let start = bound / 2;
+                let end = ::std::cmp::min(bound, ids.len());
+                match ids[start..end].binary_search_by(|probe| probe.0.cmp(&id.0)) {
+                    #result::Ok(pos) => #result::Ok(start + pos),
+                    #result::Err(pos) => #result::Err(start + pos),
+                }
+            }
+
+            /// Returns the identifiers present in both sorted and deduplicated `a` and `b`,
+            /// in ascending order. If one slice is much shorter, its identifiers are looked up
+            /// in the other with `gallop`, otherwise the slices are merged.
+            #vis fn intersect(a: &[Self], b: &[Self]) -> #vec<Self> {
+                let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+                let mut ids = #vec::with_capacity(short.len());
+                if short.len() < long.len() / 32 {
+                    let mut rest = long;
+                    for id in short {
+                        match Self::gallop(rest, id) {
+                            #result::Ok(pos) => {
+                                ids.push(id.clone());
+                                rest = &rest[pos + 1..];
+                            }
+                            #result::Err(pos) => rest = &rest[pos..],
+                        }
+                    }
+                    return ids;
+                }
+                let (mut i, mut j) = (0, 0);
+                while i < short.len() && j < long.len() {
+                    match short[i].0.cmp(&long[j].0) {
+                        #ordering::Less => i += 1,
+                        #ordering::Greater => j += 1,
+                        #ordering::Equal => {
+                            ids.push(short[i].clone());
+                            i += 1;
+                            j += 1;
+                        }
+                    }
+                }
+                ids
+            }
+
+            /// Returns the identifiers present in either of sorted and deduplicated `a` and
+            /// `b`, in ascending order and without duplicates.
+            #vis fn union(a: &[Self], b: &[Self]) -> #vec<Self> {
+                let mut ids = #vec::with_capacity(a.len() + b.len());
+                let (mut i, mut j) = (0, 0);
+                while i < a.len() && j < b.len() {
+                    match a[i].0.cmp(&b[j].0) {
+                        #ordering::Less => {
+                            ids.push(a[i].clone());
+                            i += 1;
+                        }
+                        #ordering::Greater => {
+                            ids.push(b[j].clone());
+                            j += 1;
+                        }
+                        #ordering::Equal => {
+                            ids.push(a[i].clone());
+                            i += 1;
+                            j += 1;
+                        }
+                    }
+                }
+                ids.extend_from_slice(&a[i..]);
+                ids.extend_from_slice(&b[j..]);
+                ids
+            }
+        }
+    })
+}
+
+/// Implements `get_in` and `get_in_mut`, looking up the element of a slice at the position
+/// given by the inner value.
+pub fn index_slice(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_integer()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let option = quote!(::std::option::Option);
+    let index = quote! {
+        <::std::primitive::usize as ::std::convert::TryFrom<#ty>>::try_from(self.0).ok()?
+    };
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns a reference to the element of `slice` at the position given by
+            /// the identifier, or `None` if it is out of bounds.
+            #vis fn get_in<'slice, Item>(&self, slice: &'slice [Item]) -> #option<&'slice Item> {
+                slice.get(#index)
+            }
+
+            /// Returns a mutable reference to the element of `slice` at the position given by
+            /// the identifier, or `None` if it is out of bounds.
+            #vis fn get_in_mut<'slice, Item>(
+                &self,
+                slice: &'slice mut [Item],
+            ) -> #option<&'slice mut Item> {
+                slice.get_mut(#index)
+            }
+        }
+    })
+}
+
+/// Generates the `Atomic<Name>` companion type wrapping the atomic integer matching
+/// the inner type, e.g., `AtomicU32` for `u32`.
+pub fn atomic(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_non_generic(&format!(
+        "as the generated `{}` companion type is not generic",
+        input.companion_name("Atomic", "")
+    ))?;
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let atomic_ty = match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            path.path
+                .get_ident()
+                .and_then(|ident| match ident.to_string().as_str() {
+                    "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64"
+                    | "isize" => {
+                        let atomic = format_ident!("Atomic{}", ident.to_string().to_uppercase());
+                        Some(quote!(::std::sync::atomic::#atomic))
+                    }
+                    _ => None,
+                })
+        }
+        _ => None,
+    };
+    let atomic_ty = atomic_ty.ok_or_else(|| {
+        syn::Error::new_spanned(
+            ty,
+            "Atomic requires a primitive integer inner type, such as `u32`, `u64`, or `usize`",
+        )
+    })?;
+    let (vis, companion_vis) = input.companion_item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let atomic = input.companion_name("Atomic", "");
+    let doc = format!("A [`{name}`] that can be shared between threads.");
+    let atomic_derive = companion_derive(input, &["Debug", "Default"]);
+    let ordering = quote!(::std::sync::atomic::Ordering);
+    Ok(input.in_companion_module(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Default)]
+        #atomic_derive
+        #companion_vis struct #atomic(#atomic_ty);
+
+        impl #atomic {
+            /// Creates a new atomic identifier.
+            #vis const fn new(id: #name) -> Self {
+                Self(#atomic_ty::new(id.0))
+            }
+
+            /// Loads the identifier.
+            #vis fn load(&self, order: #ordering) -> #name {
+                #name(self.0.load(order))
+            }
+
+            /// Stores the identifier.
+            #vis fn store(&self, id: #name, order: #ordering) {
+                self.0.store(id.0, order);
+            }
+
+            /// Stores the identifier, returning the previous one.
+            #vis fn swap(&self, id: #name, order: #ordering) -> #name {
+                #name(self.0.swap(id.0, order))
+            }
+
+            /// Stores `new` if the current identifier is `current`. Returns the previous
+            /// identifier, as `Ok` if it was replaced and as `Err` otherwise.
+            #vis fn compare_exchange(
+                &self,
+                current: #name,
+                new: #name,
+                success: #ordering,
+                failure: #ordering,
+            ) -> ::std::result::Result<#name, #name> {
+                self.0
+                    .compare_exchange(current.0, new.0, success, failure)
+                    .map(#name)
+                    .map_err(#name)
+            }
+
+            /// Adds `delta` to the identifier, wrapping around on overflow, and returns
+            /// the previous identifier.
+            #unchecked_vis fn fetch_add(&self, delta: #ty, order: #ordering) -> #name {
+                #name(self.0.fetch_add(delta, order))
+            }
+
+            /// Stores the maximum of the current identifier and `id`, returning the previous one.
+            #vis fn fetch_max(&self, id: #name, order: #ordering) -> #name {
+                #name(self.0.fetch_max(id.0, order))
+            }
+
+            /// Consumes the atomic and returns the identifier.
+            #vis fn into_inner(self) -> #name {
+                #name(self.0.into_inner())
+            }
+        }
+
+        impl ::std::convert::From<#name> for #atomic {
+            fn from(id: #name) -> Self {
+                Self::new(id)
+            }
+        }
+    }))
+}
+
+/// Generates the `<Name>Pool` companion type, handing out identifiers in increasing order
+/// and reusing released ones.
+pub fn pool(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_non_generic(&format!(
+        "as the generated `{}` companion type is not generic",
+        input.companion_name("", "Pool")
+    ))?;
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (vis, companion_vis) = input.companion_item_vis();
+    let pool = input.companion_name("", "Pool");
+    let doc = format!("A thread-safe pool of [`{name}`] identifiers that reuses released ones.");
+    let pool_derive = companion_derive(input, &["Debug"]);
+    let exhausted = format!("{pool} exhausted");
+    Ok(input.in_companion_module(quote! {
+        #[doc = #doc]
+        #[derive(Debug)]
+        #pool_derive
+        #companion_vis struct #pool {
+            /// The next never acquired inner value, or `None` if all have been acquired,
+            /// followed by the released ones.
+            state: ::std::sync::Mutex<(::std::option::Option<#ty>, ::std::vec::Vec<#ty>)>,
+        }
+
+        impl #pool {
+            /// Creates a pool starting at the default inner value, e.g., 0.
+            #vis fn new() -> Self {
+                Self::starting_at(#name(::std::default::Default::default()))
+            }
+
+            /// Creates a pool whose first acquired identifier is `first`.
+            #vis fn starting_at(first: #name) -> Self {
+                Self {
+                    state: ::std::sync::Mutex::new((
+                        ::std::option::Option::Some(first.0),
+                        ::std::vec::Vec::new(),
+                    )),
+                }
+            }
+
+            /// Returns the most recently released identifier, or a new one if none is released.
+            ///
+            /// # Panics
+            ///
+            /// Panics if all values of the inner type are in use.
+            #vis fn acquire(&self) -> #name {
+                self.try_acquire().expect(#exhausted)
+            }
+
+            /// Returns the most recently released identifier, or a new one if none is released,
+            /// or `None` if all values of the inner type are in use.
+            #vis fn try_acquire(&self) -> ::std::option::Option<#name> {
+                let mut state = self
+                    .state
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner);
+                let (next, released) = &mut *state;
+                if let ::std::option::Option::Some(inner) = released.pop() {
+                    return ::std::option::Option::Some(#name(inner));
+                }
+                let inner = (*next)?;
+                *next = inner.checked_add(1);
+                ::std::option::Option::Some(#name(inner))
+            }
+
+            /// Returns `id` to the pool, to be handed out again by `acquire`.
+            /// The identifier must have been acquired from this pool and not released since.
+            #vis fn release(&self, id: #name) {
+                self.state
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+                    .1
+                    .push(id.0);
+            }
+        }
+
+        impl ::std::default::Default for #pool {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    }))
+}
+
+/// Generates the `<Name>DashMap<V>` companion type, a concurrent map wrapping
+/// `dashmap::DashMap` keyed by the inner values, whose methods take identifiers.
+#[cfg(feature = "dashmap")]
+pub fn dashmap(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_non_generic(&format!(
+        "as the generated `{}` companion type is not generic",
+        input.companion_name("", "DashMap")
+    ))?;
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (vis, companion_vis) = input.companion_item_vis();
+    let map = input.companion_name("", "DashMap");
+    let doc = format!("A concurrent map keyed by [`{name}`] identifiers.");
+    let map_derive = companion_derive(input, &["Debug", "Default"]);
+    Ok(input.in_companion_module(quote! {
+        #[doc = #doc]
+        #[derive(Debug)]
+        #map_derive
+        #companion_vis struct #map<V>(::dashmap::DashMap<#ty, V>);
+
+        impl<V> #map<V> {
+            /// Creates an empty map.
+            #vis fn new() -> Self {
+                Self(::dashmap::DashMap::new())
+            }
+
+            /// Creates an empty map with room for at least `capacity` entries.
+            #vis fn with_capacity(capacity: ::std::primitive::usize) -> Self {
+                Self(::dashmap::DashMap::with_capacity(capacity))
+            }
+
+            /// Inserts `value` for `id`, returning the value it replaces, if any.
+            #vis fn insert(&self, id: #name, value: V) -> ::std::option::Option<V> {
+                self.0.insert(id.0, value)
+            }
+
+            /// Returns a reference to the value for `id`, if any, which holds a read lock
+            /// on its shard until dropped.
+            #vis fn get(&self, id: &#name) -> ::std::option::Option<::dashmap::mapref::one::Ref<'_, #ty, V>> {
+                self.0.get(&id.0)
+            }
+
+            /// Returns a mutable reference to the value for `id`, if any, which holds a write
+            /// lock on its shard until dropped.
+            #vis fn get_mut(&self, id: &#name) -> ::std::option::Option<::dashmap::mapref::one::RefMut<'_, #ty, V>> {
+                self.0.get_mut(&id.0)
+            }
+
+            /// Removes the value for `id`, returning it, if any.
+            #vis fn remove(&self, id: &#name) -> ::std::option::Option<V> {
+                self.0.remove(&id.0).map(|(_, value)| value)
+            }
+
+            /// Returns whether the map contains a value for `id`.
+            #vis fn contains_key(&self, id: &#name) -> ::std::primitive::bool {
+                self.0.contains_key(&id.0)
+            }
+
+            /// Returns the number of entries.
+            #vis fn len(&self) -> ::std::primitive::usize {
+                self.0.len()
+            }
+
+            /// Returns whether the map is empty.
+            #vis fn is_empty(&self) -> ::std::primitive::bool {
+                self.0.is_empty()
+            }
+
+            /// Removes all entries.
+            #vis fn clear(&self) {
+                self.0.clear();
+            }
+
+            /// Returns the underlying map, keyed by the inner values.
+            #vis fn into_inner(self) -> ::dashmap::DashMap<#ty, V> {
+                self.0
+            }
+        }
+
+        impl<V> ::std::default::Default for #map<V> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    }))
+}
+
+/// Generates the `<Name>RoaringSet` companion type, a compressed set of identifiers wrapping
+/// `RoaringBitmap` for `u32` inner types and `RoaringTreemap` for `u64` ones.
+#[cfg(feature = "roaring")]
+pub fn roaring(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_non_generic(&format!(
+        "as the generated `{}` companion type is not generic",
+        input.companion_name("", "RoaringSet")
+    ))?;
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (bitmap, module) = match ty {
+        Type::Path(path) if path.path.is_ident("u32") => {
+            (quote!(::roaring::RoaringBitmap), quote!(::roaring::bitmap))
+        }
+        Type::Path(path) if path.path.is_ident("u64") => (
+            quote!(::roaring::RoaringTreemap),
+            quote!(::roaring::treemap),
+        ),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "Deriving Roaring requires a `u32` or `u64` inner type",
+            ))
+        }
+    };
+    let (vis, companion_vis) = input.companion_item_vis();
+    let set = input.companion_name("", "RoaringSet");
+    let doc = format!("A compressed set of [`{name}`] identifiers, backed by a roaring bitmap.");
+    let set_derive = companion_derive(input, &["Clone", "Debug", "Default", "PartialEq"]);
+    let bool = quote!(::std::primitive::bool);
+    let option = quote!(::std::option::Option);
+    let iter = roaring_iter(name, ty, &set, &module);
+    let ops = roaring_ops(&set);
+    Ok(input.in_companion_module(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        #set_derive
+        #companion_vis struct #set(#bitmap);
+
+        impl #set {
+            /// Creates an empty set.
+            #vis fn new() -> Self {
+                Self(#bitmap::new())
+            }
+
+            /// Adds `id` to the set, returning whether it was not present.
+            #vis fn insert(&mut self, id: #name) -> #bool {
+                self.0.insert(id.0)
+            }
+
+            /// Removes `id` from the set, returning whether it was present.
+            #vis fn remove(&mut self, id: &#name) -> #bool {
+                self.0.remove(id.0)
+            }
+
+            /// Returns whether the set contains `id`.
+            #vis fn contains(&self, id: &#name) -> #bool {
+                self.0.contains(id.0)
+            }
+
+            /// Returns the number of identifiers in the set.
+            #vis fn len(&self) -> ::std::primitive::u64 {
+                self.0.len()
+            }
+
+            /// Returns whether the set is empty.
+            #vis fn is_empty(&self) -> #bool {
+                self.0.is_empty()
+            }
+
+            /// Removes all identifiers.
+            #vis fn clear(&mut self) {
+                self.0.clear();
+            }
+
+            /// Returns the smallest identifier in the set, if any.
+            #vis fn min(&self) -> #option<#name> {
+                self.0.min().map(#name)
+            }
+
+            /// Returns the largest identifier in the set, if any.
+            #vis fn max(&self) -> #option<#name> {
+                self.0.max().map(#name)
+            }
+
+            /// Returns whether all identifiers in the set are also in `other`.
+            #vis fn is_subset(&self, other: &Self) -> #bool {
+                self.0.is_subset(&other.0)
+            }
+
+            /// Returns whether the set has no identifiers in common with `other`.
+            #vis fn is_disjoint(&self, other: &Self) -> #bool {
+                self.0.is_disjoint(&other.0)
+            }
+
+            /// Returns an iterator over the identifiers in ascending order.
+            #vis fn iter(&self) -> ::std::iter::Map<#module::Iter<'_>, fn(#ty) -> #name> {
+                self.0.iter().map(#name)
+            }
+
+            /// Returns the underlying bitmap of the inner values.
+            #vis fn as_bitmap(&self) -> &#bitmap {
+                &self.0
+            }
+
+            /// Returns the underlying bitmap of the inner values.
+            #vis fn into_bitmap(self) -> #bitmap {
+                self.0
+            }
+        }
+
+        impl ::std::convert::From<#bitmap> for #set {
+            fn from(bitmap: #bitmap) -> Self {
+                Self(bitmap)
+            }
+        }
+
+        #iter
+
+        #ops
+    }))
+}
+
+/// Implements the conversions of the `<Name>RoaringSet` companion type `set` from and into
+/// iterators of identifiers, whose bitmap iterators are in `module`.
+#[cfg(feature = "roaring")]
+fn roaring_iter(name: &Ident, ty: &Type, set: &Ident, module: &TokenStream) -> TokenStream {
+    quote! {
+        impl ::std::iter::FromIterator<#name> for #set {
+            fn from_iter<I: ::std::iter::IntoIterator<Item = #name>>(ids: I) -> Self {
+                Self(ids.into_iter().map(|id| id.0).collect())
+            }
+        }
+
+        impl ::std::iter::Extend<#name> for #set {
+            fn extend<I: ::std::iter::IntoIterator<Item = #name>>(&mut self, ids: I) {
+                self.0.extend(ids.into_iter().map(|id| id.0));
+            }
+        }
+
+        impl<'a> ::std::iter::IntoIterator for &'a #set {
+            type Item = #name;
+            type IntoIter = ::std::iter::Map<#module::Iter<'a>, fn(#ty) -> #name>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl ::std::iter::IntoIterator for #set {
+            type Item = #name;
+            type IntoIter = ::std::iter::Map<#module::IntoIter, fn(#ty) -> #name>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter().map(#name)
+            }
+        }
+    }
+}
+
+/// Implements the set operators of the `<Name>RoaringSet` companion type `set`, delegating to
+/// those of the underlying bitmap.
+#[cfg(feature = "roaring")]
+fn roaring_ops(set: &Ident) -> TokenStream {
+    let ops = [
+        ("BitOr", "bitor", "union"),
+        ("BitAnd", "bitand", "intersection"),
+        ("BitXor", "bitxor", "symmetric difference"),
+        ("Sub", "sub", "difference"),
+    ]
+    .iter()
+    .map(|(op, method, description)| {
+        let op_assign = format_ident!("{}Assign", op);
+        let method_assign = format_ident!("{}_assign", method);
+        let op = format_ident!("{}", op);
+        let method = format_ident!("{}", method);
+        let doc = format!("Returns the {description} of the sets.");
+        let doc_assign = format!("Replaces the set with the {description} of the sets.");
+        quote! {
+            impl ::std::ops::#op for #set {
+                type Output = Self;
+                #[doc = #doc]
+                fn #method(self, rhs: Self) -> Self {
+                    Self(::std::ops::#op::#method(self.0, rhs.0))
+                }
+            }
+
+            impl ::std::ops::#op<&#set> for &#set {
+                type Output = #set;
+                #[doc = #doc]
+                fn #method(self, rhs: &#set) -> #set {
+                    #set(::std::ops::#op::#method(&self.0, &rhs.0))
+                }
+            }
+
+            impl ::std::ops::#op_assign for #set {
+                #[doc = #doc_assign]
+                fn #method_assign(&mut self, rhs: Self) {
+                    ::std::ops::#op_assign::#method_assign(&mut self.0, rhs.0);
+                }
+            }
+
+            impl ::std::ops::#op_assign<&#set> for #set {
+                #[doc = #doc_assign]
+                fn #method_assign(&mut self, rhs: &#set) {
+                    ::std::ops::#op_assign::#method_assign(&mut self.0, &rhs.0);
+                }
+            }
+        }
+    });
+    quote!(#(#ops)*)
+}
+
+/// Implements `set_label`, `label`, and `remove_label`, associating human-readable labels with
+/// identifiers in a registry shared by all values of the type, and `Debug` showing the label
+/// after the inner value when present.
+pub fn label(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_non_generic("as its labels are kept in a `static` registry")?;
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let vis = input.item_vis();
+    let registry = quote! {
+        ::std::sync::Mutex<::std::collections::HashMap<#ty, ::std::string::String>>
+    };
+    Ok(quote! {
+        impl #name {
+            /// Returns the registry of labels, created empty on first use.
+            fn label_registry() -> &'static #registry {
+                static LABELS: ::std::sync::OnceLock<#registry> = ::std::sync::OnceLock::new();
+                LABELS.get_or_init(::std::default::Default::default)
+            }
+
+            /// Associates `label` with `id`, returning the label it replaces, if any.
+            #vis fn set_label(
+                id: Self,
+                label: impl ::std::convert::Into<::std::string::String>,
+            ) -> ::std::option::Option<::std::string::String> {
+                Self::label_registry()
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+                    .insert(id.0, label.into())
+            }
+
+            /// Returns the label associated with the identifier, if any.
+            #vis fn label(&self) -> ::std::option::Option<::std::string::String> {
+                Self::label_registry()
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+                    .get(&self.0)
+                    .cloned()
+            }
+
+            /// Removes the label associated with `id`, returning it, if any.
+            #vis fn remove_label(id: Self) -> ::std::option::Option<::std::string::String> {
+                Self::label_registry()
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+                    .remove(&id.0)
+            }
+        }
+
+        impl ::std::fmt::Debug for #name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let mut tuple = f.debug_tuple(::std::stringify!(#name));
+                tuple.field(&self.0);
+                if let ::std::option::Option::Some(label) = self.label() {
+                    tuple.field(&label);
+                }
+                tuple.finish()
+            }
+        }
+    })
+}
+
+/// Implements `test` and `test_sequence`, constructing deterministic identifiers for tests,
+/// compiled only under `cfg(test)` or the `test-util` feature of the crate using the derive.
+pub fn fixture(input: &IdInput) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let vis = input.item_vis();
+    let overflow = format!("{{}} does not fit in {name}");
+    let inner = match (TimeOrdered::of(ty), non_numeric(ty)) {
+        (Some(TimeOrdered::UuidV7), _) => quote!(<#ty>::from_u128(::std::primitive::u128::from(n))),
+        (Some(TimeOrdered::Ulid), _) => quote!(<#ty>::from(::std::primitive::u128::from(n))),
+        (None, Some("string")) => {
+            let prefix = format!("{}-", snake_case(&name.to_string()));
+            quote!(::std::convert::From::from(
+                ::std::format!("{}{}", #prefix, n)
+            ))
+        }
+        (None, Some(kind)) => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!("Cannot derive Fixture for a {kind} inner type"),
+            ))
+        }
+        (None, None) => {
+            let (primitive, wrap) = match &input.inner_kind {
+                InnerKind::Wrapper {
+                    wrapper, primitive, ..
+                } => (*primitive, quote!(#wrapper)),
+                InnerKind::Float | InnerKind::Other => (ty, TokenStream::new()),
+            };
+            quote! {
+                #wrap(
+                    <#primitive as ::std::convert::TryFrom<::std::primitive::u32>>::try_from(n)
+                        .unwrap_or_else(|_| ::std::panic!(#overflow, n))
+                )
+            }
+        }
+    };
+    Ok(quote! {
+        #[allow(unexpected_cfgs)]
+        const _: () = {
+            #[cfg(any(test, feature = "test-util"))]
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Returns the `n`-th test identifier, which is the same on every call and
+                /// distinct from the identifiers returned for other values of `n`.
+                #vis fn test(n: ::std::primitive::u32) -> Self {
+                    Self(#inner)
+                }
+
+                /// Returns an iterator over distinct test identifiers, starting with
+                /// `Self::test(1)`.
+                #vis fn test_sequence() -> impl ::std::iter::Iterator<Item = Self> {
+                    (1..).map(Self::test)
+                }
+            }
+        };
+    })
+}
+
+/// Generates the `<Name>Generator` companion type, handing out monotonic, time-ordered
+/// identifiers: version 7 UUIDs for `Uuid` and monotonic ULIDs for `Ulid`.
+pub fn generator(input: &IdInput) -> syn::Result<TokenStream> {
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let layout = TimeOrdered::of(ty).ok_or_else(|| {
+        syn::Error::new_spanned(ty, "Generator requires a `Uuid` or `Ulid` inner type")
+    })?;
+    let (random_bits, construct) = time_ordered_layout(ty, layout);
+    let (vis, companion_vis) = input.companion_item_vis();
+    let generator = input.companion_name("", "Generator");
+    let doc = format!("A thread-safe generator of monotonic, time-ordered [`{name}`] identifiers.");
+    let generator_derive = companion_derive(input, &["Debug", "Default"]);
+    Ok(input.in_companion_module(quote! {
+        #[doc = #doc]
+        #generator_derive
+        #companion_vis struct #generator {
+            /// Milliseconds since the Unix epoch.
+            clock: ::std::boxed::Box<dyn ::std::ops::Fn() -> ::std::primitive::u64 + ::std::marker::Send + ::std::marker::Sync>,
+            /// Timestamp and random bits of the last identifier, if any, followed by
+            /// the state of the random number generator.
+            state: ::std::sync::Mutex<(
+                ::std::option::Option<(::std::primitive::u64, ::std::primitive::u128)>,
+                ::std::primitive::u64,
+            )>,
+        }
+
+        impl #generator {
+            /// Mask of the bits following the timestamp that are not fixed by the layout.
+            const RANDOM_MASK: ::std::primitive::u128 = (1 << #random_bits) - 1;
+
+            /// Creates a generator reading the system clock, with a random seed.
+            #vis fn new() -> Self {
+                use ::std::hash::BuildHasher;
+                let seed = ::std::collections::hash_map::RandomState::new().hash_one(#generator::system_millis());
+                Self::with_clock(#generator::system_millis, seed)
+            }
+
+            /// Creates a generator reading milliseconds since the Unix epoch from `clock`,
+            /// whose random bits are fully determined by `seed`.
+            #vis fn with_clock<C>(clock: C, seed: ::std::primitive::u64) -> Self
+            where
+                C: ::std::ops::Fn() -> ::std::primitive::u64
+                    + ::std::marker::Send
+                    + ::std::marker::Sync
+                    + 'static,
+            {
+                Self {
+                    clock: ::std::boxed::Box::new(clock),
+                    state: ::std::sync::Mutex::new((::std::option::Option::None, seed)),
+                }
+            }
+
+            /// Returns a new identifier, greater than all identifiers previously generated.
+            ///
+            /// Identifiers within the same millisecond share the timestamp and have
+            /// the random bits of the first one incremented. If the clock goes back, or if
+            /// the random bits run out, the timestamp of the last identifier is reused or
+            /// incremented, respectively, rather than breaking the order.
+            #vis fn generate(&self) -> #name {
+                let now = (self.clock)() & ((1 << 48) - 1);
+                let mut state = self
+                    .state
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner);
+                let (last, rng) = &mut *state;
+                let (millis, random) = match *last {
+                    ::std::option::Option::Some((millis, random))
+                        if now <= millis && random < Self::RANDOM_MASK =>
+                    {
+                        (millis, random + 1)
+                    }
+                    ::std::option::Option::Some((millis, _)) if now <= millis => {
+                        (millis + 1, #generator::next_random(rng))
+                    }
+                    _ => (now, #generator::next_random(rng)),
+                };
+                *last = ::std::option::Option::Some((millis, random));
+                #name(#construct)
+            }
+
+            /// Returns random bits with the most significant one cleared, leaving room
+            /// for increments within the same millisecond.
+            fn next_random(rng: &mut ::std::primitive::u64) -> ::std::primitive::u128 {
+                let mut next = || {
+                    // SplitMix64.
+                    *rng = rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                    let mut z = *rng;
+                    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                    ::std::primitive::u128::from(z ^ (z >> 31))
+                };
+                let bits = (next() << 64) | next();
+                bits & (Self::RANDOM_MASK >> 1)
+            }
+
+            fn system_millis() -> ::std::primitive::u64 {
+                ::std::time::SystemTime::now()
+                    .duration_since(::std::time::UNIX_EPOCH)
+                    .map_or(0, |elapsed| {
+                        ::std::convert::TryFrom::try_from(elapsed.as_millis())
+                            .unwrap_or(::std::primitive::u64::MAX)
+                    })
+            }
+        }
+
+        impl ::std::default::Default for #generator {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl ::std::fmt::Debug for #generator {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(::std::stringify!(#generator)).finish_non_exhaustive()
+            }
+        }
+    }))
+}
+
+/// Implements `shard`, requested with `#[id(shard)]`, mapping the identifier to one of
+/// a number of shards.
+pub fn shard(input: &IdInput) -> syn::Result<TokenStream> {
+    let Some(hash) = input.config.shard else {
+        return Ok(TokenStream::new());
+    };
+    let value = match &input.inner_kind {
+        InnerKind::Wrapper { .. } => quote!(self.0 .0),
+        InnerKind::Float => {
+            return Err(syn::Error::new_spanned(
+                input.inner_ty,
+                "`shard` requires an integer inner type",
+            ))
+        }
+        InnerKind::Other => quote!(self.0),
+    };
+    let shard = match hash {
+        ShardHash::Modulo => quote! {
+            (#value as ::std::primitive::u64 % ::std::primitive::u64::from(num_shards))
+                as ::std::primitive::u32
+        },
+        ShardHash::Multiplicative => quote! {
+            let hash = (#value as ::std::primitive::u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            ((::std::primitive::u128::from(hash) * ::std::primitive::u128::from(num_shards)) >> 64)
+                as ::std::primitive::u32
+        },
+    };
+    let (output, shard) = match &input.config.shard_output {
+        Some(output) => (
+            quote!(#output),
+            quote!(::std::convert::From::from({ #shard })),
+        ),
+        None => (quote!(::std::primitive::u32), shard),
+    };
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the shard of the identifier, less than `num_shards`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `num_shards` is 0.
+            #vis fn shard(self, num_shards: ::std::primitive::u32) -> #output {
+                ::std::assert!(num_shards > 0, "number of shards must be positive");
+                #shard
+            }
+        }
+    })
+}
+
+/// Implements `from_hash`, requested with `#[id(from_hash)]`, constructing the identifier from
+/// the hash of a value computed with a pinned hash function.
+pub fn from_hash(input: &IdInput) -> syn::Result<TokenStream> {
+    let Some(hash) = input.config.from_hash else {
+        return Ok(TokenStream::new());
+    };
+    let construct = match &input.inner_kind {
+        InnerKind::Wrapper {
+            wrapper, primitive, ..
+        } => quote!(#wrapper(hasher.0 as #primitive)),
+        InnerKind::Float => {
+            return Err(syn::Error::new_spanned(
+                input.inner_ty,
+                "`from_hash` requires an integer inner type",
+            ))
+        }
+        InnerKind::Other => {
+            let ty = input.inner_ty;
+            quote!(hasher.0 as #ty)
+        }
+    };
+    let (init, write) = match hash {
+        StableHash::Fnv1a => (
+            quote!(0xcbf2_9ce4_8422_2325),
+            quote! {
+                for &byte in bytes {
+                    self.0 = (self.0 ^ ::std::primitive::u64::from(byte))
+                        .wrapping_mul(0x0100_0000_01b3);
+                }
+            },
+        ),
+        StableHash::Fx => (
+            quote!(0),
+            quote! {
+                let mut mix = |word: ::std::primitive::u64| {
+                    self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x517c_c1b7_2722_0a95);
+                };
+                let mut chunks = bytes.chunks_exact(8);
+                for chunk in &mut chunks {
+                    let mut word = [0; 8];
+                    word.copy_from_slice(chunk);
+                    mix(::std::primitive::u64::from_le_bytes(word));
+                }
+                for &byte in chunks.remainder() {
+                    mix(::std::primitive::u64::from(byte));
+                }
+            },
+        ),
+    };
+    let writes = [
+        "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+    ]
+    .iter()
+    .map(|primitive| {
+        let method = format_ident!("write_{}", primitive);
+        let primitive = format_ident!("{}", primitive);
+        quote! {
+            fn #method(&mut self, value: ::std::primitive::#primitive) {
+                self.write(&value.to_le_bytes());
+            }
+        }
+    });
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the identifier whose inner value is the hash of `value`, which is
+            /// the same on every run and platform for the same `Hash` impl.
+            #vis fn from_hash<H: ::std::hash::Hash + ?::std::marker::Sized>(value: &H) -> Self {
+                /// Hasher writing integers as little-endian bytes, and `usize` and `isize`
+                /// as 64-bit integers, so that hashes do not depend on the platform.
+                struct StableHasher(::std::primitive::u64);
+
+                impl ::std::hash::Hasher for StableHasher {
+                    fn finish(&self) -> ::std::primitive::u64 {
+                        self.0
+                    }
+
+                    fn write(&mut self, bytes: &[::std::primitive::u8]) {
+                        #write
+                    }
+
+                    #(#writes)*
+
+                    fn write_usize(&mut self, value: ::std::primitive::usize) {
+                        self.write_u64(value as ::std::primitive::u64);
+                    }
+
+                    fn write_isize(&mut self, value: ::std::primitive::isize) {
+                        self.write_i64(value as ::std::primitive::i64);
+                    }
+                }
+
+                let mut hasher = StableHasher(#init);
+                ::std::hash::Hash::hash(value, &mut hasher);
+                Self(#construct)
+            }
+        }
+    })
+}
+
+/// Implements `from_bytes`, requested with `#[id(content_hash = "...")]`, constructing
+/// the identifier from a cryptographic digest of the bytes truncated to the inner type.
+pub fn content_hash(input: &IdInput) -> syn::Result<TokenStream> {
+    if input.config.content_hash.is_none() {
+        return Ok(TokenStream::new());
+    }
+    let (primitive, wrap) = match &input.inner_kind {
+        InnerKind::Wrapper {
+            wrapper, primitive, ..
+        } => (*primitive, quote!(#wrapper)),
+        InnerKind::Float => {
+            return Err(syn::Error::new_spanned(
+                input.inner_ty,
+                "`content_hash` requires an integer inner type",
+            ))
+        }
+        InnerKind::Other => (input.inner_ty, TokenStream::new()),
+    };
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the identifier whose inner value is the first bytes of the BLAKE3
+            /// digest of `bytes`, read in little-endian byte order.
+            #vis fn from_bytes(bytes: &[::std::primitive::u8]) -> Self {
+                let digest = ::blake3::hash(bytes);
+                let mut inner = [0; ::std::mem::size_of::<#primitive>()];
+                let len = inner.len();
+                inner.copy_from_slice(&digest.as_bytes()[..len]);
+                Self(#wrap(<#primitive>::from_le_bytes(inner)))
+            }
+        }
+    })
+}
+
+/// Implements `to_public` and `from_public`, requested with `#[id(scramble(key = ...))]`,
+/// permuting the inner value with a balanced Feistel network keyed by `key`.
+pub fn scramble(input: &IdInput) -> syn::Result<TokenStream> {
+    let Some(Scramble { key }) = &input.config.scramble else {
+        return Ok(TokenStream::new());
+    };
+    let ty = input.inner_ty;
+    let half: u32 = match ty {
+        Type::Path(path) if path.path.is_ident("u8") => 4,
+        Type::Path(path) if path.path.is_ident("u16") => 8,
+        Type::Path(path) if path.path.is_ident("u32") => 16,
+        Type::Path(path) if path.path.is_ident("u64") => 32,
+        Type::Path(path) if path.path.is_ident("u128") => 64,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`scramble` requires a fixed-width unsigned integer inner type, \
+                 such as `u32` or `u64`",
+            ))
+        }
+    };
+    let u64 = quote!(::std::primitive::u64);
+    let u128 = quote!(::std::primitive::u128);
+    let prelude = quote! {
+        const HALF: ::std::primitive::u32 = #half;
+        const MASK: #u64 = #u64::MAX >> (64 - HALF);
+        const ROUNDS: ::std::primitive::u32 = 4;
+        fn scramble_round(half: #u64, round: ::std::primitive::u32) -> #u64 {
+            let key: #u64 = #key;
+            // SplitMix64 finalizer of the half, keyed differently in each round.
+            let mut z = half
+                ^ key.rotate_left(round * 16)
+                ^ #u64::from(round + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            (z ^ (z >> 31)) & MASK
+        }
+    };
+    let split = quote! {
+        let mut left = (value >> HALF) as #u64;
+        let mut right = value as #u64 & MASK;
+    };
+    let join = quote!(((#u128::from(left) << HALF) | #u128::from(right)) as #ty);
+    let vis = input.item_vis();
+    let unchecked_vis = input.unchecked_vis(&vis);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the public value of the identifier, a keyed permutation of the inner value
+            /// that hides the order of identifiers, and which `from_public` reverses.
+            #vis fn to_public(self) -> #ty {
+                #prelude
+                let value = #u128::from(self.0);
+                #split
+                for round in 0..ROUNDS {
+                    let next = left ^ scramble_round(right, round);
+                    left = right;
+                    right = next;
+                }
+                #join
+            }
+
+            /// Returns the identifier whose public value, as returned by `to_public`, is `public`.
+            #unchecked_vis fn from_public(public: #ty) -> Self {
+                #prelude
+                let value = #u128::from(public);
+                #split
+                for round in (0..ROUNDS).rev() {
+                    let prev = right ^ scramble_round(left, round);
+                    right = left;
+                    left = prev;
+                }
+                Self(#join)
+            }
+        }
+    })
+}
+
+/// Implements `timestamp_millis` and `created_at`, reading the creation time encoded in
+/// a `Uuid` (version 7), `Ulid`, or snowflake identifier.
+pub fn timestamp(input: &IdInput) -> syn::Result<TokenStream> {
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let millis = match (&input.config.snowflake, TimeOrdered::of(input.inner_ty)) {
+        (Some(Snowflake { epoch, shift }), _) => {
+            quote!((self.0 as ::std::primitive::u64 >> #shift) + #epoch)
+        }
+        (None, Some(TimeOrdered::UuidV7)) => {
+            quote!((self.0.as_u128() >> 80) as ::std::primitive::u64)
+        }
+        (None, Some(TimeOrdered::Ulid)) => quote!(self.0.timestamp_ms()),
+        (None, None) => {
+            return Err(syn::Error::new_spanned(
+                input.inner_ty,
+                "Timestamp requires a `Uuid` or `Ulid` inner type, \
+                 or `#[id(snowflake(...))]` for an integer inner type",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the creation time of the identifier in milliseconds since the Unix epoch.
+            #vis fn timestamp_millis(&self) -> ::std::primitive::u64 {
+                #millis
+            }
+
+            /// Returns the creation time of the identifier.
+            #vis fn created_at(&self) -> ::std::time::SystemTime {
+                ::std::time::UNIX_EPOCH
+                    + ::std::time::Duration::from_millis(self.timestamp_millis())
+            }
+        }
+    })
+}
+
+/// Returns the number of bits following the 48-bit timestamp of `layout`, which are random for
+/// the first identifier in a millisecond and incremented for the following ones, along with
+/// the expression constructing `ty` from `millis` and `random`.
+fn time_ordered_layout(ty: &Type, layout: TimeOrdered) -> (u32, TokenStream) {
+    match layout {
+        TimeOrdered::UuidV7 => (
+            74_u32,
+            quote! {
+                #ty::from_u128(
+                    (::std::primitive::u128::from(millis) << 80)
+                        | (0x7 << 76)
+                        | ((random >> 62) << 64)
+                        | (0b10 << 62)
+                        | (random & ((1 << 62) - 1)),
+                )
+            },
+        ),
+        TimeOrdered::Ulid => (
+            80,
+            quote! {
+                <#ty as ::std::convert::From<::std::primitive::u128>>::from(
+                    (::std::primitive::u128::from(millis) << 80) | random,
+                )
+            },
+        ),
+    }
+}
+
+pub fn succ(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_integer()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the next identifier.
+            ///
+            /// # Panics
+            ///
+            /// Panics on overflow if overflow checks are enabled.
+            #vis fn next(self) -> Self {
+                Self(self.0 + 1)
+            }
+
+            /// Returns the previous identifier.
+            ///
+            /// # Panics
+            ///
+            /// Panics on overflow if overflow checks are enabled.
+            #vis fn prev(self) -> Self {
+                Self(self.0 - 1)
+            }
+
+            /// Returns the next identifier, or `None` on overflow.
+            #vis fn checked_next(self) -> ::std::option::Option<Self> {
+                self.0.checked_add(1).map(Self)
+            }
+
+            /// Returns the previous identifier, or `None` on overflow.
+            #vis fn checked_prev(self) -> ::std::option::Option<Self> {
+                self.0.checked_sub(1).map(Self)
+            }
+        }
+    })
+}
+
+pub fn distance(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    let (output, abs_diff) = match unsigned_counterpart(ty) {
+        Some((unsigned, _)) => (quote!(#unsigned), quote!(self.0.abs_diff(other.0))),
+        None => (
+            quote!(#ty),
+            quote! {
+                if self.0 > other.0 {
+                    self.0 - other.0
+                } else {
+                    other.0 - self.0
+                }
+            },
+        ),
+    };
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the absolute difference between `self` and `other`.
+            #vis fn abs_diff(self, other: Self) -> #output {
+                #abs_diff
+            }
+
+            /// Returns the distance between `self` and `other`; alias of `abs_diff`.
+            #vis fn distance(self, other: Self) -> #output {
+                self.abs_diff(other)
+            }
+        }
+    })
+}
+
+pub fn offset(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_integer()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Moves the identifier forward or backward by `delta`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the result is out of range of the inner type.
+            #vis fn offset(self, delta: isize) -> Self {
+                self.checked_offset(delta)
+                    .expect("offset out of range of the identifier type")
+            }
+
+            /// Moves the identifier forward or backward by `delta`,
+            /// returning `None` if the result is out of range of the inner type.
+            #vis fn checked_offset(self, delta: isize) -> ::std::option::Option<Self> {
+                let value = <::std::primitive::i128 as ::std::convert::TryFrom<_>>::try_from(self.0).ok()?;
+                let delta = <::std::primitive::i128 as ::std::convert::TryFrom<_>>::try_from(delta).ok()?;
+                let value = value.checked_add(delta)?;
+                <#ty as ::std::convert::TryFrom<_>>::try_from(value).ok().map(Self)
+            }
+
+            /// Moves the identifier forward or backward by `delta`,
+            /// wrapping around at the boundary of the inner type.
+            #vis fn wrapping_offset(self, delta: isize) -> Self {
+                Self(self.0.wrapping_add(delta as #ty))
+            }
+        }
+    })
+}
+
+pub fn bounds(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_integer()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Checks if the inner value is zero.
+            #vis fn is_zero(&self) -> ::std::primitive::bool {
+                self.0 == 0
+            }
+
+            /// Checks if the inner value is the smallest value of the inner type.
+            #vis fn is_min(&self) -> ::std::primitive::bool {
+                self.0 == <#ty>::MIN
+            }
+
+            /// Checks if the inner value is the largest value of the inner type.
+            #vis fn is_max(&self) -> ::std::primitive::bool {
+                self.0 == <#ty>::MAX
+            }
+        }
+    })
+}
+
+pub fn pow(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_numeric()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Raises the inner value to the power of `exp`.
+            ///
+            /// # Panics
+            ///
+            /// Panics on overflow if overflow checks are enabled.
+            #vis fn pow(self, exp: ::std::primitive::u32) -> Self {
+                Self(self.0.pow(exp))
+            }
+
+            /// Raises the inner value to the power of `exp`, returning `None` on overflow.
+            #vis fn checked_pow(self, exp: ::std::primitive::u32) -> ::std::option::Option<Self> {
+                self.0.checked_pow(exp).map(Self)
+            }
+        }
+    })
+}
+
+pub fn bit_info(input: &IdInput) -> syn::Result<TokenStream> {
+    input.require_integer()?;
+    let vis = input.item_vis();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = input.ident;
+    let ty = input.inner_ty;
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The size of the inner type in bits.
+            #vis const BITS: ::std::primitive::u32 = <#ty>::BITS;
+
+            /// Returns the number of leading zeros in the binary representation of the inner value.
+            #vis fn leading_zeros(self) -> ::std::primitive::u32 {
+                self.0.leading_zeros()
+            }
+
+            /// Returns the number of trailing zeros in the binary representation of the inner value.
+            #vis fn trailing_zeros(self) -> ::std::primitive::u32 {
+                self.0.trailing_zeros()
+            }
+
+            /// Returns the number of ones in the binary representation of the inner value.
+            #vis fn count_ones(self) -> ::std::primitive::u32 {
+                self.0.count_ones()
+            }
         }
     })
 }
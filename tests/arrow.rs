@@ -0,0 +1,22 @@
+#![cfg(feature = "arrow")]
+
+use arrow::array::{Array, Int32Array};
+use arrow::datatypes::DataType;
+use id_derive as id;
+
+#[test]
+fn test_arrow() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Arrow)]
+    struct Offset(i32);
+    assert_eq!(Offset::arrow_data_type(), DataType::Int32);
+    let column = Offset::to_arrow_array(&[Offset(-1), Offset(7)]);
+    assert_eq!(column.len(), 2);
+    assert_eq!(column.null_count(), 0);
+    assert_eq!(column.values(), &[-1, 7]);
+
+    let column = Int32Array::from(vec![Some(3), None, Some(5)]);
+    assert_eq!(
+        Offset::from_arrow_array(&column),
+        vec![Some(Offset(3)), None, Some(Offset(5))]
+    );
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "bevy")]
+
+use bevy_ecs::prelude::*;
+use bevy_reflect::{FromReflect, PartialReflect, Reflect, ReflectRef, TypePath, TypeRegistry};
+use id_derive as id;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, id::Bevy)]
+struct UnitId(u32);
+
+#[derive(Component, Reflect)]
+struct Unit {
+    id: UnitId,
+}
+
+#[derive(Resource, Reflect, Default)]
+struct Selection(HashMap<UnitId, Entity>);
+
+#[test]
+fn test_reflect() {
+    let id = UnitId(7);
+    match id.reflect_ref() {
+        ReflectRef::TupleStruct(tuple) => {
+            assert_eq!(tuple.field_len(), 1);
+            assert_eq!(tuple.field(0).unwrap().try_downcast_ref::<u32>(), Some(&7));
+        }
+        _ => panic!("expected a tuple struct"),
+    }
+    assert_eq!(UnitId::from_reflect(&UnitId(3)), Some(UnitId(3)));
+    assert_eq!(UnitId(1).reflect_partial_eq(&UnitId(1)), Some(true));
+    assert!(UnitId(1).reflect_hash().is_some());
+
+    assert_eq!(UnitId::type_path(), concat!(module_path!(), "::UnitId"));
+    assert_eq!(UnitId::short_type_path(), "UnitId");
+    assert_eq!(UnitId::type_ident(), Some("UnitId"));
+    assert_eq!(UnitId::crate_name(), Some("bevy"));
+    assert_eq!(UnitId::module_path(), Some(module_path!()));
+
+    let mut registry = TypeRegistry::default();
+    registry.register::<Selection>();
+    assert!(registry.get_with_type_path(UnitId::type_path()).is_some());
+}
+
+#[test]
+fn test_ecs() {
+    let mut world = World::new();
+    world.init_resource::<Selection>();
+    let entity = world.spawn(Unit { id: UnitId(2) }).id();
+    world
+        .resource_mut::<Selection>()
+        .0
+        .insert(UnitId(2), entity);
+    let selected = world.resource::<Selection>().0[&UnitId(2)];
+    assert_eq!(world.get::<Unit>(selected).unwrap().id, UnitId(2));
+}
@@ -3,3 +3,10 @@ fn ui() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/errors/*.rs");
 }
+
+#[test]
+#[cfg(not(any(feature = "schemars", feature = "blake3")))]
+fn disabled_features() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/errors/features/*.rs");
+}
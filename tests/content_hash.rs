@@ -0,0 +1,20 @@
+#![cfg(feature = "blake3")]
+
+use id_derive as id;
+
+#[test]
+fn test_content_hash() {
+    use std::num::Wrapping;
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(content_hash = "blake3")]
+    struct BlobId(u64);
+    assert_eq!(BlobId::from_bytes(b""), BlobId(0xa6a1_f9f5_b949_13af));
+    assert_eq!(BlobId::from_bytes(b"hello"), BlobId::from_bytes(b"hello"));
+    assert_ne!(BlobId::from_bytes(b"hello"), BlobId::from_bytes(b"hellp"));
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(content_hash = "blake3")]
+    struct ChunkId(Wrapping<u16>);
+    assert_eq!(ChunkId::from_bytes(b""), ChunkId(Wrapping(0x13af)));
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "dashmap")]
+
+use id_derive as id;
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn test_dashmap() {
+    #[derive(Debug, Clone, Copy, PartialEq, id::DashMap)]
+    struct WorkerId(u32);
+    let counts = Arc::new(WorkerIdDashMap::default());
+    assert_eq!(counts.insert(WorkerId(0), 0), None);
+    assert_eq!(counts.insert(WorkerId(1), 100), None);
+    let handles: Vec<_> = (0..4)
+        .map(|worker| {
+            let counts = Arc::clone(&counts);
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    *counts.get_mut(&WorkerId(worker % 2)).unwrap() += 1;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(*counts.get(&WorkerId(0)).unwrap(), 20);
+    assert_eq!(*counts.get(&WorkerId(1)).unwrap(), 120);
+    assert_eq!(counts.len(), 2);
+    assert!(counts.contains_key(&WorkerId(1)));
+    assert_eq!(counts.remove(&WorkerId(1)), Some(120));
+    assert!(!counts.contains_key(&WorkerId(1)));
+    assert!(counts.get(&WorkerId(2)).is_none());
+    counts.clear();
+    assert!(counts.is_empty());
+}
+
+#[test]
+fn test_dashmap_into_inner() {
+    #[derive(Clone, Copy, id::DashMap)]
+    struct KeyId(u8);
+    let map = KeyIdDashMap::with_capacity(4);
+    map.insert(KeyId(3), "three");
+    let inner: dashmap::DashMap<u8, &str> = map.into_inner();
+    assert_eq!(*inner.get(&3).unwrap(), "three");
+}
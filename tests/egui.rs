@@ -0,0 +1,26 @@
+#![cfg(feature = "egui")]
+
+use egui::Id;
+use id_derive as id;
+
+#[test]
+fn test_egui_id() {
+    #[derive(id::Egui)]
+    struct NodeId(u64);
+
+    #[derive(id::Egui)]
+    struct EdgeId(u64);
+
+    #[derive(id::Egui)]
+    struct LabelId<T>(T);
+
+    assert_eq!(Id::from(NodeId(3)), Id::from(&NodeId(3)));
+    assert_ne!(Id::from(NodeId(3)), Id::from(NodeId(4)));
+    assert_ne!(Id::from(NodeId(3)), Id::from(EdgeId(3)));
+    assert_ne!(Id::from(NodeId(3)), Id::new(3_u64));
+    assert_ne!(Id::from(LabelId(3_u64)), Id::from(LabelId(3_u32)));
+    assert_eq!(
+        Id::from(LabelId(String::from("a"))),
+        Id::from(LabelId(String::from("a")))
+    );
+}
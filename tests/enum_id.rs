@@ -0,0 +1,84 @@
+use id_derive as id;
+use std::convert::TryFrom;
+
+#[test]
+fn test_enum_id() {
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, id::EnumId)]
+    #[repr(u16)]
+    enum RoleId {
+        Admin = 1,
+        Editor,
+        Viewer = 1000,
+    }
+    const EDITOR: u16 = RoleId::Editor.discriminant();
+    assert_eq!(EDITOR, 2);
+    assert_eq!(RoleId::Viewer.discriminant(), 1000);
+    assert_eq!(RoleId::try_from(1_u32), Ok(RoleId::Admin));
+    assert_eq!(RoleId::try_from(1000_u64), Ok(RoleId::Viewer));
+    assert_eq!(RoleId::try_from(3_u32), Err(UnknownRoleId(3)));
+    assert_eq!(
+        RoleId::try_from(65_537_u64),
+        Err(UnknownRoleId(65_537)),
+        "values that wrap around to a discriminant are rejected"
+    );
+    assert_eq!(
+        UnknownRoleId(3).to_string(),
+        "unknown RoleId discriminant 3"
+    );
+}
+
+#[test]
+fn test_signed_enum_id() {
+    #[derive(Debug, PartialEq, Eq, id::EnumId)]
+    #[repr(i8)]
+    enum LevelId {
+        Low = -1,
+        High = 1,
+    }
+    assert_eq!(LevelId::Low.discriminant(), -1);
+    assert_eq!(LevelId::try_from(1_u32), Ok(LevelId::High));
+    assert_eq!(LevelId::try_from(u64::MAX), Err(UnknownLevelId(u64::MAX)));
+}
+
+mod region {
+    use super::id;
+
+    #[derive(Debug, PartialEq, Eq, id::EnumId)]
+    #[id(module = region_id, companion_derive(Hash))]
+    #[repr(u64)]
+    pub enum RegionId {
+        Europe,
+    }
+}
+
+#[test]
+fn test_enum_id_module() {
+    use region::region_id::Unknown;
+    use region::RegionId;
+    use std::collections::HashSet;
+    assert_eq!(RegionId::try_from(0_u64), Ok(RegionId::Europe));
+    let errors: HashSet<_> = [Unknown(1), Unknown(1)].iter().copied().collect();
+    assert_eq!(errors.len(), 1);
+    let err: Box<dyn std::error::Error> = Box::new(Unknown(7));
+    assert_eq!(err.to_string(), "unknown RegionId discriminant 7");
+}
+
+#[test]
+fn test_enum_id_variants() {
+    #[derive(Debug, PartialEq, Eq, id::EnumId)]
+    #[repr(u8)]
+    enum ColorId {
+        Red = 4,
+        Green = 2,
+        Blue = 9,
+    }
+    assert_eq!(ColorId::ALL, [ColorId::Red, ColorId::Green, ColorId::Blue]);
+    assert_eq!(
+        ColorId::iter().collect::<Vec<_>>(),
+        vec![ColorId::Red, ColorId::Green, ColorId::Blue]
+    );
+    assert!(ColorId::iter().all(|color| {
+        let discriminant = color.discriminant();
+        ColorId::try_from(u32::from(discriminant)).map(ColorId::discriminant) == Ok(discriminant)
+    }));
+}
@@ -0,0 +1,6 @@
+use id_derive::Atomic;
+
+#[derive(Atomic)]
+struct Test(String);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use id_derive::*;
+
+#[derive(Base32)]
+struct Test(i64);
+
+#[derive(Display)]
+#[id(check_digit = "luhn", base32)]
+struct Both(u64);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use id_derive::Display;
+
+#[derive(Display)]
+#[id(check_digit = "luhn")]
+struct Test(i32);
+
+#[derive(Display)]
+#[id(check_digit = "verhoeff")]
+struct Other(u32);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use id_derive::{Div, DivAssign};
+
+#[derive(Div, DivAssign)]
+#[id(div = "checked")]
+struct Test(u32);
+
+#[derive(Div)]
+#[id(div = "unchecked")]
+struct Unknown(u32);
+
+fn main() {}
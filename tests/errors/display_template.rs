@@ -0,0 +1,15 @@
+use id_derive::Display;
+
+#[derive(Display)]
+#[id(display = "user/{}/{}")]
+struct TwoPlaceholders(u32);
+
+#[derive(Display)]
+#[id(display = "user/{id}")]
+struct Named(u32);
+
+#[derive(Display)]
+#[id(prefix = "user/", display = "{}!")]
+struct WithPrefix(u32);
+
+fn main() {}
@@ -0,0 +1,19 @@
+use id_derive::EnumId;
+
+#[derive(EnumId)]
+enum MissingRepr {
+    A,
+}
+
+#[derive(EnumId)]
+#[repr(u8)]
+enum WithFields {
+    A(u8),
+    B { b: u8 },
+    C,
+}
+
+#[derive(EnumId)]
+struct NotEnum(u8);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use id_derive::Id;
+
+#[derive(Id, Clone, Copy, PartialEq)]
+#[id(content_hash = "blake3")]
+struct Test(u64);
+
+#[derive(Id, Clone, Copy, PartialEq)]
+#[id(content_hash = "sha256")]
+struct Unknown(u64);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use id_derive::OpaqueId;
+
+#[derive(Debug, OpaqueId)]
+#[id(integrations(schemars))]
+struct Test(u32);
+
+fn main() {}
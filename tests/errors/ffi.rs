@@ -0,0 +1,7 @@
+use id_derive::Id;
+
+#[derive(Debug, Clone, Copy, PartialEq, Id)]
+#[id(ffi)]
+struct Handle(u32);
+
+fn main() {}
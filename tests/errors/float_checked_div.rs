@@ -0,0 +1,7 @@
+use id_derive::Div;
+
+#[derive(Div)]
+#[id(div = "checked")]
+struct Score(f64);
+
+fn main() {}
@@ -0,0 +1,6 @@
+use id_derive::Generator;
+
+#[derive(Generator)]
+struct Test(u128);
+
+fn main() {}
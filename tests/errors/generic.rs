@@ -0,0 +1,12 @@
+use id_derive::*;
+
+#[derive(Clone, Copy, Label)]
+struct Labeled<const N: u8>(u32);
+
+#[derive(Clone, Copy, Atomic)]
+struct Counter<const N: u8>(u32);
+
+#[derive(Clone, Copy, Pool)]
+struct Session<const N: u8>(u32);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use id_derive::Id;
+
+#[derive(Debug, Clone, Copy, PartialEq, Id)]
+#[id(inline = "sometimes")]
+struct Slot(u32);
+
+fn main() {}
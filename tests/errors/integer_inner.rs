@@ -0,0 +1,19 @@
+use id_derive::{BitInfo, Bounds, IndexSlice, Offset, Succ};
+use std::num::Wrapping;
+
+#[derive(Succ)]
+struct Position(f64);
+
+#[derive(Offset)]
+struct Cursor(Wrapping<u32>);
+
+#[derive(Bounds)]
+struct Score(f64);
+
+#[derive(BitInfo)]
+struct Slot(Wrapping<u64>);
+
+#[derive(IndexSlice)]
+struct Row(f32);
+
+fn main() {}
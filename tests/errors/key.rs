@@ -0,0 +1,7 @@
+use id_derive::*;
+
+#[derive(Bytes)]
+#[id(key)]
+struct Weight(f64);
+
+fn main() {}
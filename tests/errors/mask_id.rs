@@ -0,0 +1,9 @@
+use id_derive::{BitOps, MaskId};
+
+#[derive(MaskId)]
+struct Signed(i32);
+
+#[derive(BitOps)]
+struct Float(f64);
+
+fn main() {}
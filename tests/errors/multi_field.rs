@@ -0,0 +1,6 @@
+use id_derive::Id;
+
+#[derive(Id)]
+struct Test(u32, u64, u8);
+
+fn main() {}
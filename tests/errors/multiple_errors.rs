@@ -0,0 +1,9 @@
+use id_derive::Id;
+
+#[derive(Id)]
+#[id(unknown = 1, delta, other)]
+struct Test {
+    id: u32,
+}
+
+fn main() {}
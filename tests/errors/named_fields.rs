@@ -0,0 +1,8 @@
+use id_derive::Id;
+
+#[derive(Id)]
+struct Test {
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use id_derive::Id;
+
+#[derive(Id)]
+struct Test();
+
+fn main() {}
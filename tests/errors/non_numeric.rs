@@ -0,0 +1,20 @@
+use id_derive::{Add, Id, MulAssign, Pow, Sub};
+use std::borrow::Cow;
+
+#[derive(Add)]
+struct Name(String);
+
+#[derive(Sub)]
+struct Label(&'static str);
+
+#[derive(MulAssign)]
+struct Title<'a>(Cow<'a, str>);
+
+#[derive(Pow)]
+struct Flag(bool);
+
+#[derive(Id, Clone, Copy, PartialEq)]
+#[id(strict)]
+struct Request(uuid::Uuid);
+
+fn main() {}
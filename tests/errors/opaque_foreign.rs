@@ -0,0 +1,9 @@
+use opaque_ids::{AtomicTicketId, TicketId};
+use std::sync::atomic::Ordering;
+
+fn main() {
+    let _ = TicketId::from_raw(1);
+    let _ = TicketId::from_le_bytes([0; 8]);
+    let _ = TicketId::decode_varint(&[1]);
+    let _ = AtomicTicketId::new(opaque_ids::issue(1)).fetch_add(1, Ordering::Relaxed);
+}
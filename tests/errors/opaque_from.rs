@@ -0,0 +1,9 @@
+use id_derive::Id;
+
+#[derive(Debug, PartialEq, Id)]
+#[id(opaque)]
+struct Test(u32);
+
+fn main() {
+    let _ = Test::from(1);
+}
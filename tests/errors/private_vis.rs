@@ -0,0 +1,11 @@
+mod ids {
+    use id_derive::Succ;
+
+    #[derive(Clone, Copy, Succ)]
+    #[id(vis = "pub(self)")]
+    pub struct Test(pub u32);
+}
+
+fn main() {
+    let _ = ids::Test(1).next();
+}
@@ -0,0 +1,11 @@
+use id_derive::Id;
+
+#[derive(Id, Clone, Copy, PartialEq)]
+#[id(scramble(key = 1))]
+struct Test(i64);
+
+#[derive(Id, Clone, Copy, PartialEq)]
+#[id(scramble(seed = 1))]
+struct Seed(u64);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use id_derive::Id;
+
+#[derive(Id, Clone, Copy, PartialEq)]
+#[id(shard = "random")]
+struct Test(u64);
+
+#[derive(Id, Clone, Copy, PartialEq)]
+#[id(shard_output = u64)]
+struct Output(u64);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use id_derive::Sub;
+
+struct Offset(i64);
+
+#[derive(Sub)]
+#[id(delta = Offset, sub_output = Inner)]
+struct Test(u32);
+
+fn main() {}
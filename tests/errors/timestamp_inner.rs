@@ -0,0 +1,10 @@
+use id_derive::Timestamp;
+
+#[derive(Timestamp)]
+struct Test(u64);
+
+#[derive(Timestamp)]
+#[id(snowflake(epoch = 0, shift = 64))]
+struct Shifted(u64);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use id_derive::Transparent;
+
+#[derive(Transparent)]
+struct UserId(u32);
+
+#[derive(Transparent)]
+#[repr(C)]
+struct GroupId(u32);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use id_derive::Id;
+
+#[derive(Debug, PartialEq, Id)]
+#[id(integrations(sqlx))]
+struct Test(u32);
+
+fn main() {}
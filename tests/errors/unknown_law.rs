@@ -0,0 +1,8 @@
+use id_derive::{id_laws, Id};
+
+#[derive(Debug, PartialEq, Id)]
+pub struct Test(u32);
+
+id_laws!(Test(u32), laws(convert, associativity));
+
+fn main() {}
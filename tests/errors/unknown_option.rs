@@ -0,0 +1,7 @@
+use id_derive::Id;
+
+#[derive(Id)]
+#[id(unknown = 1)]
+struct Test(u32);
+
+fn main() {}
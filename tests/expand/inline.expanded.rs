@@ -0,0 +1,230 @@
+#[id(inline = "always")]
+struct NodeId(u32);
+impl ::std::convert::From<u32> for NodeId {
+    #[inline(always)]
+    fn from(inner: u32) -> Self {
+        Self(inner)
+    }
+}
+impl ::std::convert::From<NodeId> for u32 {
+    #[inline(always)]
+    fn from(inner: NodeId) -> Self {
+        inner.0
+    }
+}
+impl ::std::ops::Add for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+impl ::std::ops::Add<u32> for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn add(self, rhs: u32) -> Self::Output {
+        Self(self.0 + rhs)
+    }
+}
+impl<'rhs> ::std::ops::Add<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    type Output = <Self as ::std::ops::Add<u32>>::Output;
+    #[inline(always)]
+    fn add(self, rhs: &'rhs u32) -> Self::Output {
+        ::std::ops::Add::add(self, ::std::clone::Clone::clone(rhs))
+    }
+}
+impl ::std::ops::AddAssign for NodeId {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+impl ::std::ops::AddAssign<u32> for NodeId {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: u32) {
+        self.0 += rhs;
+    }
+}
+impl<'rhs> ::std::ops::AddAssign<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: &'rhs u32) {
+        ::std::ops::AddAssign::add_assign(self, ::std::clone::Clone::clone(rhs));
+    }
+}
+impl ::std::ops::Sub for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+impl ::std::ops::Sub<u32> for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn sub(self, rhs: u32) -> Self::Output {
+        Self(self.0 - rhs)
+    }
+}
+impl<'rhs> ::std::ops::Sub<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    type Output = <Self as ::std::ops::Sub<u32>>::Output;
+    #[inline(always)]
+    fn sub(self, rhs: &'rhs u32) -> Self::Output {
+        ::std::ops::Sub::sub(self, ::std::clone::Clone::clone(rhs))
+    }
+}
+impl ::std::ops::SubAssign for NodeId {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+impl ::std::ops::SubAssign<u32> for NodeId {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: u32) {
+        self.0 -= rhs;
+    }
+}
+impl<'rhs> ::std::ops::SubAssign<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: &'rhs u32) {
+        ::std::ops::SubAssign::sub_assign(self, ::std::clone::Clone::clone(rhs));
+    }
+}
+impl ::std::ops::Mul for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+impl ::std::ops::Mul<u32> for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn mul(self, rhs: u32) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+impl<'rhs> ::std::ops::Mul<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    type Output = <Self as ::std::ops::Mul<u32>>::Output;
+    #[inline(always)]
+    fn mul(self, rhs: &'rhs u32) -> Self::Output {
+        ::std::ops::Mul::mul(self, ::std::clone::Clone::clone(rhs))
+    }
+}
+impl ::std::ops::MulAssign for NodeId {
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: Self) {
+        self.0 *= rhs.0;
+    }
+}
+impl ::std::ops::MulAssign<u32> for NodeId {
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: u32) {
+        self.0 *= rhs;
+    }
+}
+impl<'rhs> ::std::ops::MulAssign<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: &'rhs u32) {
+        ::std::ops::MulAssign::mul_assign(self, ::std::clone::Clone::clone(rhs));
+    }
+}
+impl ::std::ops::Div for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn div(self, rhs: Self) -> Self::Output {
+        Self(self.0 / rhs.0)
+    }
+}
+impl NodeId {
+    /// Divides by `rhs`, returning `None` if `rhs` is zero.
+    #[inline(always)]
+    pub fn checked_div(self, rhs: NodeId) -> ::std::option::Option<Self> {
+        self.0.checked_div(rhs.0).map(Self)
+    }
+}
+impl ::std::ops::Div<u32> for NodeId {
+    type Output = Self;
+    #[inline(always)]
+    fn div(self, rhs: u32) -> Self::Output {
+        Self(self.0 / rhs)
+    }
+}
+impl<'rhs> ::std::ops::Div<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    type Output = <Self as ::std::ops::Div<u32>>::Output;
+    #[inline(always)]
+    fn div(self, rhs: &'rhs u32) -> Self::Output {
+        ::std::ops::Div::div(self, ::std::clone::Clone::clone(rhs))
+    }
+}
+impl NodeId {
+    /// Divides by `rhs`, returning `None` if `rhs` is zero.
+    #[inline(always)]
+    pub fn checked_div_inner(self, rhs: u32) -> ::std::option::Option<Self> {
+        self.0.checked_div(rhs).map(Self)
+    }
+}
+impl ::std::ops::DivAssign for NodeId {
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: Self) {
+        self.0 /= rhs.0;
+    }
+}
+impl ::std::ops::DivAssign<u32> for NodeId {
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: u32) {
+        self.0 /= rhs;
+    }
+}
+impl<'rhs> ::std::ops::DivAssign<&'rhs u32> for NodeId
+where
+    u32: ::std::clone::Clone,
+{
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: &'rhs u32) {
+        ::std::ops::DivAssign::div_assign(self, ::std::clone::Clone::clone(rhs));
+    }
+}
+impl ::std::fmt::Display for NodeId {
+    #[inline(always)]
+    fn fmt(
+        &self,
+        f: &mut ::std::fmt::Formatter<'_>,
+    ) -> ::std::result::Result<(), ::std::fmt::Error> {
+        f.write_fmt(format_args!("{0}", self.0))
+    }
+}
+impl ::std::fmt::Binary for NodeId {
+    #[inline(always)]
+    fn fmt(
+        &self,
+        f: &mut ::std::fmt::Formatter<'_>,
+    ) -> ::std::result::Result<(), ::std::fmt::Error> {
+        if f.alternate() {
+            f.write_fmt(format_args!("{0:#b}", self.0))
+        } else {
+            f.write_fmt(format_args!("{0:b}", self.0))
+        }
+    }
+}
@@ -0,0 +1,5 @@
+use id_derive::Id;
+
+#[derive(Id)]
+#[id(inline = "always")]
+struct NodeId(u32);
@@ -0,0 +1,48 @@
+#![cfg(feature = "fake")]
+
+use fake::rand::rngs::StdRng;
+use fake::rand::SeedableRng;
+use fake::{Dummy, Fake, Faker};
+use id_derive as id;
+
+#[test]
+fn test_fake() {
+    #[derive(Debug, PartialEq, id::Fake)]
+    struct UserId(u64);
+
+    #[derive(Dummy)]
+    struct User {
+        id: UserId,
+        name: String,
+    }
+
+    let User {
+        id: UserId(_),
+        name,
+    } = Faker.fake();
+    assert!(!name.is_empty());
+    let first: UserId = Faker.fake_with_rng(&mut StdRng::seed_from_u64(7));
+    let second: UserId = Faker.fake_with_rng(&mut StdRng::seed_from_u64(7));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_fake_validated() {
+    fn below_ten(inner: &u8) -> Result<(), String> {
+        if *inner < 10 {
+            Ok(())
+        } else {
+            Err(format!("{inner} is too large"))
+        }
+    }
+
+    #[derive(Debug, id::Fake, id::FromInner)]
+    #[id(validate(below_ten, error = String))]
+    struct DigitId(u8);
+
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..100 {
+        let digit: DigitId = Faker.fake_with_rng(&mut rng);
+        assert!(digit.0 < 10);
+    }
+}
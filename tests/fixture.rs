@@ -0,0 +1,40 @@
+use id_derive as id;
+use std::collections::HashSet;
+use std::num::Wrapping;
+
+#[test]
+fn test_fixture() {
+    #[derive(Debug, PartialEq, Eq, Hash, id::Fixture)]
+    struct OrderId(u64);
+    assert_eq!(OrderId::test(7), OrderId(7));
+    assert_eq!(OrderId::test(7), OrderId::test(7));
+    let orders: HashSet<_> = OrderId::test_sequence().take(100).collect();
+    assert_eq!(orders.len(), 100);
+    assert!(!orders.contains(&OrderId(0)));
+
+    #[derive(Debug, PartialEq, id::Fixture)]
+    struct SkuId(String);
+    let skus: Vec<_> = SkuId::test_sequence().take(2).collect();
+    assert_eq!(skus, [SkuId("sku_id-1".into()), SkuId("sku_id-2".into())]);
+
+    #[derive(Debug, PartialEq, id::Fixture)]
+    struct TickId(Wrapping<i16>);
+    assert_eq!(TickId::test(3), TickId(Wrapping(3)));
+
+    #[derive(Debug, PartialEq, id::Fixture)]
+    struct TraceId(uuid::Uuid);
+    assert_eq!(TraceId::test(1), TraceId(uuid::Uuid::from_u128(1)));
+
+    #[derive(Debug, PartialEq, id::Fixture)]
+    struct EventId(ulid::Ulid);
+    assert_eq!(EventId::test(2), EventId(ulid::Ulid(2)));
+}
+
+#[test]
+#[should_panic(expected = "300 does not fit in ByteId")]
+fn test_fixture_overflow() {
+    #[derive(Debug, PartialEq, id::Fixture)]
+    struct ByteId(u8);
+    assert_eq!(ByteId::test(255), ByteId(255));
+    ByteId::test(300);
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use id_derive as id;
+use ulid::Ulid;
+use uuid::Uuid;
+
+#[test]
+fn test_uuid_v7_generator() {
+    #[derive(Debug, PartialEq, PartialOrd, id::Generator)]
+    struct EventId(Uuid);
+    let now = Arc::new(AtomicU64::new(1_700_000_000_000));
+    let clock = Arc::clone(&now);
+    let generator = EventIdGenerator::with_clock(move || clock.load(Ordering::Relaxed), 7);
+    let first = generator.generate();
+    assert_eq!(first.0.get_version_num(), 7);
+    assert_eq!(first.0.get_variant(), uuid::Variant::RFC4122);
+    assert_eq!(first.0.as_u128() >> 80, 1_700_000_000_000);
+    let second = generator.generate();
+    assert_eq!(second.0.as_u128(), first.0.as_u128() + 1);
+    now.store(1_600_000_000_000, Ordering::Relaxed);
+    let third = generator.generate();
+    assert!(third > second);
+    assert_eq!(third.0.as_u128() >> 80, 1_700_000_000_000);
+    now.store(1_700_000_000_001, Ordering::Relaxed);
+    let fourth = generator.generate();
+    assert!(fourth > third);
+    assert_eq!(fourth.0.as_u128() >> 80, 1_700_000_000_001);
+}
+
+#[test]
+fn test_ulid_generator() {
+    #[derive(Debug, PartialEq, PartialOrd, id::Generator)]
+    struct JobId(Ulid);
+    let generator = JobIdGenerator::with_clock(|| 1_700_000_000_000, 7);
+    let ids: Vec<_> = (0..100).map(|_| generator.generate()).collect();
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(ids
+        .iter()
+        .all(|id| id.0.timestamp_ms() == 1_700_000_000_000));
+    let replay = JobIdGenerator::with_clock(|| 1_700_000_000_000, 7);
+    assert_eq!(replay.generate(), ids[0]);
+}
+
+#[test]
+fn test_system_clock_generator() {
+    #[derive(Debug, PartialEq, PartialOrd, id::Generator)]
+    struct RequestId(Uuid);
+    let generator = Arc::new(RequestIdGenerator::new());
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let generator = Arc::clone(&generator);
+            std::thread::spawn(move || {
+                let ids: Vec<_> = (0..100).map(|_| generator.generate()).collect();
+                assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+                ids
+            })
+        })
+        .collect();
+    let mut ids: Vec<_> = threads
+        .into_iter()
+        .flat_map(|thread| thread.join().unwrap())
+        .map(|id| id.0)
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 400);
+}
+
+#[test]
+fn test_timestamp() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[derive(Debug, PartialEq, PartialOrd, id::Generator, id::Timestamp)]
+    struct EventId(Uuid);
+    #[derive(Debug, PartialEq, PartialOrd, id::Generator, id::Timestamp)]
+    struct JobId(Ulid);
+    #[derive(id::Timestamp)]
+    #[id(snowflake(epoch = 1_000, shift = 12))]
+    struct ShardedId(i64);
+
+    let event = EventIdGenerator::with_clock(|| 1_700_000_000_123, 1).generate();
+    assert_eq!(event.timestamp_millis(), 1_700_000_000_123);
+    assert_eq!(
+        event.created_at(),
+        UNIX_EPOCH + Duration::from_millis(1_700_000_000_123)
+    );
+    let job = JobIdGenerator::with_clock(|| 1_700_000_000_456, 1).generate();
+    assert_eq!(job.timestamp_millis(), 1_700_000_000_456);
+    assert_eq!(ShardedId((5_000 << 12) | 0xabc).timestamp_millis(), 6_000);
+}
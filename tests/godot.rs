@@ -0,0 +1,43 @@
+#![cfg(feature = "godot")]
+
+use godot::builtin::StringName;
+use godot::meta::error::ConvertError;
+use godot::meta::{FromGodot, GodotConvert, ToGodot};
+use id_derive as id;
+
+#[derive(Debug, PartialEq, id::Godot)]
+struct UnitId(u32);
+
+#[derive(Debug, PartialEq, id::Godot)]
+#[id(validate(check_level, error = String))]
+struct LevelId(i64);
+
+fn check_level(level: &i64) -> Result<(), String> {
+    if *level > 0 {
+        Ok(())
+    } else {
+        Err(format!("invalid level {level}"))
+    }
+}
+
+#[test]
+fn test_godot_integer() {
+    let via: <UnitId as GodotConvert>::Via = UnitId(7).to_godot();
+    assert_eq!(via, 7_u32);
+    assert_eq!(UnitId::try_from_godot(7).unwrap(), UnitId(7));
+    assert_eq!(LevelId::try_from_godot(3).unwrap(), LevelId(3));
+    assert!(LevelId::try_from_godot(0).is_err());
+}
+
+#[derive(Debug, PartialEq, id::Godot)]
+struct PlayerName(String);
+
+#[test]
+fn test_godot_string() {
+    // Creating a `StringName` calls into the engine, which is not running in tests, so only the
+    // signatures of the round trip are checked.
+    let to_godot: fn(&PlayerName) -> StringName = PlayerName::to_godot;
+    let try_from_godot: fn(StringName) -> Result<PlayerName, ConvertError> =
+        PlayerName::try_from_godot;
+    let _ = (to_godot, try_from_godot);
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "hash32")]
+
+use heapless::FnvIndexMap;
+use id_derive as id;
+
+#[test]
+fn test_hash32() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Hash32)]
+    struct SensorId(u16);
+    let mut readings: FnvIndexMap<SensorId, i32, 4> = FnvIndexMap::new();
+    readings.insert(SensorId(3), -12).unwrap();
+    readings.insert(SensorId(7), 25).unwrap();
+    assert_eq!(readings.get(&SensorId(3)), Some(&-12));
+    assert_eq!(readings.get(&SensorId(5)), None);
+}
+
+#[test]
+fn test_generic_hash32() {
+    use hash32::{Hash, Hasher};
+
+    #[derive(id::Hash32)]
+    struct GenericId<T>(T);
+    let hash = |value: &dyn Fn(&mut hash32::FnvHasher)| {
+        let mut hasher = hash32::FnvHasher::default();
+        value(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(
+        hash(&|hasher| GenericId(42_u32).hash(hasher)),
+        hash(&|hasher| 42_u32.hash(hasher))
+    );
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "heed")]
+
+use heed::types::Str;
+use heed::{BytesDecode, BytesEncode, Database, EnvOpenOptions};
+use id_derive as id;
+
+#[derive(Debug, PartialEq, Clone, Copy, id::Heed, id::Bytes)]
+#[id(key)]
+#[cfg_attr(feature = "redb", derive(id::Redb))]
+struct UserId(u32);
+
+#[derive(Debug, PartialEq, Clone, Copy, id::Heed)]
+struct Offset(i64);
+
+#[test]
+fn test_heed_encoding() {
+    let bytes = UserId::bytes_encode(&UserId(0x0102_0304)).unwrap();
+    assert_eq!(bytes.as_ref(), UserId(0x0102_0304).to_key_bytes());
+    assert_eq!(UserId::bytes_decode(&bytes).unwrap(), UserId(0x0102_0304));
+    assert!(UserId::bytes_decode(&[0; 5]).is_err());
+    let low = Offset::bytes_encode(&Offset(-1)).unwrap();
+    let high = Offset::bytes_encode(&Offset(1)).unwrap();
+    assert!(low < high);
+
+    #[derive(Debug, PartialEq, id::Heed)]
+    struct ShardedId<const SHARD: u8>(u32);
+    let bytes = ShardedId::<2>::bytes_encode(&ShardedId(9)).unwrap();
+    assert_eq!(ShardedId::<2>::bytes_decode(&bytes).unwrap(), ShardedId(9));
+}
+
+#[test]
+fn test_heed_database() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("id-derive-heed-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let env = unsafe { EnvOpenOptions::new().open(&dir)? };
+    let mut tx = env.write_txn()?;
+    let users: Database<UserId, Str> = env.create_database(&mut tx, None)?;
+    for (id, name) in [(300, "carol"), (2, "alice"), (70_000, "dave"), (3, "bob")] {
+        users.put(&mut tx, &UserId(id), name)?;
+    }
+    assert_eq!(users.get(&tx, &UserId(3))?, Some("bob"));
+    let scanned = users
+        .range(&tx, &(UserId(3)..UserId(70_000)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(scanned, [(UserId(3), "bob"), (UserId(300), "carol")]);
+    drop(tx);
+    drop(env);
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[cfg(feature = "redb")]
+#[test]
+fn test_heed_matches_redb() {
+    use redb::Value;
+
+    for id in [UserId(0), UserId(258), UserId(u32::MAX)] {
+        assert_eq!(
+            UserId::bytes_encode(&id).unwrap().as_ref(),
+            <UserId as Value>::as_bytes(&id)
+        );
+    }
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "http")]
+
+use http::HeaderValue;
+use id_derive as id;
+use std::convert::TryFrom;
+use std::error::Error;
+
+#[test]
+fn test_header_value() {
+    #[derive(Debug, PartialEq, id::Display, id::FromStr, id::Http)]
+    #[id(prefix = "trace-")]
+    struct TraceId(u32);
+    let header = HeaderValue::from(TraceId(48879));
+    assert_eq!(header, "trace-48879");
+    assert_eq!(HeaderValue::from(&TraceId(1)), "trace-1");
+    assert_eq!(TraceId::try_from(&header).unwrap(), TraceId(48879));
+
+    let err = TraceId::try_from(&HeaderValue::from_static("48879")).unwrap_err();
+    assert!(matches!(err, TraceIdHeaderError::Parse(_)));
+    assert!(err
+        .to_string()
+        .starts_with("invalid TraceId header value: "));
+    assert!(err.source().is_some());
+
+    let err = TraceId::try_from(&HeaderValue::from_bytes(b"trace-\xff").unwrap()).unwrap_err();
+    assert!(matches!(err, TraceIdHeaderError::NotVisibleAscii(_)));
+}
+
+#[derive(id::Id, id::FromStr, Debug, Clone, Copy, PartialEq)]
+#[id(integrations(http), module = request_id)]
+struct RequestId(u64);
+
+#[test]
+fn test_header_integration() {
+    let header = HeaderValue::from(RequestId(42));
+    assert_eq!(header, "42");
+    assert_eq!(RequestId::try_from(&header).unwrap(), RequestId(42));
+    let err = RequestId::try_from(&HeaderValue::from_static("x")).unwrap_err();
+    assert!(matches!(err, request_id::HeaderError::Parse(_)));
+}
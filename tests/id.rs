@@ -84,6 +84,126 @@ fn test_div_inner() {
     assert_eq!(DivId(12) / 3, DivId(4));
 }
 
+#[test]
+fn test_op_output() {
+    #[derive(Debug, PartialEq, Eq, id::FromInner)]
+    struct Area(u32);
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Id)]
+    #[id(mul_output = Area, div_output = Inner, add_output = Self)]
+    struct Position(u32);
+    assert_eq!(Position(3) * Position(4), Area(12));
+    assert_eq!(Position(12) / Position(4), 3);
+    assert_eq!(Position(12) + Position(4), Position(16));
+    assert_eq!(Position(12) * 2, Position(24));
+
+    #[derive(Debug, PartialEq, Eq, id::Sub, id::Div)]
+    #[id(sub_output = Inner, div = "checked", div_output = Area)]
+    struct Index(u32);
+    assert_eq!(Index(12) - Index(4), 8);
+    assert_eq!(Index(12) / Index(4), Some(Area(3)));
+    assert_eq!(Index(12) / Index(0), None);
+}
+
+#[test]
+fn test_wrapper_inner() {
+    use std::num::{Saturating, Wrapping};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Id, id::ConstOps)]
+    #[id(debug_overflow_checks)]
+    struct SeqNum(Wrapping<u8>);
+    assert_eq!(SeqNum::from(255) + SeqNum::from(1), SeqNum::from(0));
+    assert_eq!(SeqNum::from(0) - Wrapping(1), SeqNum::from(255));
+    assert_eq!(u8::from(SeqNum::from(7)), 7);
+    assert_eq!(SeqNum::from(7).checked_div(SeqNum::from(0)), None);
+    assert_eq!(
+        SeqNum::from(7).checked_div_inner(Wrapping(2)),
+        Some(SeqNum::from(3))
+    );
+    assert_eq!(
+        SeqNum::from(255).const_add(SeqNum::from(2)),
+        SeqNum::from(1)
+    );
+    assert_eq!(SeqNum::from(12).to_string(), "12");
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Id, id::ConstOps)]
+    struct Level(Saturating<u8>);
+    assert_eq!(Level::from(255) + Level::from(1), Level::from(255));
+    let mut level = Level::from(1);
+    level -= Level::from(2);
+    assert_eq!(level, Level::from(0));
+    assert_eq!(Level::from(200).const_mul(Level::from(2)), Level::from(255));
+}
+
+#[test]
+fn test_float_inner() {
+    #[derive(Debug, Clone, Copy, PartialEq, id::Id)]
+    #[id(debug_overflow_checks)]
+    struct Score(f64);
+    assert_eq!(Score(1.5) + Score(2.0), Score(3.5));
+    assert_eq!(Score(3.0) / 2.0, Score(1.5));
+    let mut score = Score(1.0);
+    score *= 4.0;
+    assert_eq!(score.to_string(), "4");
+
+    #[derive(Debug, Clone, Copy, id::OpaqueId)]
+    struct Weight(f32);
+    assert!(Weight(1.0) < Weight(2.0));
+    assert_eq!(Weight(1.0), Weight::from(1.0));
+}
+
+#[test]
+fn test_no_id_arithmetic() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Offset(i64);
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Id)]
+    #[id(no_id_arithmetic, delta = Offset)]
+    struct UserId(u32);
+    let mut id = UserId(3) + 4;
+    id *= 2;
+    id /= 7;
+    assert_eq!(id, UserId(2));
+    assert_eq!(id.checked_div_inner(0), None);
+    assert_eq!(UserId(5) - UserId(7), Offset(-2));
+    assert_eq!(UserId(5) + Offset(-2), UserId(3));
+}
+
+#[test]
+fn test_const_ops() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::ConstOps)]
+    struct ConstId(u32);
+    const IDS: [ConstId; 4] = [
+        ConstId(6).const_add(ConstId(2)),
+        ConstId(6).const_sub(ConstId(2)),
+        ConstId(6).const_mul(ConstId(2)),
+        ConstId(6).const_div(ConstId(2)),
+    ];
+    assert_eq!(IDS, [ConstId(8), ConstId(4), ConstId(12), ConstId(3)]);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::ConstOps)]
+    #[id(debug_overflow_checks)]
+    struct CheckedId(u8);
+    const CHECKED: CheckedId = CheckedId(250).const_add(CheckedId(5));
+    assert_eq!(CHECKED, CheckedId(255));
+}
+
+#[test]
+fn test_checked_div() {
+    #[derive(Debug, PartialEq, Eq, id::Div, id::DivInner)]
+    struct DivId(u32);
+    assert_eq!(DivId(12).checked_div(DivId(3)), Some(DivId(4)));
+    assert_eq!(DivId(12).checked_div(DivId(0)), None);
+    assert_eq!(DivId(12).checked_div_inner(0), None);
+
+    #[derive(Debug, PartialEq, Eq, id::Id)]
+    #[id(div = "checked")]
+    struct CheckedId(u32);
+    assert_eq!(CheckedId(12) / CheckedId(3), Some(CheckedId(4)));
+    assert_eq!(CheckedId(12) / 0, None);
+    let mut id = CheckedId(12);
+    id *= 2;
+    assert_eq!(id, CheckedId(24));
+}
+
 #[test]
 fn test_from_inner() {
     #[derive(Debug, PartialEq, Eq, id::FromInner)]
@@ -105,3 +225,1295 @@ fn test_into_inner() {
     assert_eq!(155, u32::from(IntoId(155)));
     assert_eq!(155, u32::from(ConvertId(155)));
 }
+
+#[test]
+fn test_bytes() {
+    #[derive(Debug, PartialEq, Eq, id::Bytes)]
+    struct BytesId(u32);
+    assert_eq!(BytesId(0x0102_0304).to_le_bytes(), [4, 3, 2, 1]);
+    assert_eq!(BytesId(0x0102_0304).to_be_bytes(), [1, 2, 3, 4]);
+    assert_eq!(BytesId::from_le_bytes([4, 3, 2, 1]), BytesId(0x0102_0304));
+    assert_eq!(BytesId::from_be_bytes([1, 2, 3, 4]), BytesId(0x0102_0304));
+}
+
+#[test]
+fn test_key_bytes() {
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, id::Bytes)]
+    #[id(key)]
+    struct SeqId(i64);
+    let ids = [
+        SeqId(i64::MIN),
+        SeqId(-2),
+        SeqId(-1),
+        SeqId(0),
+        SeqId(1),
+        SeqId(i64::MAX),
+    ];
+    for pair in ids.windows(2) {
+        assert!(pair[0].to_key_bytes() < pair[1].to_key_bytes());
+        assert!(pair[0].to_key() < pair[1].to_key());
+    }
+    for id in ids {
+        assert_eq!(SeqId::from_key_bytes(id.to_key_bytes()), id);
+        assert_eq!(SeqIdKey::from(id).id(), id);
+    }
+
+    let store: BTreeMap<Vec<u8>, SeqId> = ids
+        .iter()
+        .map(|&id| (id.to_key().as_ref().to_vec(), id))
+        .collect();
+    let scanned: Vec<_> = store
+        .range(SeqId(-1).to_key_bytes().to_vec()..SeqId(1).to_key_bytes().to_vec())
+        .map(|(_, id)| *id)
+        .collect();
+    assert_eq!(scanned, [SeqId(-1), SeqId(0)]);
+
+    #[derive(Debug, PartialEq, Clone, Copy, id::Bytes)]
+    #[id(key)]
+    struct ShardedId<const SHARD: u8>(u32);
+    let key: ShardedIdKey<3> = ShardedId(0x0102_0304).to_key();
+    assert_eq!(key.as_ref(), [1, 2, 3, 4]);
+    assert_eq!(key.id(), ShardedId::<3>(0x0102_0304));
+    assert_eq!(ShardedId::<3>::from_key_bytes([1, 2, 3, 4]), key.id());
+}
+
+#[test]
+fn test_varint() {
+    use std::io::ErrorKind;
+
+    #[derive(Debug, PartialEq, Clone, Copy, id::Varint)]
+    struct TermId(u64);
+    #[derive(Debug, PartialEq, Clone, Copy, id::Varint)]
+    struct Gap(i16);
+    assert_eq!(TermId::VARINT_MAX_LEN, 10);
+    assert_eq!(Gap::VARINT_MAX_LEN, 3);
+
+    let mut buf = Vec::new();
+    let ids = [TermId(0), TermId(127), TermId(128), TermId(u64::MAX)];
+    let lens: Vec<_> = ids.iter().map(|id| id.encode_varint(&mut buf)).collect();
+    assert_eq!(lens, [1, 1, 2, 10]);
+    let mut offset = 0;
+    for id in ids {
+        let (decoded, len) = TermId::decode_varint(&buf[offset..]).unwrap();
+        assert_eq!(decoded, id);
+        offset += len;
+    }
+    assert_eq!(offset, buf.len());
+    assert_eq!(TermId::decode_varint(&[0x80]), None);
+    assert_eq!(TermId::decode_varint(&[0xff; 10]), None);
+
+    let mut buf = Vec::new();
+    for gap in [0, -1, 1, -2, i16::MIN, i16::MAX] {
+        Gap(gap).write_varint(&mut buf).unwrap();
+    }
+    assert_eq!(buf[..4], [0, 1, 2, 3]);
+    let mut reader = &buf[..];
+    let gaps: Vec<_> = (0..6)
+        .map(|_| Gap::read_varint(&mut reader).unwrap().0)
+        .collect();
+    assert_eq!(gaps, [0, -1, 1, -2, i16::MIN, i16::MAX]);
+    let err = Gap::read_varint(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let err = Gap::read_varint(&mut &[0xff, 0xff, 0x04][..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        Gap::read_varint(&mut &[0xff, 0xff, 0x03][..]).unwrap(),
+        Gap(i16::MIN)
+    );
+}
+
+#[test]
+fn test_sorted() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Sorted)]
+    struct DocId(u32);
+    #[derive(Debug, PartialEq, Clone, id::Sorted)]
+    struct Tag(String);
+
+    let ids: Vec<_> = (0..1000).map(|n| DocId(n * 3)).collect();
+    for (pos, id) in ids.iter().enumerate() {
+        assert_eq!(DocId::gallop(&ids, id), Ok(pos));
+        assert_eq!(DocId::gallop(&ids, &DocId(id.0 + 1)), Err(pos + 1));
+    }
+    assert_eq!(DocId::gallop(&[], &DocId(1)), Err(0));
+
+    let few = [DocId(0), DocId(10), DocId(2997), DocId(5000)];
+    assert_eq!(DocId::intersect(&few, &ids), [DocId(0), DocId(2997)]);
+    assert_eq!(DocId::intersect(&ids, &few), [DocId(0), DocId(2997)]);
+    let evens: Vec<_> = (0..1500).map(|n| DocId(n * 2)).collect();
+    let sixes: Vec<_> = (0..500).map(|n| DocId(n * 6)).collect();
+    assert_eq!(DocId::intersect(&ids, &evens), sixes);
+    assert_eq!(DocId::intersect(&ids, &[]), []);
+
+    let union = DocId::union(&few, &ids);
+    assert_eq!(union.len(), 1002);
+    assert!(union.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(DocId::union(&[], &few), few);
+
+    let a = [Tag("a".into()), Tag("c".into())];
+    let b = [Tag("b".into()), Tag("c".into())];
+    assert_eq!(DocId::union(&[DocId(1)], &[DocId(1)]), [DocId(1)]);
+    assert_eq!(Tag::intersect(&a, &b), [Tag("c".into())]);
+    assert_eq!(Tag::union(&a, &b).len(), 3);
+}
+
+#[test]
+fn test_index_slice() {
+    #[derive(Clone, Copy, id::IndexSlice)]
+    struct DocId(u32);
+    #[derive(Clone, Copy, id::IndexSlice)]
+    struct Offset(i64);
+
+    let mut docs = vec![String::from("a"), String::from("b")];
+    assert_eq!(DocId(1).get_in(&docs).map(String::as_str), Some("b"));
+    assert_eq!(DocId(2).get_in(&docs), None);
+    DocId(0).get_in_mut(&mut docs).unwrap().push('!');
+    assert_eq!(docs, ["a!", "b"]);
+    assert!(DocId(0).get_in_mut(&mut Vec::<u8>::new()).is_none());
+    assert_eq!(Offset(1).get_in(&[5, 6]), Some(&6));
+    assert_eq!(Offset(-1).get_in(&[5, 6]), None);
+}
+
+#[test]
+fn test_range() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Range)]
+    struct RangeId(u32);
+    let ids: Vec<_> = RangeId::range(RangeId(2), RangeId(5)).collect();
+    assert_eq!(ids, vec![RangeId(2), RangeId(3), RangeId(4)]);
+    let ids: Vec<_> = RangeId::range_inclusive(RangeId(2), RangeId(4)).collect();
+    assert_eq!(ids, vec![RangeId(2), RangeId(3), RangeId(4)]);
+    assert_eq!(RangeId(0).to(RangeId(10)).count(), 10);
+    assert_eq!(RangeId(0).to_inclusive(RangeId(10)).count(), 11);
+    assert_eq!(RangeId(3).to(RangeId(3)).next(), None);
+}
+
+#[test]
+fn test_range_span() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Range)]
+    struct SpanId(u64);
+    let span = SpanIdRange::from(SpanId(5)..SpanId(8));
+    assert_eq!((span.start(), span.end()), (SpanId(5), SpanId(8)));
+    assert!(span.contains(SpanId(5)));
+    assert!(!span.contains(SpanId(8)));
+    assert_eq!(span.len(), 3);
+    assert!(!span.is_empty());
+    assert_eq!(
+        span.iter().collect::<Vec<_>>(),
+        [SpanId(5), SpanId(6), SpanId(7)]
+    );
+    assert_eq!(span.len(), 3);
+    assert!(SpanId(3).to(SpanId(2)).is_empty());
+    assert_eq!(SpanId(0).to(SpanId(u64::MAX)).len(), usize::MAX);
+
+    let span = SpanIdRangeInclusive::from(SpanId(5)..=SpanId(8));
+    assert_eq!((span.start(), span.end()), (SpanId(5), SpanId(8)));
+    assert!(span.contains(SpanId(8)));
+    assert_eq!(span.len(), 4);
+    let mut iter = span.iter();
+    iter.by_ref().for_each(drop);
+    assert!(iter.is_empty());
+    assert!(!span.is_empty());
+}
+
+#[test]
+fn test_range_iterator_traits() {
+    use std::iter::FusedIterator;
+
+    fn assert_fused<I: FusedIterator>(_: &I) {}
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Range)]
+    struct PageId(u16);
+    let pages = PageId(1).to(PageId(4));
+    assert_fused(&pages);
+    assert_eq!(ExactSizeIterator::len(&pages), 3);
+    assert_eq!(
+        pages.clone().rev().collect::<Vec<_>>(),
+        [PageId(3), PageId(2), PageId(1)]
+    );
+    let mut pages = PageId(1).to_inclusive(PageId(4));
+    assert_eq!(pages.next_back(), Some(PageId(4)));
+    assert_eq!(pages.next(), Some(PageId(1)));
+    assert_eq!(ExactSizeIterator::len(&pages), 2);
+    assert_fused(&PageId(65_534).successors());
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Range)]
+    struct EventId(u64);
+    let mut events = EventId(0).to(EventId(10));
+    assert_eq!(events.next_back(), Some(EventId(9)));
+    assert_eq!(events.nth_back(1), Some(EventId(7)));
+    assert_fused(&events);
+}
+
+#[test]
+fn test_successors() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Range)]
+    struct SuccessorsId(u8);
+    let ids: Vec<_> = SuccessorsId(7).successors().take(3).collect();
+    assert_eq!(ids, vec![SuccessorsId(7), SuccessorsId(8), SuccessorsId(9)]);
+    assert_eq!(SuccessorsId(250).successors().count(), 6);
+    let pairs: Vec<_> = SuccessorsId(1).successors().zip("ab".chars()).collect();
+    assert_eq!(pairs, vec![(SuccessorsId(1), 'a'), (SuccessorsId(2), 'b')]);
+}
+
+#[test]
+fn test_succ() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Succ)]
+    struct SuccId(u8);
+    assert_eq!(SuccId(7).next(), SuccId(8));
+    assert_eq!(SuccId(7).prev(), SuccId(6));
+    assert_eq!(SuccId(7).checked_next(), Some(SuccId(8)));
+    assert_eq!(SuccId(255).checked_next(), None);
+    assert_eq!(SuccId(1).checked_prev(), Some(SuccId(0)));
+    assert_eq!(SuccId(0).checked_prev(), None);
+}
+
+#[test]
+fn test_distance() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Distance)]
+    struct DistanceId(u32);
+    assert_eq!(DistanceId(3).abs_diff(DistanceId(10)), 7);
+    assert_eq!(DistanceId(10).abs_diff(DistanceId(3)), 7);
+    assert_eq!(DistanceId(10).distance(DistanceId(10)), 0);
+
+    #[derive(Clone, Copy, id::Distance)]
+    struct SignedId(i32);
+    assert_eq!(SignedId(-3).abs_diff(SignedId(4)), 7_u32);
+    assert_eq!(SignedId(i32::MAX).abs_diff(SignedId(i32::MIN)), u32::MAX);
+    assert_eq!(SignedId(i32::MIN).distance(SignedId(i32::MAX)), u32::MAX);
+
+    #[derive(Clone, Copy, id::Distance)]
+    struct Weight(f64);
+    assert_eq!(Weight(1.5).abs_diff(Weight(4.0)), 2.5);
+}
+
+#[test]
+fn test_bounds() {
+    #[derive(id::Bounds)]
+    struct BoundedId(i8);
+    assert!(BoundedId(0).is_zero());
+    assert!(!BoundedId(1).is_zero());
+    assert!(BoundedId(-128).is_min());
+    assert!(!BoundedId(0).is_min());
+    assert!(BoundedId(127).is_max());
+    assert!(!BoundedId(0).is_max());
+}
+
+#[test]
+fn test_pow() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Pow)]
+    struct PowId(u8);
+    assert_eq!(PowId(2).pow(7), PowId(128));
+    assert_eq!(PowId(3).pow(0), PowId(1));
+    assert_eq!(PowId(2).checked_pow(7), Some(PowId(128)));
+    assert_eq!(PowId(2).checked_pow(8), None);
+}
+
+#[test]
+fn test_bit_info() {
+    #[derive(Clone, Copy, id::BitInfo)]
+    struct PackedId(u16);
+    assert_eq!(PackedId::BITS, 16);
+    assert_eq!(PackedId(0b1011_0000).leading_zeros(), 8);
+    assert_eq!(PackedId(0b1011_0000).trailing_zeros(), 4);
+    assert_eq!(PackedId(0b1011_0000).count_ones(), 3);
+}
+
+#[test]
+fn test_delta() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Offset(i64);
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Id)]
+    #[id(delta = Offset)]
+    struct DeltaId(u32);
+    assert_eq!(DeltaId(14) - DeltaId(4), Offset(10));
+    assert_eq!(DeltaId(4) - DeltaId(14), Offset(-10));
+    assert_eq!(DeltaId(4) + Offset(10), DeltaId(14));
+    assert_eq!(DeltaId(14) + Offset(-10), DeltaId(4));
+    assert_eq!(DeltaId(14) - Offset(10), DeltaId(4));
+    assert_eq!(DeltaId(4) - Offset(-10), DeltaId(14));
+    assert_eq!(DeltaId(4) + DeltaId(1), DeltaId(5));
+}
+
+#[test]
+fn test_delta_bounds() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Offset(i64);
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Add, id::Sub)]
+    #[id(delta = Offset)]
+    struct Position(u64);
+    const HALF: u64 = 1 << 63;
+    assert_eq!(Position(u64::MAX) + Offset(i64::MIN), Position(HALF - 1));
+    assert_eq!(Position(0) - Offset(i64::MIN), Position(HALF));
+    assert_eq!(Position(0) - Position(HALF), Offset(i64::MIN));
+    assert_eq!(Position(HALF - 1) - Position(0), Offset(i64::MAX));
+    assert!(std::panic::catch_unwind(|| Position(HALF) - Position(0)).is_err());
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Add, id::Sub)]
+    #[id(delta = Offset)]
+    struct Level(i64);
+    assert_eq!(Level(0) + Offset(i64::MIN), Level(i64::MIN));
+    assert_eq!(Level(-1) - Offset(i64::MIN), Level(i64::MAX));
+    assert_eq!(Level(i64::MIN) - Level(0), Offset(i64::MIN));
+    assert_eq!(Level(-1) - Level(i64::MAX), Offset(i64::MIN));
+    assert!(std::panic::catch_unwind(|| Level(i64::MAX) - Level(-1)).is_err());
+}
+
+#[test]
+fn test_offset() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Offset)]
+    struct OffsetId(u8);
+    assert_eq!(OffsetId(10).offset(-3), OffsetId(7));
+    assert_eq!(OffsetId(10).offset(3), OffsetId(13));
+    assert_eq!(OffsetId(10).checked_offset(-11), None);
+    assert_eq!(OffsetId(10).checked_offset(246), None);
+    assert_eq!(OffsetId(10).checked_offset(245), Some(OffsetId(255)));
+    assert_eq!(OffsetId(1).wrapping_offset(-2), OffsetId(255));
+    assert_eq!(OffsetId(255).wrapping_offset(2), OffsetId(1));
+}
+
+#[test]
+fn test_from_str() {
+    #[derive(Debug, PartialEq, Eq, id::FromStr)]
+    struct FromStrId(u32);
+    assert_eq!("12".parse::<FromStrId>(), Ok(FromStrId(12)));
+    assert!("abc".parse::<FromStrId>().is_err());
+}
+
+#[test]
+fn test_parse_error() {
+    use std::error::Error;
+
+    #[derive(Debug, PartialEq, Eq, id::FromStr)]
+    #[id(parse_error)]
+    struct UserId(u32);
+    assert_eq!("12".parse::<UserId>().unwrap(), UserId(12));
+    let err = "abc".parse::<UserId>().unwrap_err();
+    assert_eq!(err.input(), "abc");
+    assert_eq!(err.type_name(), "UserId");
+    assert_eq!(err.cause(), Some(&"abc".parse::<u32>().unwrap_err()));
+    assert_eq!(
+        err.to_string(),
+        "invalid UserId \"abc\": invalid digit found in string"
+    );
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        "invalid digit found in string"
+    );
+}
+
+#[test]
+fn test_prefix() {
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr)]
+    #[id(prefix = "DOC-")]
+    struct DocId(u32);
+    assert_eq!(DocId(42).to_string(), "DOC-42");
+    assert_eq!("DOC-42".parse::<DocId>().unwrap(), DocId(42));
+    assert!(" DOC-42".parse::<DocId>().is_err());
+    assert!("doc-42".parse::<DocId>().is_err());
+    let err = "42".parse::<DocId>().unwrap_err();
+    assert_eq!(err.cause(), None);
+    assert_eq!(
+        err.to_string(),
+        "invalid DocId \"42\": expected prefix \"DOC-\""
+    );
+}
+
+#[test]
+fn test_display_template() {
+    #[derive(id::Display)]
+    #[id(display = "user/{}")]
+    struct UserId(u32);
+    assert_eq!(UserId(42).to_string(), "user/42");
+
+    #[derive(id::Display)]
+    #[id(display = "{{{:>4}}}")]
+    struct Wrapped(u8);
+    assert_eq!(Wrapped(7).to_string(), "{   7}");
+}
+
+#[test]
+fn test_display_round_trip() {
+    use std::convert::TryFrom;
+
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr)]
+    #[id(display = "{{user/{:03}}}")]
+    struct UserId(u32);
+    assert_eq!(UserId(7).to_string(), "{user/007}");
+    assert_eq!("{user/007}".parse::<UserId>().unwrap(), UserId(7));
+    assert_eq!(UserId::try_from("{user/1234}").unwrap(), UserId(1234));
+    UserId(7).assert_round_trip();
+    let err = "{user/7".parse::<UserId>().unwrap_err();
+    assert_eq!(err.cause(), None);
+    assert_eq!(
+        err.to_string(),
+        "invalid UserId \"{user/7\": expected format \"{{user/{:03}}}\""
+    );
+
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr)]
+    #[id(display = "[{:>4}]", parse(trim))]
+    struct Padded(u8);
+    Padded(5).assert_round_trip();
+
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr)]
+    #[id(prefix = "DOC-")]
+    struct DocId(u32);
+    DocId(42).assert_round_trip();
+}
+
+#[test]
+#[should_panic(expected = "\"[   5]\" does not parse back")]
+fn test_display_round_trip_failure() {
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr)]
+    #[id(display = "[{:>4}]")]
+    struct Padded(u8);
+    Padded(5).assert_round_trip();
+}
+
+#[test]
+fn test_try_from_str() {
+    use std::convert::TryFrom;
+
+    #[derive(Debug, PartialEq, Eq, id::FromStr)]
+    struct UserId(u32);
+    assert_eq!(UserId::try_from("7").unwrap(), UserId(7));
+    assert_eq!(UserId::try_from(String::from("7")).unwrap(), UserId(7));
+    assert_eq!(
+        UserId::try_from("x").unwrap_err(),
+        "x".parse::<u32>().unwrap_err()
+    );
+
+    #[derive(Debug, PartialEq, Eq, id::FromStr)]
+    #[id(parse_error)]
+    struct DocId(u32);
+    let err: DocIdParseError = DocId::try_from(String::from("x")).unwrap_err();
+    assert_eq!(err.input(), "x");
+
+    #[derive(Debug, id::OpaqueId)]
+    struct Name(String);
+    assert_eq!(
+        Name::try_from("alice").unwrap(),
+        Name::from(String::from("alice"))
+    );
+}
+
+#[test]
+fn test_lenient_parsing() {
+    #[derive(Debug, PartialEq, Eq, id::FromStr)]
+    #[id(prefix = "DOC-", parse(trim, case_insensitive_prefix, allow_plus))]
+    struct DocId(u32);
+    assert_eq!("  doc-42 ".parse::<DocId>().unwrap(), DocId(42));
+    assert_eq!("Doc-+42".parse::<DocId>().unwrap(), DocId(42));
+    assert!("DO-42".parse::<DocId>().is_err());
+    assert!("é".parse::<DocId>().is_err());
+
+    #[derive(Debug, PartialEq, Eq, id::FromStr)]
+    #[id(parse(trim, allow_plus))]
+    struct Name(String);
+    assert_eq!(
+        " +alice\n".parse::<Name>().unwrap(),
+        Name(String::from("alice"))
+    );
+}
+
+#[test]
+fn test_companion_derive() {
+    use std::collections::HashSet;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, id::Range)]
+    #[id(companion_derive(Clone, Debug, Hash))]
+    struct DocId(u32);
+    let ranges: HashSet<DocIdRange> = vec![DocId(1).to(DocId(3)), DocId(1).to(DocId(3))]
+        .into_iter()
+        .collect();
+    assert_eq!(ranges.len(), 1);
+
+    #[derive(Debug, PartialEq, id::FromStr)]
+    #[id(parse_error, companion_derive(Clone, Debug, PartialEq))]
+    struct UserId(u32);
+    assert_eq!("1".parse::<UserId>(), Ok(UserId(1)));
+    let err = "abc".parse::<UserId>().unwrap_err();
+    assert_eq!(err.clone(), err);
+}
+
+#[test]
+fn test_atomic() {
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Atomic)]
+    struct JobId(u64);
+    let high_water_mark = Arc::new(AtomicJobId::new(JobId(0)));
+    let handles: Vec<_> = (1..=4)
+        .map(|job| {
+            let high_water_mark = Arc::clone(&high_water_mark);
+            thread::spawn(move || high_water_mark.fetch_max(JobId(job), Ordering::SeqCst))
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(high_water_mark.load(Ordering::SeqCst), JobId(4));
+
+    let id = AtomicJobId::from(JobId(1));
+    assert_eq!(id.swap(JobId(5), Ordering::Relaxed), JobId(1));
+    assert_eq!(id.fetch_add(2, Ordering::Relaxed), JobId(5));
+    assert_eq!(
+        id.compare_exchange(JobId(1), JobId(2), Ordering::Relaxed, Ordering::Relaxed),
+        Err(JobId(7))
+    );
+    id.store(JobId(3), Ordering::Relaxed);
+    assert_eq!(format!("{:?}", id), "AtomicJobId(3)");
+    assert_eq!(id.into_inner(), JobId(3));
+    assert_eq!(AtomicJobId::default().into_inner(), JobId(0));
+}
+
+#[test]
+fn test_pool() {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, id::Pool)]
+    struct ConnectionId(usize);
+    let pool = Arc::new(ConnectionIdPool::default());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || (0..10).map(|_| pool.acquire()).collect::<Vec<_>>())
+        })
+        .collect();
+    let acquired: HashSet<_> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect();
+    assert_eq!(acquired, (0..40).map(ConnectionId).collect());
+    pool.release(ConnectionId(3));
+    pool.release(ConnectionId(7));
+    assert_eq!(pool.acquire(), ConnectionId(7));
+    assert_eq!(pool.acquire(), ConnectionId(3));
+    assert_eq!(pool.acquire(), ConnectionId(40));
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Pool)]
+    struct SlotId(u8);
+    let pool = SlotIdPool::starting_at(SlotId(254));
+    assert_eq!(pool.try_acquire(), Some(SlotId(254)));
+    assert_eq!(pool.try_acquire(), Some(SlotId(255)));
+    assert_eq!(pool.try_acquire(), None);
+    pool.release(SlotId(255));
+    assert_eq!(pool.try_acquire(), Some(SlotId(255)));
+}
+
+#[test]
+fn test_label() {
+    use std::thread;
+
+    #[derive(Clone, Copy, PartialEq, id::Label)]
+    struct VertexId(u64);
+    assert_eq!(VertexId::set_label(VertexId(3), "source"), None);
+    assert_eq!(VertexId(3).label().as_deref(), Some("source"));
+    assert_eq!(format!("{:?}", VertexId(3)), r#"VertexId(3, "source")"#);
+    assert_eq!(format!("{:?}", VertexId(4)), "VertexId(4)");
+    thread::spawn(|| VertexId::set_label(VertexId(4), String::from("sink")))
+        .join()
+        .unwrap();
+    assert_eq!(
+        format!("{:?}", [VertexId(3), VertexId(4)]),
+        r#"[VertexId(3, "source"), VertexId(4, "sink")]"#
+    );
+    assert_eq!(
+        VertexId::set_label(VertexId(3), "start").as_deref(),
+        Some("source")
+    );
+    assert_eq!(
+        VertexId::remove_label(VertexId(3)).as_deref(),
+        Some("start")
+    );
+    assert_eq!(VertexId(3).label(), None);
+    assert_eq!(
+        format!("{:#?}", VertexId(4)),
+        "VertexId(\n    4,\n    \"sink\",\n)"
+    );
+}
+
+#[test]
+fn test_transparent() {
+    #[derive(Debug, PartialEq, id::Transparent)]
+    #[repr(transparent)]
+    struct NodeId(u64);
+    let parents = [0_u64, 0, 1];
+    let parent: &NodeId = NodeId::from_ref(&parents[2]);
+    assert_eq!(parent, &NodeId(1));
+    assert_eq!(parent.as_inner_ref(), &1);
+    let mut raw = 5;
+    let id = NodeId::from_mut(&mut raw);
+    id.0 = 9;
+    assert_eq!(raw, 9);
+
+    #[derive(Debug, PartialEq, id::Transparent)]
+    #[repr(transparent)]
+    struct NameId<T>(T);
+    let name = String::from("root");
+    assert_eq!(NameId::from_ref(&name), &NameId(String::from("root")));
+
+    #[derive(Debug, PartialEq, id::Transparent, id::FromInner)]
+    #[repr(transparent)]
+    #[id(opaque)]
+    struct TokenId(u32);
+    assert_eq!(TokenId::from_ref(&3), &TokenId::from_raw(3));
+
+    let mut decoded = Vec::with_capacity(100);
+    decoded.extend(0..10_u64);
+    let ptr = decoded.as_ptr();
+    let mut ids = NodeId::wrap_vec(decoded);
+    assert_eq!(ids.len(), 10);
+    assert_eq!(ids.capacity(), 100);
+    assert_eq!(ids[3], NodeId(3));
+    ids.push(NodeId(10));
+    let raw = NodeId::unwrap_vec(ids);
+    assert_eq!(raw.as_ptr(), ptr);
+    assert_eq!(raw, (0..=10).collect::<Vec<_>>());
+    let names = NameId::wrap_vec(vec![String::from("a"), String::from("b")]);
+    assert_eq!(NameId::unwrap_vec(names).concat(), "ab");
+    assert!(NodeId::wrap_vec(Vec::new()).is_empty());
+
+    let mut buffer = [3_u64, 1, 2];
+    let ids = NodeId::wrap_slice(&buffer);
+    assert_eq!(ids, [NodeId(3), NodeId(1), NodeId(2)]);
+    assert_eq!(NodeId::unwrap_slice(ids).as_ptr(), buffer.as_ptr());
+    NodeId::wrap_slice_mut(&mut buffer).sort_by_key(|id| id.0);
+    assert_eq!(buffer, [1, 2, 3]);
+    let mut ids = [NodeId(7), NodeId(8)];
+    NodeId::unwrap_slice_mut(&mut ids)[1] += 1;
+    assert_eq!(ids, [NodeId(7), NodeId(9)]);
+    assert!(NodeId::wrap_slice(&[]).is_empty());
+
+    // Raw bytes, as read from a memory-mapped file, aligned for the inner type.
+    let bytes: Vec<u8> = [4_u64, 6, 9].iter().flat_map(|n| n.to_ne_bytes()).collect();
+    let (prefix, raw, _) = unsafe { bytes.align_to::<u64>() };
+    if prefix.is_empty() {
+        let ids = NodeIdSlice::from_raw(raw);
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids.as_ids(), [NodeId(4), NodeId(6), NodeId(9)]);
+        assert_eq!(ids.as_raw().as_ptr(), raw.as_ptr());
+        let ids: Vec<_> = ids.into_iter().map(|id| id.0).collect();
+        assert_eq!(ids, [4, 6, 9]);
+    }
+    let mut raw = [3_u64, 2, 1];
+    let ids = NodeIdSlice::from_raw_mut(&mut raw);
+    ids.sort_by_key(|id| id.0);
+    ids[0].0 = 0;
+    assert_eq!(raw, [0, 2, 3]);
+    let names = [NameId(String::from("a"))];
+    assert_eq!(NameIdSlice::from_ids(&names).as_raw(), ["a"]);
+}
+
+#[test]
+fn test_validate() {
+    use std::convert::TryFrom;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct OutOfRange(u16);
+
+    fn port(inner: &u16) -> Result<(), OutOfRange> {
+        if *inner >= 1024 {
+            Ok(())
+        } else {
+            Err(OutOfRange(*inner))
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Id, id::FromStr)]
+    #[id(validate(port, error = OutOfRange))]
+    struct Port(u16);
+    assert_eq!(Port::try_from(8080), Ok(Port(8080)));
+    assert_eq!(Port::try_from(80), Err(OutOfRange(80)));
+    assert_eq!("8080".parse::<Port>().unwrap(), Port(8080));
+    let err = "80".parse::<Port>().unwrap_err();
+    assert!(err.cause().is_none());
+    assert_eq!(err.to_string(), "invalid Port \"80\": rejected by validation");
+    assert_eq!(u16::from(Port(8080) + 1), 8081);
+}
+
+#[test]
+fn test_shard() {
+    use std::num::Wrapping;
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(shard)]
+    struct UserId(u64);
+    assert_eq!(UserId(10).shard(4), 2);
+    assert_eq!(UserId(3).shard(1), 0);
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(shard = "multiplicative")]
+    struct EvenId(u32);
+    let mut counts = [0; 4];
+    for id in (0..4000).step_by(2) {
+        counts[EvenId(id).shard(4) as usize] += 1;
+    }
+    assert!(counts.iter().all(|&count| count > 400), "{:?}", counts);
+
+    #[derive(Debug, PartialEq)]
+    struct ShardId(u32);
+    impl From<u32> for ShardId {
+        fn from(shard: u32) -> Self {
+            Self(shard)
+        }
+    }
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(shard, shard_output = ShardId)]
+    struct CounterId(Wrapping<u16>);
+    assert_eq!(CounterId(Wrapping(7)).shard(3), ShardId(1));
+}
+
+#[test]
+#[should_panic(expected = "number of shards must be positive")]
+fn test_shard_zero() {
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(shard)]
+    struct UserId(u64);
+    let _ = UserId(10).shard(0);
+}
+
+#[test]
+fn test_from_hash() {
+    use std::num::Wrapping;
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(from_hash)]
+    struct TagId(u64);
+    assert_eq!(TagId::from_hash(&1_u64), TagId(9_929_646_806_074_584_996));
+    assert_eq!(TagId::from_hash(&1_usize), TagId::from_hash(&1_u64));
+    assert_eq!(
+        TagId::from_hash("rust"),
+        TagId::from_hash(&"rust".to_string())
+    );
+    assert_ne!(TagId::from_hash("rust"), TagId::from_hash("go"));
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(from_hash = "fx")]
+    struct BlobId(Wrapping<u64>);
+    assert_eq!(
+        BlobId::from_hash(&1_u64),
+        BlobId(Wrapping(0x517c_c1b7_2722_0a95))
+    );
+    assert_ne!(
+        BlobId::from_hash(&[1_u8; 9][..]),
+        BlobId::from_hash(&[1_u8; 8][..])
+    );
+}
+
+#[test]
+fn test_scramble() {
+    use std::collections::HashSet;
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(scramble(key = 0x0123_4567_89ab_cdef))]
+    struct SmallId(u8);
+    let public: HashSet<_> = (0..=255).map(|id| SmallId(id).to_public()).collect();
+    assert_eq!(public.len(), 256);
+    for id in 0..=255 {
+        assert_eq!(SmallId::from_public(SmallId(id).to_public()), SmallId(id));
+    }
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(scramble(key = 42))]
+    struct OrderId(u64);
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(scramble(key = 43))]
+    struct OtherOrderId(u64);
+    let public: Vec<_> = (1..100).map(|id| OrderId(id).to_public()).collect();
+    assert!(public.windows(2).any(|pair| pair[0] > pair[1]));
+    assert_ne!(OrderId(1).to_public(), OtherOrderId(1).to_public());
+    for (id, public) in (1..100).zip(public) {
+        assert_eq!(OrderId::from_public(public), OrderId(id));
+    }
+
+    #[derive(Debug, PartialEq, Copy, Clone, id::Id)]
+    #[id(scramble(key = 7))]
+    struct WideId(u128);
+    let id = WideId(u128::MAX - 5);
+    assert_eq!(WideId::from_public(id.to_public()), id);
+}
+
+#[test]
+fn test_check_digit() {
+    #[derive(Debug, PartialEq, id::Display, id::FromStr)]
+    #[id(check_digit = "luhn")]
+    struct AccountId(u32);
+    assert_eq!(AccountId(0).to_string(), "00");
+    assert_eq!(AccountId(18).to_string(), "182");
+    assert_eq!("182".parse::<AccountId>().unwrap(), AccountId(18));
+    assert_eq!(
+        "183".parse::<AccountId>().unwrap_err().to_string(),
+        "invalid AccountId \"183\": invalid check digit"
+    );
+    assert_eq!(
+        "8".parse::<AccountId>().unwrap_err().to_string(),
+        "invalid AccountId \"8\": missing check digit"
+    );
+    let err = "1x2".parse::<AccountId>().unwrap_err();
+    assert!(err.cause().is_some());
+    AccountId(4_294_967_295).assert_round_trip();
+
+    #[derive(Debug, PartialEq, id::Display, id::FromStr)]
+    #[id(display = "card {:>8}", check_digit = "luhn", parse(trim))]
+    struct CardId(usize);
+    assert_eq!(CardId(4321).to_string(), "card    43216");
+    assert_eq!("card    43216".parse::<CardId>().unwrap(), CardId(4321));
+    CardId(987_654_321).assert_round_trip();
+}
+
+#[test]
+fn test_opaque_id() {
+    use std::collections::HashSet;
+    #[derive(Debug, id::OpaqueId)]
+    struct UserId(String);
+    let id: UserId = "alice".parse().unwrap();
+    assert_eq!(id, UserId::from(String::from("alice")));
+    assert_eq!(&id.to_string(), "alice");
+    assert!(UserId::from(String::from("a")) < UserId::from(String::from("b")));
+    let ids: HashSet<_> = vec![id, UserId::from(String::from("alice"))]
+        .into_iter()
+        .collect();
+    assert_eq!(ids.len(), 1);
+    assert_eq!(String::from(ids.into_iter().next().unwrap()), "alice");
+}
+
+#[test]
+fn test_generics() {
+    use std::marker::PhantomData;
+    use std::ops::Add;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    struct Tagged<T>(u32, PhantomData<T>);
+    impl<T> Add for Tagged<T> {
+        type Output = Self;
+        fn add(self, rhs: Self) -> Self {
+            Tagged(self.0 + rhs.0, PhantomData)
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, id::Add, id::FromInner, id::IntoInner)]
+    struct GenericId<T>(Tagged<T>)
+    where
+        T: Copy;
+    let id = GenericId::<u8>::from(Tagged(1, PhantomData)) + GenericId(Tagged(2, PhantomData));
+    assert_eq!(Tagged::from(id), Tagged(3, PhantomData));
+}
+
+mod visibility {
+    use id_derive as id;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Succ, id::Range)]
+    #[id(vis = "pub(crate)")]
+    pub struct VisId(pub u32);
+}
+
+#[test]
+fn test_vis() {
+    use visibility::VisId;
+    assert_eq!(VisId(1).next(), VisId(2));
+    let range: visibility::VisIdRange = VisId(1).to(VisId(3));
+    assert_eq!(range.count(), 2);
+}
+
+mod namespaced {
+    use id_derive as id;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Display, id::FromStr)]
+    #[id(module = user_id, display = "user/{}")]
+    pub struct UserId(pub u32);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Range)]
+    #[id(module = doc_id)]
+    struct DocId(u32);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Range)]
+    #[id(module = vis_id, vis = "pub(super)")]
+    pub struct VisId(pub u32);
+
+    pub fn doc_id_count() -> usize {
+        let range: doc_id::RangeInclusive = DocId(1).to_inclusive(DocId(3));
+        range.count()
+    }
+}
+
+#[test]
+fn test_module() {
+    use namespaced::{user_id, UserId, VisId};
+    let err: user_id::ParseError = "42".parse::<UserId>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid UserId \"42\": expected format \"user/{}\""
+    );
+    assert_eq!("user/42".parse::<UserId>().unwrap(), UserId(42));
+    UserId(42).assert_round_trip();
+    assert_eq!(namespaced::doc_id_count(), 3);
+    let range: namespaced::vis_id::Range = VisId(1).to(VisId(3));
+    assert_eq!(range.count(), 2);
+}
+
+mod minting {
+    use id_derive as id;
+
+    #[derive(Debug, Clone, Copy, id::OpaqueId)]
+    #[id(opaque)]
+    pub struct TicketId(u64);
+
+    pub fn issue(serial: u64) -> TicketId {
+        TicketId::from_raw(serial)
+    }
+}
+
+#[test]
+fn test_opaque_constructor() {
+    use minting::TicketId;
+    let ticket = minting::issue(12);
+    assert_eq!(ticket.to_string(), "12");
+    assert_eq!("12".parse::<TicketId>().unwrap(), ticket);
+    assert_eq!(ticket.into_raw(), 12);
+    assert_eq!(u64::from(ticket), 12);
+}
+
+#[test]
+fn test_ffi() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+    #[id(ffi)]
+    #[repr(transparent)]
+    struct BufferHandle(u32);
+
+    extern "C" fn grow(handle: BufferHandle, by: u32) -> BufferHandle {
+        handle + by
+    }
+
+    const NULL: BufferHandle = BufferHandle::from_raw(0);
+    const RAW: u32 = BufferHandle(5).into_raw();
+    assert_eq!(grow(NULL, RAW), BufferHandle(5));
+    assert_eq!(BufferHandle::from(7), BufferHandle::from_raw(7));
+    assert_eq!(std::mem::size_of::<BufferHandle>(), 4);
+
+    #[derive(Debug, id::OpaqueId)]
+    #[id(ffi)]
+    #[repr(transparent)]
+    struct DeviceHandle(u64);
+    const DEVICE: DeviceHandle = DeviceHandle::from_raw(3);
+    assert_eq!(DEVICE.into_raw(), 3);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_renamed_from() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+    #[id(renamed_from = "OldDocId")]
+    struct DocId(u32);
+    let old: OldDocId = OldDocId::from(4);
+    let new: DocId = old;
+    assert_eq!(new + 1, DocId(5));
+
+    #[derive(Debug, PartialEq, id::FromInner)]
+    #[id(renamed_from = "OldKey")]
+    struct Key<T: Clone>(T)
+    where
+        T: PartialEq;
+    let key: OldKey<u8> = Key::from(1_u8);
+    assert_eq!(key, Key(1));
+}
+
+#[test]
+fn test_inline() {
+    use std::convert::TryFrom;
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, id::Id, id::FromStr, id::Range, id::Atomic)]
+    #[id(inline = "always", parse_error)]
+    struct HotId(u32);
+    assert_eq!(HotId(1) + HotId(2), HotId(3));
+    assert_eq!("7".parse::<HotId>().unwrap(), HotId(7));
+    assert!("x".parse::<HotId>().is_err());
+    assert_eq!(HotId(1).to(HotId(3)).count(), 2);
+    assert_eq!(
+        AtomicHotId::new(HotId(4)).load(std::sync::atomic::Ordering::Relaxed),
+        HotId(4)
+    );
+
+    #[derive(Debug, id::OpaqueId)]
+    #[id(inline = "never", validate(non_empty, error = String))]
+    struct ColdId(String);
+    fn non_empty(inner: &str) -> Result<(), String> {
+        if inner.is_empty() {
+            Err(String::from("empty"))
+        } else {
+            Ok(())
+        }
+    }
+    assert_eq!(
+        ColdId::try_from(String::from("x")),
+        Ok(ColdId(String::from("x")))
+    );
+
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+    #[id(inline = "default")]
+    struct WarmId(f64);
+    assert_eq!(WarmId(1.5) * 2.0, WarmId(3.0));
+}
+
+#[test]
+fn test_cast() {
+    #[derive(Debug, PartialEq, id::Convert)]
+    struct UserId(u32);
+    #[derive(Debug, id::OpaqueId)]
+    struct HTTPSessionId(u32);
+    #[derive(Debug, PartialEq, id::Id)]
+    #[id(cast_to(UserId, HTTPSessionId))]
+    struct LegacyUserId(u32);
+    assert_eq!(LegacyUserId(7).to_user_id(), UserId(7));
+    assert_eq!(LegacyUserId(7).to_http_session_id(), HTTPSessionId(7));
+}
+
+#[test]
+fn test_from_id() {
+    #[derive(Debug, PartialEq, id::Convert)]
+    struct LegacyUserId(u32);
+    #[derive(Debug, id::OpaqueId)]
+    #[id(from_id(LegacyUserId))]
+    struct UserId(u32);
+    #[derive(Debug, PartialEq, id::Id)]
+    #[id(from_id(LegacyUserId, UserId))]
+    struct AccountId(u32);
+    assert_eq!(u32::from(UserId::from(LegacyUserId(3))), 3);
+    assert_eq!(AccountId::from(LegacyUserId(3)), AccountId(3));
+    assert_eq!(AccountId::from(UserId(4)), AccountId(4));
+}
+
+#[test]
+fn test_debug_overflow_checks() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Id)]
+    #[id(debug_overflow_checks)]
+    struct CheckedId(u8);
+    assert_eq!(CheckedId(250) + 5, CheckedId(255));
+    let mut id = CheckedId(10);
+    id -= CheckedId(4);
+    id *= 2;
+    assert_eq!(id, CheckedId(12));
+    let result = std::panic::catch_unwind(|| CheckedId(255) + CheckedId(1));
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+    let result = std::panic::catch_unwind(|| {
+        let mut id = CheckedId(0);
+        id -= 1;
+        id
+    });
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Offset(i64);
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, id::Add, id::Sub, id::AddAssignInner)]
+    #[id(delta = Offset, debug_overflow_checks)]
+    struct DeltaId(u8);
+    assert_eq!(DeltaId(4) + Offset(10), DeltaId(14));
+    assert_eq!(DeltaId(4) - Offset(-10), DeltaId(14));
+    let result = std::panic::catch_unwind(|| DeltaId(255) + Offset(1));
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+}
+
+#[test]
+fn test_base32() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Base32)]
+    struct LabelId(u128);
+    for &(inner, encoded) in &[
+        (0, "0"),
+        (31, "Z"),
+        (32, "10"),
+        (u128::from(u64::MAX), "FZZZZZZZZZZZZ"),
+        (u128::MAX, "7ZZZZZZZZZZZZZZZZZZZZZZZZZ"),
+    ] {
+        assert_eq!(LabelId(inner).to_base32_crockford(), encoded);
+        assert_eq!(
+            LabelId::from_base32_crockford(encoded),
+            Some(LabelId(inner))
+        );
+    }
+    assert_eq!(
+        LabelId::from_base32_crockford("oIl-z"),
+        Some(LabelId(32 * 32 + 32 + 31))
+    );
+    assert_eq!(LabelId::from_base32_crockford("U"), None);
+    assert_eq!(LabelId::from_base32_crockford("-"), None);
+    assert_eq!(
+        LabelId::from_base32_crockford("80000000000000000000000000"),
+        None
+    );
+
+    #[derive(Debug, PartialEq, id::Base32)]
+    struct TicketId(u8);
+    assert_eq!(TicketId::from_base32_crockford("7Z"), Some(TicketId(255)));
+    assert_eq!(TicketId::from_base32_crockford("80"), None);
+
+    #[derive(Debug, PartialEq, id::Display, id::FromStr)]
+    #[id(display = "[{:>4}]", base32, parse(trim))]
+    struct ShelfId(usize);
+    assert_eq!(ShelfId(1024).to_string(), "[ 100]");
+    assert_eq!("[ 100]".parse::<ShelfId>().unwrap(), ShelfId(1024));
+    assert_eq!(
+        "[10U]".parse::<ShelfId>().unwrap_err().to_string(),
+        "invalid ShelfId \"[10U]\": invalid Crockford Base32 value"
+    );
+    ShelfId(usize::MAX).assert_round_trip();
+}
+
+#[test]
+fn test_bit_ops() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::BitOps)]
+    #[id(debug_overflow_checks)]
+    struct FlagId(u8);
+    assert_eq!(FlagId(0b1100) & FlagId(0b1010), FlagId(0b1000));
+    assert_eq!(FlagId(0b1100) | FlagId(0b1010), FlagId(0b1110));
+    assert_eq!(FlagId(0b1100) ^ FlagId(0b1010), FlagId(0b0110));
+    assert_eq!(!FlagId(0b1111_0000), FlagId(0b0000_1111));
+    let mut id = FlagId(0b0001);
+    id |= FlagId(0b0110);
+    id &= FlagId(0b0011);
+    id ^= FlagId(0b1000);
+    assert_eq!(id, FlagId(0b1011));
+
+    use std::num::Wrapping;
+    #[derive(Debug, PartialEq, Clone, Copy, id::BitOps)]
+    struct WrappingFlagId(Wrapping<i16>);
+    assert_eq!(
+        !WrappingFlagId(Wrapping(0)) & WrappingFlagId(Wrapping(5)),
+        WrappingFlagId(Wrapping(5))
+    );
+}
+
+#[test]
+fn test_mask_id() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::MaskId)]
+    struct Capability(u64);
+    const NET: Capability = Capability(1);
+    const DISK: Capability = Capability(1 << 7);
+    const ROOT: Capability = Capability(1 << 63);
+    const ALL: Capability = NET.union(DISK).union(ROOT);
+    const NONE: Capability = ALL.difference(ALL);
+
+    assert!(NONE.is_empty());
+    assert!(!ALL.is_empty());
+    assert!(ALL.contains(&NET.union(ROOT)));
+    assert!(!DISK.contains(&ALL));
+    assert!(ALL.contains(&NONE));
+    assert_eq!(ALL.intersection(DISK.union(Capability(2))), DISK);
+    assert_eq!(ALL.iter().collect::<Vec<_>>(), vec![NET, DISK, ROOT]);
+    assert_eq!(NONE.iter().count(), 0);
+
+    let mut caps = NET;
+    caps.insert(DISK.union(ROOT));
+    assert_eq!(caps, ALL);
+    caps.remove(NET.union(DISK));
+    assert_eq!(caps, ROOT);
+    caps.toggle(ROOT.union(NET));
+    assert_eq!(caps, NET);
+    assert_eq!(!caps & ALL, DISK | ROOT);
+}
+
+#[test]
+fn test_non_numeric_display() {
+    #[derive(id::Display)]
+    struct Name(String);
+    assert_eq!(Name(String::from("alice")).to_string(), "alice");
+
+    #[derive(id::Display)]
+    #[id(prefix = "req-")]
+    struct RequestId(uuid::Uuid);
+    assert_eq!(
+        RequestId(uuid::Uuid::nil()).to_string(),
+        "req-00000000-0000-0000-0000-000000000000"
+    );
+}
+
+#[test]
+fn test_adaptive_id() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+    struct RequestId(uuid::Uuid);
+    let request = RequestId::from(uuid::Uuid::nil());
+    assert_eq!(uuid::Uuid::from(request), uuid::Uuid::nil());
+    assert_eq!(request.to_string(), "00000000-0000-0000-0000-000000000000");
+
+    #[derive(Debug, PartialEq, Clone, id::Id)]
+    struct Tag<'a>(std::borrow::Cow<'a, str>);
+    assert_eq!(
+        Tag::from(std::borrow::Cow::Borrowed("rust")).to_string(),
+        "rust"
+    );
+
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+    struct Score(f64);
+    assert_eq!(Score(1.5) + Score(1.0), Score(2.5));
+}
+
+#[test]
+fn test_inner_ref_ops() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+    struct DocId(u64);
+    let values = [1_u64, 2, 4];
+    let id = values.iter().fold(DocId(8), |id, value| id + value);
+    assert_eq!(id, DocId(15));
+    let mut id = DocId(8);
+    for value in &values {
+        id += value;
+    }
+    assert_eq!(id, DocId(15));
+    let two = &values[1];
+    assert_eq!(id - two, DocId(13));
+    assert_eq!(id * two, DocId(30));
+    assert_eq!(id / two, DocId(7));
+    id -= two;
+    id *= two;
+    id /= two;
+    assert_eq!(id, DocId(13));
+
+    #[derive(Debug, PartialEq, Clone, Copy, id::DivInner, id::SubAssignInner)]
+    #[id(div = "checked")]
+    struct ShardId(u32);
+    let divisors = [2_u32, 0, 3];
+    assert_eq!(
+        divisors.iter().map(|d| ShardId(8) / d).collect::<Vec<_>>(),
+        vec![Some(ShardId(4)), None, Some(ShardId(2))]
+    );
+    let mut shard = ShardId(8);
+    for d in &divisors {
+        shard -= d;
+    }
+    assert_eq!(shard, ShardId(3));
+
+    use std::num::Wrapping;
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+    #[id(debug_overflow_checks)]
+    struct SeqId(Wrapping<u8>);
+    let steps = [Wrapping(2_u8)];
+    assert_eq!(SeqId::from(255) + steps.first().unwrap(), SeqId::from(1));
+}
+
+#[test]
+fn test_commutative_inner() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::Id, id::CommutativeInner)]
+    struct NodeId(usize);
+    let base = 100_usize;
+    assert_eq!(base + NodeId(5), NodeId(105));
+    assert_eq!(base + NodeId(5), NodeId(5) + base);
+    assert_eq!(2 * NodeId(5), NodeId(10));
+
+    use std::num::Wrapping;
+    #[derive(Debug, PartialEq, Clone, Copy, id::CommutativeInner)]
+    #[id(debug_overflow_checks)]
+    struct SeqId(Wrapping<u8>);
+    assert_eq!(Wrapping(200) + SeqId(Wrapping(100)), SeqId(Wrapping(44)));
+}
+
+#[test]
+#[should_panic(expected = "attempt to multiply with overflow")]
+fn test_commutative_inner_overflow() {
+    #[derive(Debug, PartialEq, Clone, Copy, id::CommutativeInner)]
+    #[id(debug_overflow_checks)]
+    struct NodeId(u8);
+    let _ = 128 * NodeId(2);
+}
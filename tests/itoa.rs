@@ -0,0 +1,42 @@
+#![cfg(feature = "itoa")]
+
+use id_derive as id;
+
+#[test]
+fn test_write_to() {
+    #[derive(id::Display, id::Itoa)]
+    struct PlainId(i64);
+    #[derive(id::Display, id::Itoa)]
+    #[id(display = "<{}>")]
+    struct TaggedId(u8);
+    #[derive(id::Display, id::Itoa)]
+    #[id(display = "#{:>6}")]
+    struct PaddedId(u16);
+
+    let mut line = String::new();
+    PlainId(i64::MIN).write_to(&mut line).unwrap();
+    TaggedId(255).write_to(&mut line).unwrap();
+    PaddedId(42).write_to(&mut line).unwrap();
+    assert_eq!(
+        line,
+        format!("{}{}{}", PlainId(i64::MIN), TaggedId(255), PaddedId(42))
+    );
+}
+
+#[test]
+#[cfg(feature = "arrayvec")]
+fn test_to_array_string() {
+    #[derive(id::Display, id::Itoa)]
+    #[id(prefix = "user-")]
+    struct UserId(i128);
+    #[derive(id::Display, id::Itoa)]
+    #[id(display = "[{:>8}]")]
+    struct PaddedId(u8);
+
+    let id = UserId(i128::MIN).to_array_string();
+    assert_eq!(id.as_str(), UserId(i128::MIN).to_string());
+    assert_eq!(id.capacity(), 45);
+    let padded = PaddedId(7).to_array_string();
+    assert_eq!(padded.as_str(), "[       7]");
+    assert_eq!(padded.capacity(), 10);
+}
@@ -0,0 +1,27 @@
+use id_derive as id;
+use id_derive::id_laws;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, id::Id, id::FromStr)]
+pub struct UserId(u32);
+
+id_laws!(UserId(u32));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, id::Id, id::FromStr)]
+#[id(display = "slot-{}")]
+pub struct SlotId(u8);
+
+id_laws!(SlotId(u8), samples(0_u8, 1_u8, 128_u8, u8::MAX));
+
+#[derive(Debug, id::OpaqueId)]
+pub struct Name(String);
+
+id_laws!(
+    Name(String),
+    samples("alice", "bob", ""),
+    laws(convert, display, hash)
+);
+
+#[derive(Debug, PartialEq, id::Add, id::Sub, id::AddInner, id::SubInner)]
+pub struct Offset(i64);
+
+id_laws!(Offset(i64), samples(-3_i8, 0_i8, 5_i8), laws(arithmetic));
@@ -0,0 +1,113 @@
+use id_derive as id;
+
+macro_rules! define_id {
+    ($name:ident, $ty:ty) => {
+        #[derive(
+            Debug,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+            Clone,
+            Copy,
+            id::Id,
+            id::FromStr,
+            id::ConstOps,
+            id::BitOps,
+            id::Bounds,
+            id::Pow,
+            id::BitInfo,
+            id::Succ,
+            id::Distance,
+            id::Range,
+            id::Atomic,
+            id::Bytes,
+            id::Base32,
+        )]
+        #[id(prefix = "id-", parse_error, shard, from_hash, scramble(key = 7), key)]
+        pub struct $name($ty);
+    };
+}
+
+macro_rules! define_wrapping_id {
+    ($name:ident, $ty:ty) => {
+        #[derive(Debug, PartialEq, Clone, Copy, id::Id)]
+        #[id(debug_overflow_checks)]
+        pub struct $name(std::num::Wrapping<$ty>);
+    };
+}
+
+macro_rules! define_opaque_id {
+    ($name:ident, $ty:ty) => {
+        #[derive(Debug, PartialEq, Clone, id::Id)]
+        pub struct $name($ty);
+    };
+}
+
+define_id!(NodeId, u32);
+define_wrapping_id!(SeqId, u8);
+define_opaque_id!(LabelId, &'static str);
+
+#[test]
+fn test_macro_generated_id() {
+    assert_eq!(NodeId(1) + NodeId(2), NodeId(3));
+    assert_eq!(NodeId(7).to_string(), "id-7");
+    assert_eq!("id-7".parse::<NodeId>().unwrap(), NodeId(7));
+    assert_eq!(NodeId::from_public(NodeId(7).to_public()), NodeId(7));
+    assert_eq!(NodeId::BITS, 32);
+    assert_eq!(
+        NodeId::from_base32_crockford(&NodeId(40).to_base32_crockford()),
+        Some(NodeId(40))
+    );
+
+    assert_eq!(SeqId::from(255) + std::num::Wrapping(1), SeqId::from(0));
+    assert_eq!(LabelId::from("root").to_string(), "root");
+}
+
+/// Items that shadow names from the prelude, which generated code must not pick up.
+mod shadowed {
+    #![allow(dead_code, non_camel_case_types, unused_macros)]
+
+    use id_derive as id;
+
+    macro_rules! write {
+        ($($arg:tt)*) => {
+            compile_error!("shadowed `write!` used by generated code")
+        };
+    }
+
+    type Option = ();
+    type Result = ();
+    type bool = ();
+    type u32 = ();
+    type usize = ();
+
+    #[derive(
+        Debug,
+        PartialEq,
+        Clone,
+        Copy,
+        id::Id,
+        id::FromStr,
+        id::Bounds,
+        id::Pow,
+        id::BitInfo,
+        id::Succ,
+        id::Range,
+        id::MaskId,
+    )]
+    #[id(prefix = "flag-")]
+    pub struct FlagId(pub u16);
+}
+
+#[test]
+fn test_shadowed_prelude() {
+    use shadowed::FlagId;
+    assert_eq!(FlagId(3).to_string(), "flag-3");
+    assert_eq!(format!("{:b}", FlagId(3)), "11");
+    assert!(FlagId(0).is_zero());
+    assert_eq!(FlagId(3).pow(2), FlagId(9));
+    assert_eq!(FlagId(1).count_ones(), 1);
+    assert!(FlagId(3).contains(&FlagId(1)));
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "metrics")]
+
+use id_derive as id;
+use metrics::SharedString;
+
+#[test]
+fn test_metrics_label() {
+    #[derive(id::Display, id::Metrics)]
+    #[id(display = "shard/{:02}")]
+    struct ShardId(u8);
+    assert_eq!(SharedString::from(ShardId(3)).as_ref(), "shard/03");
+    assert_eq!(SharedString::from(&ShardId(12)).as_ref(), "shard/12");
+    let shard = ShardId(4);
+    let label = metrics::Label::new("shard", &shard);
+    assert_eq!(label.value(), "shard/04");
+    let label = metrics::Label::new("shard", shard);
+    assert_eq!(label.value(), "shard/04");
+
+    #[derive(id::Id, Clone, Copy, PartialEq)]
+    #[id(integrations(metrics))]
+    struct TenantId(u32);
+    assert_eq!(SharedString::from(TenantId(7)).as_ref(), "7");
+}
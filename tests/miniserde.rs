@@ -0,0 +1,37 @@
+#![cfg(feature = "miniserde")]
+
+use id_derive as id;
+use miniserde::json;
+
+#[test]
+fn test_miniserde() {
+    #[derive(Debug, PartialEq, id::Miniserde)]
+    struct UserId(u32);
+    assert_eq!(json::to_string(&UserId(12)), "12");
+    assert_eq!(json::from_str::<UserId>("12").unwrap(), UserId(12));
+    assert!(json::from_str::<UserId>("-1").is_err());
+    assert!(json::from_str::<UserId>("\"12\"").is_err());
+
+    #[derive(Debug, PartialEq, id::Miniserde)]
+    struct OffsetId(i64);
+    assert_eq!(json::from_str::<OffsetId>("-3").unwrap(), OffsetId(-3));
+
+    #[derive(Debug, PartialEq, id::Miniserde)]
+    struct Username(String);
+    assert_eq!(json::to_string(&Username(String::from("ann"))), r#""ann""#);
+    assert_eq!(
+        json::from_str::<Username>(r#""ann""#).unwrap(),
+        Username(String::from("ann"))
+    );
+}
+
+#[test]
+fn test_generic_miniserde() {
+    #[derive(Debug, PartialEq, id::Miniserde)]
+    struct GenericId<T>(T);
+    assert_eq!(json::to_string(&GenericId(1.5_f64)), "1.5");
+    assert_eq!(
+        json::from_str::<Vec<GenericId<u8>>>("[1,2]").unwrap(),
+        [GenericId(1), GenericId(2)]
+    );
+}
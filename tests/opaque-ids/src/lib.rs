@@ -0,0 +1,15 @@
+//! Identifiers defined in a crate of their own, so that the compile-fail tests can check
+//! that other crates cannot create them.
+//!
+//! `Convert` is derived rather than `OpaqueId`, whose integrations would need the crates
+//! of the features enabled for the tests.
+
+use id_derive::{Atomic, Bytes, Convert, Varint};
+
+#[derive(Debug, Clone, Copy, Convert, Bytes, Varint, Atomic)]
+#[id(opaque)]
+pub struct TicketId(u64);
+
+pub fn issue(serial: u64) -> TicketId {
+    TicketId::from_raw(serial)
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "opentelemetry")]
+
+use id_derive as id;
+use opentelemetry::{KeyValue, Value};
+use std::num::Wrapping;
+
+#[test]
+fn test_opentelemetry_value() {
+    #[derive(id::Display, id::Opentelemetry)]
+    struct ShardId(u32);
+    assert_eq!(Value::from(ShardId(3)), Value::I64(3));
+    let shard = ShardId(4);
+    assert_eq!(KeyValue::new("shard", &shard).value, Value::I64(4));
+    assert_eq!(KeyValue::new("shard", shard).value, Value::I64(4));
+
+    #[derive(id::Display, id::Opentelemetry)]
+    struct OffsetId(i16);
+    assert_eq!(Value::from(OffsetId(-2)), Value::I64(-2));
+
+    #[derive(id::Display, id::Opentelemetry)]
+    struct CounterId(Wrapping<u8>);
+    assert_eq!(Value::from(CounterId(Wrapping(9))), Value::I64(9));
+
+    #[derive(id::Display, id::Opentelemetry)]
+    struct ScoreId(f32);
+    assert_eq!(Value::from(ScoreId(0.5)), Value::F64(0.5));
+
+    #[derive(id::Display, id::Opentelemetry)]
+    struct UserId(u64);
+    assert_eq!(
+        Value::from(UserId(u64::MAX)),
+        Value::from(u64::MAX.to_string())
+    );
+
+    #[derive(id::Display, id::Opentelemetry)]
+    #[id(display = "order-{:04}")]
+    struct OrderId(u32);
+    assert_eq!(Value::from(OrderId(7)), Value::from("order-0007"));
+
+    #[derive(id::Id, Clone, Copy, PartialEq)]
+    #[id(integrations(opentelemetry))]
+    struct TenantId(i64);
+    assert_eq!(Value::from(TenantId(-7)), Value::I64(-7));
+}
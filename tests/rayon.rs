@@ -0,0 +1,39 @@
+#![cfg(feature = "rayon")]
+
+use id_derive as id;
+use rayon::prelude::*;
+
+#[test]
+fn test_par_range() {
+    #[derive(Debug, Clone, Copy, PartialEq, id::Range)]
+    struct DocId(u32);
+    let range = DocId::range(DocId(10), DocId(10_000));
+    let ids: Vec<DocId> = range.clone().into_par_iter().collect();
+    assert_eq!(ids, range.collect::<Vec<_>>());
+    let evens = DocId(0)
+        .to(DocId(101))
+        .into_par_iter()
+        .with_min_len(8)
+        .filter(|id| id.0 % 2 == 0)
+        .count();
+    assert_eq!(evens, 51);
+    assert_eq!(DocId(5).to(DocId(5)).into_par_iter().count(), 0);
+    let reversed: Vec<_> = DocId(0).to(DocId(3)).into_par_iter().rev().collect();
+    assert_eq!(reversed, [DocId(2), DocId(1), DocId(0)]);
+    assert_eq!(
+        DocId(u32::MAX - 5000)
+            .to_inclusive(DocId(u32::MAX))
+            .into_par_iter()
+            .find_last(|id| id.0 % 1000 == 0),
+        Some(DocId(4_294_967_000))
+    );
+
+    #[derive(Debug, Clone, Copy, PartialEq, id::Range)]
+    struct OffsetId(i64);
+    let sum: i64 = OffsetId(-50)
+        .to_inclusive(OffsetId(50))
+        .into_par_iter()
+        .map(|id| id.0)
+        .sum();
+    assert_eq!(sum, 0);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "redb")]
+
+use id_derive as id;
+use redb::backends::InMemoryBackend;
+use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
+
+#[derive(Debug, PartialEq, Clone, Copy, id::Redb, id::Bytes)]
+#[id(key)]
+struct UserId(u32);
+
+#[derive(Debug, PartialEq, Clone, Copy, id::Redb)]
+struct Balance(i16);
+
+const BALANCES: TableDefinition<UserId, Balance> = TableDefinition::new("balances");
+
+#[test]
+fn test_redb() -> Result<(), redb::Error> {
+    let db = Database::builder().create_with_backend(InMemoryBackend::new())?;
+    let tx = db.begin_write()?;
+    {
+        let mut table = tx.open_table(BALANCES)?;
+        for (user, balance) in [(300, -2), (2, 5), (70_000, i16::MIN), (0, i16::MAX)] {
+            table.insert(UserId(user), Balance(balance))?;
+        }
+    }
+    tx.commit()?;
+
+    let table = db.begin_read()?.open_table(BALANCES)?;
+    assert_eq!(table.get(UserId(2))?.unwrap().value(), Balance(5));
+    assert!(table.get(UserId(3))?.is_none());
+    let scanned = table
+        .range(UserId(1)..UserId(70_000))?
+        .map(|entry| entry.map(|(user, balance)| (user.value(), balance.value())))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        scanned,
+        [(UserId(2), Balance(5)), (UserId(300), Balance(-2))]
+    );
+    let (last, balance) = table.last()?.unwrap();
+    assert_eq!(
+        (last.value(), balance.value()),
+        (UserId(70_000), Balance(i16::MIN))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_redb_encoding() {
+    use redb::{Key, Value};
+
+    assert_eq!(<UserId as Value>::fixed_width(), Some(4));
+    let bytes = <UserId as Value>::as_bytes(&UserId(258));
+    assert_eq!(bytes, UserId(258).to_key_bytes());
+    assert_eq!(<UserId as Value>::from_bytes(&bytes), UserId(258));
+    let (low, high) = (
+        <Balance as Value>::as_bytes(&Balance(-1)),
+        <Balance as Value>::as_bytes(&Balance(1)),
+    );
+    assert_eq!(
+        <Balance as Key>::compare(&low, &high),
+        std::cmp::Ordering::Less
+    );
+    #[derive(Debug, PartialEq, id::Redb)]
+    struct ShardedId<const SHARD: u8>(u32);
+    let bytes = <ShardedId<1> as Value>::as_bytes(&ShardedId(7));
+    assert_eq!(<ShardedId<1> as Value>::from_bytes(&bytes), ShardedId(7));
+}
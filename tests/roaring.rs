@@ -0,0 +1,53 @@
+#![cfg(feature = "roaring")]
+
+use id_derive as id;
+
+#[test]
+fn test_roaring_bitmap() {
+    #[derive(Debug, PartialEq, id::Roaring)]
+    struct DocId(u32);
+
+    let mut set = DocIdRoaringSet::new();
+    assert!(set.is_empty());
+    assert!(set.insert(DocId(3)));
+    assert!(!set.insert(DocId(3)));
+    set.extend([DocId(u32::MAX), DocId(0)]);
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.min(), Some(DocId(0)));
+    assert_eq!(set.max(), Some(DocId(u32::MAX)));
+    assert!(set.remove(&DocId(0)));
+    assert!(!set.remove(&DocId(0)));
+    let ids: Vec<_> = (&set).into_iter().collect();
+    assert_eq!(ids, [DocId(3), DocId(u32::MAX)]);
+    assert_eq!(set.as_bitmap().len(), 2);
+
+    let evens: DocIdRoaringSet = (0..100).step_by(2).map(DocId).collect();
+    let threes: DocIdRoaringSet = (0..100).step_by(3).map(DocId).collect();
+    assert_eq!((&evens & &threes).len(), 17);
+    assert_eq!((&evens | &threes).len(), 50 + 34 - 17);
+    assert_eq!((&evens ^ &threes).len(), 50 + 34 - 34);
+    assert_eq!((&evens - &threes).len(), 50 - 17);
+    let sixes = evens.clone() & threes.clone();
+    assert!(sixes.is_subset(&evens));
+    assert!(sixes.is_disjoint(&(threes.clone() - evens.clone())));
+    let mut union = evens.clone();
+    union |= threes;
+    union ^= &evens;
+    assert_eq!(union.into_iter().next(), Some(DocId(3)));
+    set.clear();
+    assert_eq!(set, DocIdRoaringSet::default());
+}
+
+#[derive(Debug, PartialEq, id::Roaring)]
+#[id(module = ids)]
+struct RowId(u64);
+
+#[test]
+fn test_roaring_treemap() {
+    let mut set: ids::RoaringSet = vec![RowId(1 << 40), RowId(7)].into_iter().collect();
+    assert!(set.contains(&RowId(1 << 40)));
+    let bitmap: roaring::RoaringTreemap = vec![7, 8].into_iter().collect();
+    set &= ids::RoaringSet::from(bitmap);
+    assert_eq!(set.iter().collect::<Vec<_>>(), [RowId(7)]);
+    assert_eq!(set.into_bitmap().len(), 1);
+}
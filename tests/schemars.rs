@@ -0,0 +1,21 @@
+#![cfg(feature = "schemars")]
+
+use id_derive as id;
+use schemars::schema_for;
+
+#[test]
+fn test_schemars() {
+    #[derive(id::Schemars, id::IntoInner)]
+    struct UserId(u32);
+    assert_eq!(schema_for!(UserId), schema_for!(u32));
+    assert_eq!(u32::from(UserId(7)), 7);
+}
+
+#[test]
+fn test_integrations() {
+    #[derive(Debug, Clone, Copy, PartialEq, id::Id)]
+    #[id(integrations(schemars))]
+    struct UserId(u64);
+    assert_eq!(schema_for!(UserId), schema_for!(u64));
+    assert_eq!(UserId(1) + 1, UserId(2));
+}
@@ -0,0 +1,242 @@
+#![cfg(feature = "serde")]
+
+use id_derive as id;
+
+#[test]
+fn test_serde() {
+    #[derive(Debug, PartialEq, Eq, id::Serde)]
+    struct SerdeId(u32);
+    assert_eq!(serde_json::to_string(&SerdeId(12)).unwrap(), "12");
+    assert_eq!(serde_json::from_str::<SerdeId>("12").unwrap(), SerdeId(12));
+}
+
+#[test]
+fn test_opaque_id_serde() {
+    #[derive(Debug, id::OpaqueId)]
+    struct UserId(String);
+    let id = UserId::from(String::from("alice"));
+    assert_eq!(serde_json::to_string(&id).unwrap(), "\"alice\"");
+    assert_eq!(serde_json::from_str::<UserId>("\"alice\"").unwrap(), id);
+}
+
+#[test]
+fn test_generic_serde() {
+    #[derive(Debug, PartialEq, Eq, id::Serde)]
+    struct GenericId<T>(T)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned;
+    assert_eq!(serde_json::to_string(&GenericId(12_u8)).unwrap(), "12");
+    assert_eq!(
+        serde_json::from_str::<GenericId<u8>>("12").unwrap(),
+        GenericId(12)
+    );
+}
+
+#[test]
+fn test_human_readable_serde() {
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr, id::Serde)]
+    #[id(serde = "human_readable")]
+    struct DocId(u32);
+    assert_eq!(serde_json::to_string(&DocId(12)).unwrap(), "\"12\"");
+    assert_eq!(serde_json::from_str::<DocId>("\"12\"").unwrap(), DocId(12));
+    assert!(serde_json::from_str::<DocId>("\"abc\"").is_err());
+    let bytes = bincode::serialize(&DocId(12)).unwrap();
+    assert_eq!(bytes, 12_u32.to_le_bytes());
+    assert_eq!(bincode::deserialize::<DocId>(&bytes).unwrap(), DocId(12));
+}
+
+#[test]
+fn test_display_format_serde() {
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr, id::Serde)]
+    #[id(serde = "human_readable", display = "doc:{}")]
+    struct DocId(u32);
+    assert_eq!(serde_json::to_string(&DocId(12)).unwrap(), "\"doc:12\"");
+    assert_eq!(
+        serde_json::from_str::<DocId>("\"doc:12\"").unwrap(),
+        DocId(12)
+    );
+    assert!(serde_json::from_str::<DocId>("\"12\"").is_err());
+}
+
+#[test]
+fn test_path_id() {
+    #[derive(Debug, PartialEq, Eq, id::PathId)]
+    struct UserId(u32);
+    assert_eq!(UserId(12).to_string(), "12");
+    assert_eq!("12".parse::<UserId>().unwrap(), UserId(12));
+    let err = "abc".parse::<UserId>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid UserId \"abc\": invalid digit found in string"
+    );
+    let _: &dyn std::error::Error = &err;
+    assert_eq!(
+        serde_json::from_str::<UserId>("\"12\"").unwrap(),
+        UserId(12)
+    );
+    let err = serde_json::from_str::<UserId>("\"abc\"").unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("invalid UserId \"abc\": invalid digit found in string"));
+}
+
+#[test]
+fn test_borrowed_str_serde() {
+    use std::borrow::Cow;
+
+    #[derive(Debug, PartialEq, Eq, id::Serde)]
+    struct Name<'a>(&'a str);
+    let json = String::from("\"alice\"");
+    assert_eq!(serde_json::from_str::<Name>(&json).unwrap(), Name("alice"));
+
+    #[derive(Debug, PartialEq, Eq, id::Serde)]
+    struct CowName<'a>(Cow<'a, str>);
+    let name: CowName = serde_json::from_str("\"alice\"").unwrap();
+    assert!(matches!(name.0, Cow::Borrowed("alice")));
+    let name: CowName = serde_json::from_str(r#""al\"ice""#).unwrap();
+    assert!(matches!(name.0, Cow::Owned(ref s) if s == "al\"ice"));
+    let names: Vec<CowName> = serde_json::from_slice(b"[\"a\", \"b\"]").unwrap();
+    assert!(names.iter().all(|name| matches!(name.0, Cow::Borrowed(_))));
+    assert_eq!(serde_json::to_string(&names).unwrap(), "[\"a\",\"b\"]");
+}
+
+fn non_empty(inner: &str) -> Result<(), &'static str> {
+    if inner.is_empty() {
+        Err("empty identifier")
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_validated_deserialize() {
+    #[derive(Debug, id::OpaqueId)]
+    #[id(validate(non_empty, error = &'static str))]
+    struct Name(String);
+    assert_eq!(
+        serde_json::from_str::<Name>("\"alice\"").unwrap(),
+        Name(String::from("alice"))
+    );
+    let err = serde_json::from_str::<Name>("\"\"").unwrap_err();
+    assert!(err.to_string().starts_with("empty identifier"));
+
+    #[derive(Debug, PartialEq, Eq, id::Display, id::FromStr, id::Serde)]
+    #[id(serde = "human_readable", validate(non_zero, error = String))]
+    struct DocId(u32);
+    fn non_zero(inner: &u32) -> Result<(), String> {
+        match inner {
+            0 => Err(String::from("zero is reserved")),
+            _ => Ok(()),
+        }
+    }
+    assert_eq!(serde_json::from_str::<DocId>("\"7\"").unwrap(), DocId(7));
+    assert!(serde_json::from_str::<DocId>("\"0\"").is_err());
+    assert!(bincode::deserialize::<DocId>(&0_u32.to_le_bytes()).is_err());
+
+    #[derive(Debug, PartialEq, Eq, id::PathId)]
+    #[id(validate(non_zero, error = String))]
+    struct UserId(u32);
+    assert!(serde_json::from_str::<UserId>("\"0\"").is_err());
+    assert_eq!(serde_json::from_str::<UserId>("\"3\"").unwrap(), UserId(3));
+}
+
+#[test]
+fn test_enum_serde() {
+    #[derive(Debug, PartialEq, Eq, id::Serde)]
+    #[repr(i16)]
+    enum LevelId {
+        Low = -1,
+        Mid,
+        High = 10,
+    }
+    assert_eq!(serde_json::to_string(&LevelId::Low).unwrap(), "-1");
+    assert_eq!(serde_json::to_string(&LevelId::Mid).unwrap(), "0");
+    assert_eq!(
+        serde_json::to_string(&[LevelId::High, LevelId::Mid]).unwrap(),
+        "[10,0]"
+    );
+    assert_eq!(
+        serde_json::from_str::<LevelId>("10").unwrap(),
+        LevelId::High
+    );
+    assert_eq!(
+        serde_json::from_str::<LevelId>("3")
+            .unwrap_err()
+            .to_string(),
+        "unknown LevelId discriminant 3"
+    );
+    assert!(serde_json::from_str::<LevelId>("\"Low\"").is_err());
+    let bytes = bincode::serialize(&LevelId::Low).unwrap();
+    assert_eq!(bytes, (-1_i16).to_le_bytes());
+    assert_eq!(
+        bincode::deserialize::<LevelId>(&bytes).unwrap(),
+        LevelId::Low
+    );
+}
+
+#[test]
+fn test_packed_serde() {
+    use serde::{Deserialize, Serialize};
+
+    fn non_zero(inner: &u64) -> Result<(), &'static str> {
+        if *inner == 0 {
+            Err("zero document")
+        } else {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq, id::Serde)]
+    #[id(serde_packed, validate(non_zero, error = &'static str))]
+    struct DocId(u64);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Postings {
+        term: String,
+        #[serde(
+            serialize_with = "DocId::serialize_packed",
+            deserialize_with = "DocId::deserialize_packed"
+        )]
+        docs: Vec<DocId>,
+    }
+
+    let postings = Postings {
+        term: String::from("id"),
+        docs: (1..=1000).map(DocId).collect(),
+    };
+    let bytes = bincode::serialize(&postings).unwrap();
+    assert_eq!(bytes.len(), 8 + 2 + 8 + 1000 * 8);
+    assert_eq!(&bytes[18..26], &1_u64.to_le_bytes());
+    assert_eq!(bincode::deserialize::<Postings>(&bytes).unwrap(), postings);
+
+    let json = serde_json::to_string(&Postings {
+        term: String::from("id"),
+        docs: vec![DocId(258)],
+    })
+    .unwrap();
+    assert_eq!(json, r#"{"term":"id","docs":[2,1,0,0,0,0,0,0]}"#);
+    assert_eq!(
+        serde_json::from_str::<Postings>(&json).unwrap().docs,
+        [DocId(258)]
+    );
+    let err = serde_json::from_str::<Postings>(r#"{"term":"id","docs":[2,1,0]}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("packed DocId buffer of 3 bytes is not a multiple of 8"));
+    let err =
+        serde_json::from_str::<Postings>(r#"{"term":"id","docs":[0,0,0,0,0,0,0,0]}"#).unwrap_err();
+    assert!(err.to_string().starts_with("zero document"));
+
+    #[derive(Debug, PartialEq, id::Serde)]
+    #[id(serde_packed)]
+    struct ScoreId(f32);
+    let mut buffer = Vec::new();
+    ScoreId::serialize_packed(
+        &[ScoreId(0.5), ScoreId(-1.0)],
+        &mut serde_json::Serializer::new(&mut buffer),
+    )
+    .unwrap();
+    let scores =
+        ScoreId::deserialize_packed(&mut serde_json::Deserializer::from_slice(&buffer)).unwrap();
+    assert_eq!(scores, [ScoreId(0.5), ScoreId(-1.0)]);
+}
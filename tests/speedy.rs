@@ -0,0 +1,25 @@
+#![cfg(feature = "speedy")]
+
+use id_derive as id;
+use speedy::{Readable, Writable};
+
+#[test]
+fn test_speedy() {
+    #[derive(Debug, PartialEq, Eq, id::Speedy)]
+    struct SpeedyId(u32);
+    let bytes = SpeedyId(12).write_to_vec().unwrap();
+    assert_eq!(bytes, 12_u32.to_le_bytes());
+    assert_eq!(SpeedyId::read_from_buffer(&bytes).unwrap(), SpeedyId(12));
+}
+
+#[test]
+fn test_generic_speedy() {
+    #[derive(Debug, PartialEq, Eq, id::Speedy)]
+    struct GenericId<T>(T);
+    let bytes = GenericId(12_u16).write_to_vec().unwrap();
+    assert_eq!(bytes, 12_u16.to_le_bytes());
+    assert_eq!(
+        GenericId::<u16>::read_from_buffer(&bytes).unwrap(),
+        GenericId(12)
+    );
+}
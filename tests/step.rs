@@ -0,0 +1,16 @@
+#![cfg(feature = "step_trait")]
+#![feature(step_trait)]
+
+use id_derive as id;
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, id::Step)]
+struct StepId(u32);
+
+#[test]
+fn test_range_iteration() {
+    let ids: Vec<_> = (StepId(2)..StepId(5)).collect();
+    assert_eq!(ids, vec![StepId(2), StepId(3), StepId(4)]);
+    let ids: Vec<_> = (StepId(2)..=StepId(3)).rev().collect();
+    assert_eq!(ids, vec![StepId(3), StepId(2)]);
+    assert_eq!((StepId(0)..StepId(10)).count(), 10);
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "tantivy")]
+
+use id_derive as id;
+use tantivy::collector::TopDocs;
+use tantivy::query::AllQuery;
+use tantivy::schema::{OwnedValue, Schema, FAST, INDEXED};
+use tantivy::{doc, DocAddress, Index, IndexWriter, Order};
+
+#[derive(Debug, PartialEq, Clone, Copy, PartialOrd, id::Tantivy)]
+struct ProductId(u64);
+
+#[derive(Debug, PartialEq, Clone, Copy, PartialOrd, id::Tantivy)]
+struct Rank(i64);
+
+#[derive(Debug, PartialEq, id::Tantivy)]
+struct LocalDocId(u32);
+
+#[test]
+fn test_doc_ids() {
+    assert_eq!(LocalDocId::from_doc_id(3), LocalDocId(3));
+    assert_eq!(LocalDocId(3).doc_id(), 3);
+    let address = DocAddress::new(2, 9);
+    assert_eq!(ProductId::from_doc_address(address).doc_address(), address);
+    assert_eq!(
+        ProductId::from_doc_address(address),
+        ProductId((2 << 32) | 9)
+    );
+    assert_eq!(OwnedValue::from(Rank(-3)), OwnedValue::I64(-3));
+
+    #[derive(Debug, PartialEq, Clone, Copy, PartialOrd, id::Tantivy)]
+    struct ShardedId<const SHARD: u8>(u64);
+    let id = ShardedId::<1>::from_doc_address(address);
+    assert_eq!(id.doc_address(), address);
+    assert_eq!(OwnedValue::from(id), OwnedValue::U64((2 << 32) | 9));
+}
+
+#[test]
+fn test_fast_field() -> tantivy::Result<()> {
+    let mut schema = Schema::builder();
+    let product = schema.add_u64_field("product", FAST | INDEXED);
+    let rank = schema.add_i64_field("rank", FAST);
+    let index = Index::create_in_ram(schema.build());
+    let mut writer: IndexWriter = index.writer_with_num_threads(1, 15_000_000)?;
+    for (id, r) in [(10, -1), (30, 5), (20, 2)] {
+        writer.add_document(doc!(
+            product => OwnedValue::from(ProductId(id)),
+            rank => OwnedValue::from(Rank(r)),
+        ))?;
+    }
+    writer.commit()?;
+
+    let searcher = index.reader()?.searcher();
+    let by_product: Vec<(ProductId, DocAddress)> = searcher.search(
+        &AllQuery,
+        &TopDocs::with_limit(2).order_by_fast_field("product", Order::Asc),
+    )?;
+    let products: Vec<_> = by_product.iter().map(|(id, _)| *id).collect();
+    assert_eq!(products, [ProductId(10), ProductId(20)]);
+    let by_rank: Vec<(Rank, DocAddress)> = searcher.search(
+        &AllQuery,
+        &TopDocs::with_limit(1).order_by_fast_field("rank", Order::Desc),
+    )?;
+    assert_eq!(by_rank[0].0, Rank(5));
+    Ok(())
+}